@@ -1,4 +1,5 @@
 use criterion::{Criterion, criterion_group, criterion_main};
+use scripture_links_lib::text_processor::{ProcessorOptions, TextProcessor};
 use scripture_links_lib::{parse_scripture_reference, process_text_for_scripture_references};
 use std::hint::black_box;
 
@@ -16,6 +17,11 @@ fn benchmark_text_processing(c: &mut Criterion) {
     c.bench_function("process large text", |b| {
         b.iter(|| process_text_for_scripture_references(black_box(&large_text)));
     });
+
+    let processor = TextProcessor::new(ProcessorOptions::default());
+    c.bench_function("process large text with shared processor", |b| {
+        b.iter(|| processor.process(black_box(&large_text)));
+    });
 }
 
 criterion_group!(
@@ -122,6 +122,8 @@ def book_title_to_url_name(book_title: str, volume_title: str) -> str:
         "Abraham": "abr",
         "Joseph Smith—Matthew": "js-m",
         "Joseph Smith—History": "js-h",
+        "Joseph Smith--Matthew": "js-m",
+        "Joseph Smith--History": "js-h",
         "Articles of Faith": "a-of-f"
     }
     
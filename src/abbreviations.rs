@@ -3,8 +3,11 @@
 use crate::types::StandardWork;
 use std::collections::HashMap;
 
+/// The (`url_name`, `standard_work`) an abbreviation resolves to
+pub type BookMapping = (&'static str, StandardWork);
+
 /// Type alias for abbreviation mappings: abbreviation -> (`url_name`, `standard_work`)
-pub type AbbreviationMap = HashMap<&'static str, (&'static str, StandardWork)>;
+pub type AbbreviationMap = HashMap<&'static str, BookMapping>;
 
 /// Create a comprehensive mapping of scripture abbreviations to their URL names and standard works
 #[must_use]
@@ -228,106 +231,130 @@ pub fn create_abbreviation_map() -> AbbreviationMap {
     map
 }
 
+/// Scripture books as (slug, display name), in canonical order
+pub const BOOK_DISPLAY_NAMES: &[(&str, &str)] = &[
+    // Old Testament
+    ("gen", "Genesis"),
+    ("ex", "Exodus"),
+    ("lev", "Leviticus"),
+    ("num", "Numbers"),
+    ("deut", "Deuteronomy"),
+    ("josh", "Joshua"),
+    ("judg", "Judges"),
+    ("ruth", "Ruth"),
+    ("1-sam", "1 Samuel"),
+    ("2-sam", "2 Samuel"),
+    ("1-kgs", "1 Kings"),
+    ("2-kgs", "2 Kings"),
+    ("1-chr", "1 Chronicles"),
+    ("2-chr", "2 Chronicles"),
+    ("ezra", "Ezra"),
+    ("neh", "Nehemiah"),
+    ("esth", "Esther"),
+    ("job", "Job"),
+    ("ps", "Psalms"),
+    ("prov", "Proverbs"),
+    ("eccl", "Ecclesiastes"),
+    ("song", "Song of Solomon"),
+    ("isa", "Isaiah"),
+    ("jer", "Jeremiah"),
+    ("lam", "Lamentations"),
+    ("ezek", "Ezekiel"),
+    ("dan", "Daniel"),
+    ("hosea", "Hosea"),
+    ("joel", "Joel"),
+    ("amos", "Amos"),
+    ("obad", "Obadiah"),
+    ("jonah", "Jonah"),
+    ("micah", "Micah"),
+    ("nahum", "Nahum"),
+    ("hab", "Habakkuk"),
+    ("zeph", "Zephaniah"),
+    ("hag", "Haggai"),
+    ("zech", "Zechariah"),
+    ("mal", "Malachi"),
+    // New Testament
+    ("matt", "Matthew"),
+    ("mark", "Mark"),
+    ("luke", "Luke"),
+    ("john", "John"),
+    ("acts", "Acts"),
+    ("rom", "Romans"),
+    ("1-cor", "1 Corinthians"),
+    ("2-cor", "2 Corinthians"),
+    ("gal", "Galatians"),
+    ("eph", "Ephesians"),
+    ("philip", "Philippians"),
+    ("col", "Colossians"),
+    ("1-thes", "1 Thessalonians"),
+    ("2-thes", "2 Thessalonians"),
+    ("1-tim", "1 Timothy"),
+    ("2-tim", "2 Timothy"),
+    ("titus", "Titus"),
+    ("philem", "Philemon"),
+    ("heb", "Hebrews"),
+    ("james", "James"),
+    ("1-pet", "1 Peter"),
+    ("2-pet", "2 Peter"),
+    ("1-jn", "1 John"),
+    ("2-jn", "2 John"),
+    ("3-jn", "3 John"),
+    ("jude", "Jude"),
+    ("rev", "Revelation"),
+    // Book of Mormon
+    ("1-ne", "1 Nephi"),
+    ("2-ne", "2 Nephi"),
+    ("jacob", "Jacob"),
+    ("enos", "Enos"),
+    ("jarom", "Jarom"),
+    ("omni", "Omni"),
+    ("w-of-m", "Words of Mormon"),
+    ("mosiah", "Mosiah"),
+    ("alma", "Alma"),
+    ("hel", "Helaman"),
+    ("3-ne", "3 Nephi"),
+    ("4-ne", "4 Nephi"),
+    ("morm", "Mormon"),
+    ("ether", "Ether"),
+    ("moro", "Moroni"),
+    // Doctrine and Covenants
+    ("dc", "D&C"),
+    ("od", "Official Declaration 1"),
+    // Pearl of Great Price
+    ("moses", "Moses"),
+    ("abr", "Abraham"),
+    ("js-m", "Joseph Smith—Matthew"),
+    ("js-h", "Joseph Smith—History"),
+    ("a-of-f", "Articles of Faith"),
+];
+
+/// Full names of the Study Helps, by slug
+pub const STUDY_HELP_NAMES: &[(&str, &str)] = &[
+    ("jst", "Joseph Smith Translation"),
+    ("tg", "Topical Guide"),
+    ("bd", "Bible Dictionary"),
+    ("it", "Index to the Triple Combination"),
+    ("gs", "Guide to the Scriptures"),
+    ("hc", "History of the Church"),
+];
+
+/// Full name of a Study Helps slug, such as "Topical Guide" for "tg"
+#[must_use]
+pub fn study_help_name(slug: &str) -> Option<&'static str> {
+    STUDY_HELP_NAMES
+        .iter()
+        .find(|(help, _)| *help == slug)
+        .map(|(_, name)| *name)
+}
+
 /// Canonical display name for a book slug (for wikilink targets).
 /// Returns full book names for consistent Obsidian note matching.
 #[must_use]
 pub fn book_slug_to_display_name(slug: &str) -> Option<&'static str> {
-    Some(match slug {
-        // Old Testament
-        "gen" => "Genesis",
-        "ex" => "Exodus",
-        "lev" => "Leviticus",
-        "num" => "Numbers",
-        "deut" => "Deuteronomy",
-        "josh" => "Joshua",
-        "judg" => "Judges",
-        "ruth" => "Ruth",
-        "1-sam" => "1 Samuel",
-        "2-sam" => "2 Samuel",
-        "1-kgs" => "1 Kings",
-        "2-kgs" => "2 Kings",
-        "1-chr" => "1 Chronicles",
-        "2-chr" => "2 Chronicles",
-        "ezra" => "Ezra",
-        "neh" => "Nehemiah",
-        "esth" => "Esther",
-        "job" => "Job",
-        "ps" => "Psalms",
-        "prov" => "Proverbs",
-        "eccl" => "Ecclesiastes",
-        "song" => "Song of Solomon",
-        "isa" => "Isaiah",
-        "jer" => "Jeremiah",
-        "lam" => "Lamentations",
-        "ezek" => "Ezekiel",
-        "dan" => "Daniel",
-        "hosea" => "Hosea",
-        "joel" => "Joel",
-        "amos" => "Amos",
-        "obad" => "Obadiah",
-        "jonah" => "Jonah",
-        "micah" => "Micah",
-        "nahum" => "Nahum",
-        "hab" => "Habakkuk",
-        "zeph" => "Zephaniah",
-        "hag" => "Haggai",
-        "zech" => "Zechariah",
-        "mal" => "Malachi",
-        // New Testament
-        "matt" => "Matthew",
-        "mark" => "Mark",
-        "luke" => "Luke",
-        "john" => "John",
-        "acts" => "Acts",
-        "rom" => "Romans",
-        "1-cor" => "1 Corinthians",
-        "2-cor" => "2 Corinthians",
-        "gal" => "Galatians",
-        "eph" => "Ephesians",
-        "philip" => "Philippians",
-        "col" => "Colossians",
-        "1-thes" => "1 Thessalonians",
-        "2-thes" => "2 Thessalonians",
-        "1-tim" => "1 Timothy",
-        "2-tim" => "2 Timothy",
-        "titus" => "Titus",
-        "philem" => "Philemon",
-        "heb" => "Hebrews",
-        "james" => "James",
-        "1-pet" => "1 Peter",
-        "2-pet" => "2 Peter",
-        "1-jn" => "1 John",
-        "2-jn" => "2 John",
-        "3-jn" => "3 John",
-        "jude" => "Jude",
-        "rev" => "Revelation",
-        // Book of Mormon
-        "1-ne" => "1 Nephi",
-        "2-ne" => "2 Nephi",
-        "jacob" => "Jacob",
-        "enos" => "Enos",
-        "jarom" => "Jarom",
-        "omni" => "Omni",
-        "w-of-m" => "Words of Mormon",
-        "mosiah" => "Mosiah",
-        "alma" => "Alma",
-        "hel" => "Helaman",
-        "3-ne" => "3 Nephi",
-        "4-ne" => "4 Nephi",
-        "morm" => "Mormon",
-        "ether" => "Ether",
-        "moro" => "Moroni",
-        // Doctrine and Covenants
-        "dc" => "D&C",
-        "od" => "Official Declaration 1",
-        // Pearl of Great Price
-        "moses" => "Moses",
-        "abr" => "Abraham",
-        "js-m" => "Joseph Smith—Matthew",
-        "js-h" => "Joseph Smith—History",
-        "a-of-f" => "Articles of Faith",
-        _ => return None,
-    })
+    BOOK_DISPLAY_NAMES
+        .iter()
+        .find(|(book, _)| *book == slug)
+        .map(|(_, name)| *name)
 }
 
 #[cfg(test)]
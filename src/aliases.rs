@@ -0,0 +1,204 @@
+//! User-defined book aliases, such as "Mos." for Mosiah or "D and C" for the Doctrine and Covenants
+
+use crate::abbreviations::{BookMapping, create_abbreviation_map};
+use crate::language::Language;
+use crate::localized_names::{check_book_name_language, lookup_localized_book};
+use crate::types::StandardWork;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// A user-defined name for a book
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BookAlias {
+    /// The alias as written in the configuration
+    pub alias: String,
+    /// Book name used in URLs (e.g., "mosiah")
+    pub book: &'static str,
+    pub standard_work: StandardWork,
+}
+
+/// Aliases mapped to known books, looked up before the built-in abbreviations
+///
+/// Lookups ignore case and a trailing period, like the built-in
+/// abbreviations, so an alias can override a built-in one.
+///
+/// Book names in other languages can also be enabled; they are looked up
+/// after the built-in abbreviations, see [`crate::localized_names`].
+///
+/// # Examples
+///
+/// ```
+/// use scripture_links_lib::Language;
+/// use scripture_links_lib::aliases::BookAliases;
+///
+/// let aliases = BookAliases::new([("Mos.", "mosiah"), ("D and C", "dc")]).unwrap();
+/// assert_eq!(aliases.get("mos").unwrap().0, "mosiah");
+/// assert!(BookAliases::new([("Mos.", "mosaic")]).is_err());
+///
+/// let mut aliases = BookAliases::default();
+/// aliases.enable_language(Language::SPANISH).unwrap();
+/// assert_eq!(aliases.get_localized("Génesis").unwrap().0.0, "gen");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "BTreeMap<String, String>")]
+pub struct BookAliases {
+    aliases: BTreeMap<String, BookAlias>,
+    languages: Vec<Language>,
+}
+
+impl BookAliases {
+    /// Build the alias table from alias/book slug pairs
+    ///
+    /// # Errors
+    /// Returns an error if an alias is empty or contains a colon, or if a slug
+    /// is not a known book of scripture.
+    pub fn new<I, A, B>(aliases: I) -> Result<Self, String>
+    where
+        I: IntoIterator<Item = (A, B)>,
+        A: AsRef<str>,
+        B: AsRef<str>,
+    {
+        let abbreviations = create_abbreviation_map();
+        let mut table = BTreeMap::new();
+        for (alias, slug) in aliases {
+            let (alias, slug) = (alias.as_ref().trim(), slug.as_ref().trim());
+            if normalize(alias).is_empty() || alias.contains(':') {
+                return Err(format!("Invalid book alias '{alias}'"));
+            }
+            let (book, standard_work) = abbreviations
+                .values()
+                .find(|(book, work)| *book == slug && !work.is_study_help())
+                .ok_or_else(|| format!("Alias '{alias}' maps to unknown book '{slug}'"))?;
+            table.insert(
+                normalize(alias),
+                BookAlias {
+                    alias: alias.to_string(),
+                    book,
+                    standard_work: standard_work.clone(),
+                },
+            );
+        }
+        Ok(Self {
+            aliases: table,
+            languages: Vec::new(),
+        })
+    }
+
+    /// The book an alias refers to
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<BookMapping> {
+        self.aliases
+            .get(&normalize(name))
+            .map(|alias| (alias.book, alias.standard_work.clone()))
+    }
+
+    /// Also recognize the book names of `language`
+    ///
+    /// # Errors
+    /// Returns an error if there are no book names in `language`.
+    pub fn enable_language(&mut self, language: Language) -> Result<(), String> {
+        check_book_name_language(language)?;
+        if !self.languages.contains(&language) {
+            self.languages.push(language);
+        }
+        Ok(())
+    }
+
+    /// Languages whose book names are recognized, in lookup order
+    #[must_use]
+    pub fn languages(&self) -> &[Language] {
+        &self.languages
+    }
+
+    /// The book a name in one of the enabled languages refers to, and that language
+    #[must_use]
+    pub fn get_localized(&self, name: &str) -> Option<(BookMapping, Language)> {
+        lookup_localized_book(name, &self.languages)
+    }
+
+    /// Add the aliases and languages of `other`, replacing existing aliases with the same name
+    pub fn extend(&mut self, other: Self) {
+        self.aliases.extend(other.aliases);
+        for language in other.languages {
+            if !self.languages.contains(&language) {
+                self.languages.push(language);
+            }
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &BookAlias> {
+        self.aliases.values()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.aliases.is_empty()
+    }
+}
+
+impl TryFrom<BTreeMap<String, String>> for BookAliases {
+    type Error = String;
+
+    fn try_from(aliases: BTreeMap<String, String>) -> Result<Self, String> {
+        Self::new(aliases)
+    }
+}
+
+fn normalize(name: &str) -> String {
+    name.trim().trim_end_matches('.').to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup_ignores_case_and_trailing_period() {
+        let aliases = BookAliases::new([("JSH", "js-h"), ("Mos.", "mosiah")]).unwrap();
+        assert_eq!(
+            aliases.get("jsh"),
+            Some(("js-h", StandardWork::PearlOfGreatPrice))
+        );
+        assert_eq!(aliases.get("Mos").unwrap().0, "mosiah");
+        assert_eq!(aliases.get("MOS.").unwrap().0, "mosiah");
+        assert_eq!(aliases.get("Moses"), None);
+    }
+
+    #[test]
+    fn test_rejects_unknown_books_and_bad_aliases() {
+        assert_eq!(
+            BookAliases::new([("Ne", "nephi")]).unwrap_err(),
+            "Alias 'Ne' maps to unknown book 'nephi'"
+        );
+        // Study Helps need a topic and cannot be aliased like books
+        assert!(BookAliases::new([("Topical", "tg")]).is_err());
+        assert!(BookAliases::new([(" . ", "alma")]).is_err());
+        assert!(BookAliases::new([("Al:", "alma")]).is_err());
+    }
+
+    #[test]
+    fn test_extend_replaces_same_alias() {
+        let mut aliases = BookAliases::new([("Ne", "1-ne"), ("JSH", "js-h")]).unwrap();
+        aliases.extend(BookAliases::new([("ne.", "2-ne")]).unwrap());
+        assert_eq!(aliases.get("Ne").unwrap().0, "2-ne");
+        assert_eq!(aliases.iter().count(), 2);
+    }
+
+    #[test]
+    fn test_enabled_languages() {
+        let mut aliases = BookAliases::default();
+        assert!(aliases.get_localized("Juan").is_none());
+        aliases.enable_language(Language::SPANISH).unwrap();
+        assert_eq!(
+            aliases.get_localized("juan"),
+            Some((("john", StandardWork::NewTestament), Language::SPANISH))
+        );
+        assert!(aliases.enable_language(Language::ENGLISH).is_err());
+
+        let mut other = BookAliases::default();
+        other.enable_language(Language::GERMAN).unwrap();
+        other.enable_language(Language::SPANISH).unwrap();
+        aliases.extend(other);
+        assert_eq!(aliases.languages(), [Language::SPANISH, Language::GERMAN]);
+    }
+}
@@ -0,0 +1,191 @@
+//! Catalog of the supported books, their accepted names and verse counts
+
+use crate::abbreviations::{BOOK_DISPLAY_NAMES, create_abbreviation_map};
+use crate::scripture_data::get_book_info;
+use crate::types::StandardWork;
+use serde::Serialize;
+
+/// Reference styles accepted by the parser, for documentation and UIs
+const ACCEPTED_FORMATS: &[&str] = &[
+    "Official abbreviations (e.g., 'Gen.', 'Matt.', '1 Ne.')",
+    "Full book names (e.g., 'Genesis', 'Matthew', '1 Nephi')",
+    "Compact abbreviations (e.g., 'Gen.', 'Matt.')",
+    "Case insensitive",
+    "Optional spacing between book and chapter",
+];
+
+const EXAMPLES: &[&str] = &[
+    "Genesis 1:1",
+    "Matt. 5:3-4",
+    "2 Ne. 10:14-15",
+    "D&C 128:22-23",
+    "Moses 1:39",
+];
+
+/// A supported book of scripture
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BookEntry {
+    /// Book name used in URLs (e.g., "1-ne")
+    pub slug: &'static str,
+    pub name: &'static str,
+    pub standard_work: StandardWork,
+    /// Every abbreviation and full name the parser accepts for the book
+    pub aliases: Vec<&'static str>,
+    /// Number of chapters, or `None` for books without chapter and verse data
+    /// (the Official Declarations), which are linked but not validated
+    pub chapters: Option<usize>,
+    /// Number of verses, or `None` for books without chapter and verse data
+    pub verses: Option<u32>,
+    /// Number of verses in each chapter, starting with chapter 1; empty for
+    /// books without chapter and verse data
+    pub verse_counts: Vec<u32>,
+}
+
+/// Description of what the parser accepts, as returned to WASM callers
+#[derive(Debug, Clone, Serialize)]
+pub struct SupportedFormats {
+    pub supported_works: Vec<&'static str>,
+    pub formats: &'static [&'static str],
+    pub examples: &'static [&'static str],
+    pub books: Vec<BookEntry>,
+}
+
+/// Every supported book in canonical order
+///
+/// Aliases come from the abbreviation table and chapter and verse counts from
+/// the scripture data, so the catalog cannot drift from what the parser accepts.
+/// Books without verse data report no chapter or verse counts.
+///
+/// # Examples
+///
+/// ```
+/// use scripture_links_lib::books::book_catalog;
+///
+/// let books = book_catalog();
+/// let alma = books.iter().find(|book| book.slug == "alma").unwrap();
+/// assert_eq!(alma.chapters, Some(63));
+/// assert!(alma.aliases.contains(&"Alma"));
+/// ```
+#[must_use]
+pub fn book_catalog() -> Vec<BookEntry> {
+    let abbreviations = create_abbreviation_map();
+
+    BOOK_DISPLAY_NAMES
+        .iter()
+        .filter_map(|&(slug, name)| {
+            let mut aliases = Vec::new();
+            let mut standard_work = None;
+            for (alias, (book, work)) in &abbreviations {
+                if *book == slug {
+                    aliases.push(*alias);
+                    standard_work = Some(work.clone());
+                }
+            }
+            aliases.sort_unstable();
+
+            let info = get_book_info(slug);
+            Some(BookEntry {
+                slug,
+                name,
+                standard_work: standard_work?,
+                aliases,
+                chapters: info.map(|info| info.chapters.len()),
+                verses: info.map(|info| info.chapters.iter().sum()),
+                verse_counts: info.map_or_else(Vec::new, |info| info.chapters.clone()),
+            })
+        })
+        .collect()
+}
+
+/// The supported standard works, books and reference styles
+#[must_use]
+pub fn supported_formats() -> SupportedFormats {
+    let books = book_catalog();
+    let mut supported_works: Vec<&'static str> = Vec::new();
+    for book in &books {
+        let work = book.standard_work.display_name();
+        if !supported_works.contains(&work) {
+            supported_works.push(work);
+        }
+    }
+
+    SupportedFormats {
+        supported_works,
+        formats: ACCEPTED_FORMATS,
+        examples: EXAMPLES,
+        books,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_scripture_reference;
+
+    #[test]
+    fn test_catalog_is_in_canonical_order() {
+        let books = book_catalog();
+        assert_eq!(books.first().unwrap().slug, "gen");
+        assert_eq!(books.last().unwrap().slug, "a-of-f");
+        assert_eq!(books.len(), BOOK_DISPLAY_NAMES.len());
+    }
+
+    #[test]
+    fn test_counts_come_from_scripture_data() {
+        let books = book_catalog();
+        let genesis = books.iter().find(|book| book.slug == "gen").unwrap();
+        assert_eq!(genesis.chapters, Some(50));
+        assert_eq!(genesis.verse_counts[0], 31);
+        assert_eq!(genesis.verses, Some(1533));
+
+        let history = books.iter().find(|book| book.slug == "js-h").unwrap();
+        assert_eq!(history.standard_work, StandardWork::PearlOfGreatPrice);
+        assert_eq!(history.chapters, Some(1));
+        assert_eq!(history.verses, Some(75));
+    }
+
+    #[test]
+    fn test_books_without_verse_data_have_no_counts() {
+        let books = book_catalog();
+        let declarations = books.iter().find(|book| book.slug == "od").unwrap();
+        assert_eq!(
+            declarations.standard_work,
+            StandardWork::DoctrineAndCovenants
+        );
+        assert_eq!(declarations.chapters, None);
+        assert_eq!(declarations.verses, None);
+        assert!(declarations.verse_counts.is_empty());
+        // Still linked, only without validation
+        assert!(parse_scripture_reference("OD 1:1").is_ok());
+    }
+
+    #[test]
+    fn test_every_alias_parses_to_its_book() {
+        for book in book_catalog() {
+            if book.chapters.is_none() {
+                continue;
+            }
+            for alias in &book.aliases {
+                let parsed = parse_scripture_reference(&format!("{alias} 1:1"))
+                    .unwrap_or_else(|e| panic!("alias '{alias}' failed: {e}"));
+                assert_eq!(parsed.book, book.slug, "alias '{alias}'");
+            }
+        }
+    }
+
+    #[test]
+    fn test_supported_formats_lists_works_once() {
+        let formats = supported_formats();
+        assert_eq!(
+            formats.supported_works,
+            vec![
+                "Old Testament",
+                "New Testament",
+                "Book of Mormon",
+                "Doctrine and Covenants",
+                "Pearl of Great Price",
+            ]
+        );
+        assert_eq!(formats.books.len(), book_catalog().len());
+    }
+}
@@ -1,24 +1,55 @@
 //! Command-line interface handling
 
-use crate::abbreviations::book_slug_to_display_name;
+use crate::books::book_catalog;
+use crate::config::Config;
+use crate::diff::{diff_hunks, render_unified_diff};
+use crate::files::collect_files;
+use crate::html::HtmlAttributes;
+use crate::info::{ChapterLocation, ReferenceInfo, reference_info_with_provider};
 use crate::json_output::{
-    BatchResponse, ErrorCategory, ErrorInfo, SingleReferenceResponse, TextProcessingResponse,
-    ValidationResponse, create_error_response,
+    BatchResponse, CheckResponse, DiffResponse, ErrorCategory, ErrorInfo, ExtractResponse,
+    FileResult, FoundReference, InfoResponse, MultiFileResponse, SingleReferenceResponse,
+    TextPosition, TextProcessingResponse, ValidationResponse, VaultResponse,
+    create_error_response_in,
 };
-use crate::types::OutputFormat;
-use crate::{generate_url, parse_scripture_reference, process_text_with_format};
-use clap::Parser;
+use crate::language::Language;
+use crate::localized_names::parse_display_language;
+use crate::messages::{ReferenceError, language_from_env, parse_message_language};
+use crate::parallel::{default_jobs, map_ordered};
+use crate::parse_scripture_reference_with_works;
+use crate::parser::reference_language;
+use crate::text_processor::{ProcessedText, ProcessorOptions, ReferenceMatch, TextProcessor};
+use crate::types::{OutputFormat, ScriptureReference};
+use crate::url_provider::UrlProviders;
+use crate::vault::{VaultLayout, VaultOptions, VerseAnchors, write_vault};
+use crate::watch::{ChangeFilter, DEFAULT_DEBOUNCE, watch};
+use crate::wikilink::{StudyHelpsTemplate, WikilinkTemplate, parse_path};
+use clap::error::ErrorKind;
+use clap::{ArgGroup, Args, CommandFactory, Parser, Subcommand};
+use std::cell::RefCell;
+use std::collections::VecDeque;
 use std::fs;
-use std::io::Write;
-use std::path::Path;
+use std::io::{BufRead, BufReader, BufWriter, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
 
 /// Custom error type for CLI operations
 pub type CliError = Box<dyn std::error::Error>;
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq, clap::ValueEnum)]
+/// Exit status used by `--check` when a file contains unlinked references
+pub const CHECK_FAILED_EXIT_CODE: i32 = 3;
+
+/// Value of `--text`, `--file` or `--batch` that reads standard input instead
+pub const STDIN_ARG: &str = "-";
+
+/// Name shown for standard input in messages and diffs
+const STDIN_NAME: &str = "<stdin>";
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, clap::ValueEnum)]
 pub enum FormatArg {
+    #[default]
     Markdown,
     Wikilink,
+    Html,
 }
 
 impl From<FormatArg> for OutputFormat {
@@ -26,6 +57,41 @@ impl From<FormatArg> for OutputFormat {
         match f {
             FormatArg::Markdown => Self::Markdown,
             FormatArg::Wikilink => Self::Wikilink,
+            FormatArg::Html => Self::Html,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, clap::ValueEnum)]
+pub enum LayoutArg {
+    Flat,
+    #[default]
+    Work,
+    Book,
+}
+
+impl From<LayoutArg> for VaultLayout {
+    fn from(layout: LayoutArg) -> Self {
+        match layout {
+            LayoutArg::Flat => Self::Flat,
+            LayoutArg::Work => Self::Work,
+            LayoutArg::Book => Self::Book,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, clap::ValueEnum)]
+pub enum AnchorsArg {
+    #[default]
+    Heading,
+    Block,
+}
+
+impl From<AnchorsArg> for VerseAnchors {
+    fn from(anchors: AnchorsArg) -> Self {
+        match anchors {
+            AnchorsArg::Heading => Self::Heading,
+            AnchorsArg::Block => Self::Block,
         }
     }
 }
@@ -35,73 +101,657 @@ impl From<FormatArg> for OutputFormat {
 #[command(name = "scripture-links")]
 #[command(about = "Generate links to scriptures on ChurchofJesusChrist.org")]
 #[command(version)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct Cli {
-    /// Scripture reference (e.g., "Isa. 6:5", "2 Ne. 10:14-15")
-    #[arg(short, long, group = "input")]
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Output in JSON format (machine-readable)
+    #[arg(long, global = true)]
+    pub json: bool,
+
+    /// Read settings from this file instead of discovering `.scripture-links.toml`
+    /// and the user config
+    #[arg(long, global = true, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// Ignore config files and use the built-in defaults
+    #[arg(long, global = true, conflicts_with = "config")]
+    pub no_config: bool,
+
+    /// Site to link to: church (the default), biblegateway, blueletterbible or a URL
+    /// template like `https://example.org/{work}/{book}/{chapter}#{verse}`; prefix with
+    /// ot=, nt=, bible=, bofm=, dc= or pgp= to choose it for one standard work (repeatable)
+    #[arg(long, global = true, value_name = "[WORK=]PROVIDER", value_parser = UrlProviders::parse_arg)]
+    pub url_provider: Vec<UrlProviders>,
+
+    /// Language of the linked pages, as a three-letter ISO 639-3 code (e.g., eng, spa, por)
+    #[arg(long, global = true, value_name = "CODE", value_parser = Language::new)]
+    pub lang: Option<Language>,
+
+    /// Link every reference in the --lang language, even those written with book
+    /// names of another language
+    #[arg(long, global = true)]
+    pub force_lang: bool,
+
+    /// Language of book names in wikilinks and canonical link text: eng (the default),
+    /// spa, por, fra or deu
+    #[arg(long, global = true, value_name = "CODE", value_parser = parse_display_language)]
+    pub display_lang: Option<Language>,
+
+    /// Language of error messages: eng, spa, por, fra or deu (default: from LANG,
+    /// or English in JSON output)
+    #[arg(long, global = true, value_name = "CODE", value_parser = parse_message_language)]
+    pub message_lang: Option<Language>,
+
+    /// How wikilinks are written: plain `[[Book Chapter]]:Verse` (the default), alias
+    /// `[[Book Chapter|Ref]]`, heading `[[Book Chapter#Verse|Ref]]`, block
+    /// `[[Book Chapter#^Verse|Ref]]` or a template like `[[{book_name} {chapter}|{text}]]`
+    #[arg(long, global = true, value_name = "STYLE", value_parser = WikilinkTemplate::new)]
+    pub wikilink: Option<WikilinkTemplate>,
+
+    /// Folder of the linked notes, such as `Scriptures/{work_name}/`; may use `{work}`,
+    /// `{work_name}`, `{book}` and `{book_name}`
+    #[arg(long, global = true, value_name = "PATH", value_parser = parse_path)]
+    pub wikilink_path: Option<String>,
+
+    /// How Study Helps topics are written as wikilinks: folder
+    /// `[[Topical Guide/Faith|TG Faith]]` (the default), name
+    /// `[[Topical Guide Faith|TG Faith]]`, abbreviation `[[TG Faith]]` or a template
+    /// using `{help}`, `{abbreviation}`, `{help_name}`, `{topic}`, `{topic_slug}` and `{text}`
+    #[arg(long, global = true, value_name = "STYLE", value_parser = StudyHelpsTemplate::new)]
+    pub study_helps_wikilink: Option<StudyHelpsTemplate>,
+
+    /// Class of HTML links (default: scripture-ref); empty for none
+    #[arg(long, global = true, value_name = "CLASS")]
+    pub html_class: Option<String>,
+
+    /// Attribute of HTML links, added to or replacing data-book, data-chapter,
+    /// data-verses and title; the value may use placeholders such as `{book}`,
+    /// `{verses}`, `{reference}` and `{url}`, and an empty value removes the attribute
+    /// (repeatable)
+    #[arg(long, global = true, value_name = "NAME=VALUE", value_parser = HtmlAttributes::parse_arg)]
+    pub html_attribute: Vec<HtmlAttributes>,
+
+    /// Also recognize book names in this language: spa, por, fra or deu (repeatable)
+    #[arg(long, global = true, value_name = "CODE", value_parser = Language::new)]
+    pub book_names: Vec<Language>,
+
+    /// Scripture reference (e.g., "Isa. 6:5", "2 Ne. 10:14-15") [deprecated: use `parse`]
+    #[arg(short, long, group = "input", help_heading = DEPRECATED_HEADING)]
     pub reference: Option<String>,
 
-    /// Process text and convert scripture references to markdown links
-    #[arg(short, long, group = "input")]
+    /// Process text and convert scripture references to markdown links ("-" reads stdin)
+    /// [deprecated: use `link --text`]
+    #[arg(short, long, group = "input", help_heading = DEPRECATED_HEADING)]
     pub text: Option<String>,
 
-    /// Process file and convert scripture references to markdown links
-    #[arg(short, long, group = "input")]
-    pub file: Option<String>,
+    /// Process files and convert scripture references to markdown links ("-" reads stdin)
+    /// [deprecated: use `link`]
+    #[arg(short, long, group = "input", num_args = 1.., help_heading = DEPRECATED_HEADING)]
+    pub file: Vec<String>,
 
-    /// Write output back to the file (only with --file); only writes if content changed
-    #[arg(short, long)]
+    /// Watch files or directories and link references in place whenever a file is saved
+    /// [deprecated: use `link --watch`]
+    #[arg(short, long, group = "input", num_args = 1.., help_heading = DEPRECATED_HEADING)]
+    pub watch: Vec<String>,
+
+    /// Only process files matching this glob when walking directories
+    #[arg(long, help_heading = DEPRECATED_HEADING)]
+    pub include: Vec<String>,
+
+    /// Skip files matching this glob when walking directories
+    #[arg(long, help_heading = DEPRECATED_HEADING)]
+    pub exclude: Vec<String>,
+
+    /// Number of files to process in parallel when processing multiple files
+    #[arg(short, long, help_heading = DEPRECATED_HEADING)]
+    pub jobs: Option<usize>,
+
+    /// Write output back to the file (only with --file) [deprecated: use `link --in-place`]
+    #[arg(short, long, help_heading = DEPRECATED_HEADING)]
     pub in_place: bool,
 
-    /// Link format: markdown [text](url) or wikilink [[Book Chapter]]:Verse for Obsidian
-    #[arg(long, value_enum, default_value = "markdown")]
-    pub format: FormatArg,
+    /// Check whether the file contains unlinked references (only with --file)
+    /// [deprecated: use `lint`]
+    #[arg(long, conflicts_with = "in_place", help_heading = DEPRECATED_HEADING)]
+    pub check: bool,
 
-    /// Output in JSON format (machine-readable)
-    #[arg(long)]
-    pub json: bool,
+    /// Show a unified diff of the changes (only with --file) [deprecated: use `link --diff`]
+    #[arg(long, help_heading = DEPRECATED_HEADING)]
+    pub diff: bool,
 
-    /// Validate references without generating URLs
-    #[arg(long)]
+    /// Link format: markdown [text](url) (the default), wikilink [[Book Chapter]]:Verse
+    /// for Obsidian or html <a href="url">text</a>
+    #[arg(long, value_enum, help_heading = DEPRECATED_HEADING)]
+    pub format: Option<FormatArg>,
+
+    /// Validate references without generating URLs [deprecated: use `validate`]
+    #[arg(long, help_heading = DEPRECATED_HEADING)]
     pub validate_only: bool,
 
-    /// Process multiple references (comma-separated)
-    #[arg(long, group = "input")]
+    /// Process multiple references (comma-separated; "-" reads one or more per line from stdin)
+    /// [deprecated: use `parse`]
+    #[arg(long, group = "input", help_heading = DEPRECATED_HEADING)]
     pub batch: Option<String>,
 }
 
+const DEPRECATED_HEADING: &str = "Deprecated options";
+
+/// Subcommands of the command-line interface
+#[derive(Subcommand)]
+pub enum Command {
+    /// Convert scripture references in text or files to links
+    Link(LinkArgs),
+    /// Report files that contain unlinked scripture references (exits with status 3)
+    Lint(LintArgs),
+    /// Convert scripture references to URLs
+    Parse(ParseArgs),
+    /// Check scripture references without generating URLs
+    Validate(ValidateArgs),
+    /// List the scripture references found in text or a file
+    Extract(ExtractArgs),
+    /// Show verse counts, neighboring chapters and the chapter URL for a reference
+    Info(InfoArgs),
+    /// List the supported books with their accepted abbreviations and chapter and verse counts
+    Books,
+    /// Create a note for every chapter, so wikilinks to chapters have a target
+    Vault(VaultArgs),
+}
+
+/// Arguments of the `link` command
+#[derive(Args)]
+#[command(group(ArgGroup::new("source").required(true).args(["paths", "text"])))]
+#[allow(clippy::struct_excessive_bools)]
+pub struct LinkArgs {
+    /// Files or directories to process ("-" reads stdin); directories are processed
+    /// recursively honoring .gitignore-style ignore files
+    pub paths: Vec<String>,
+
+    /// Process this text instead of files ("-" reads stdin)
+    #[arg(short, long, conflicts_with = "paths")]
+    pub text: Option<String>,
+
+    /// Write output back to the files; only writes files whose content changed
+    #[arg(short, long, conflicts_with = "text")]
+    pub in_place: bool,
+
+    /// Show a unified diff of the changes instead of the processed text;
+    /// combine with --in-place to also write them
+    #[arg(long, conflicts_with = "text")]
+    pub diff: bool,
+
+    /// Keep running and link references in place whenever a file is saved
+    #[arg(short, long, conflicts_with_all = ["text", "in_place", "diff"])]
+    pub watch: bool,
+
+    #[command(flatten)]
+    pub walk: WalkArgs,
+
+    /// Link format: markdown [text](url) (the default), wikilink [[Book Chapter]]:Verse
+    /// for Obsidian or html <a href="url">text</a>
+    #[arg(long, value_enum)]
+    pub format: Option<FormatArg>,
+
+    /// Also link Study Helps references such as "TG Faith"
+    #[arg(long, overrides_with = "no_study_helps")]
+    pub study_helps: bool,
+
+    /// Do not link Study Helps references, even if the config enables them
+    #[arg(long, overrides_with = "study_helps")]
+    pub no_study_helps: bool,
+
+    /// Rewrite each book name as written into its full name in --display-lang
+    /// (e.g., "Gen. 1:1" becomes "Genesis 1:1")
+    #[arg(long)]
+    pub canonicalize: bool,
+
+    /// Also rewrite existing scripture links (Markdown links and HTML anchors to
+    /// ChurchofJesusChrist.org and `[[Book Chapter]]` wikilinks) in --format
+    #[arg(long)]
+    pub convert: bool,
+
+    /// Replace scripture links with their text instead of linking references, for
+    /// plain-text output; links in code blocks and code spans are kept
+    #[arg(long, conflicts_with_all = ["watch", "format", "canonicalize", "convert"])]
+    pub unlink: bool,
+}
+
+/// Arguments of the `lint` command
+#[derive(Args)]
+pub struct LintArgs {
+    /// Files or directories to check ("-" reads stdin)
+    #[arg(required = true)]
+    pub paths: Vec<String>,
+
+    #[command(flatten)]
+    pub walk: WalkArgs,
+}
+
+/// Options for walking directories, shared by `link` and `lint`
+#[derive(Args)]
+pub struct WalkArgs {
+    /// Only process files matching this glob when walking directories (repeatable;
+    /// defaults to Markdown and text files)
+    #[arg(long)]
+    pub include: Vec<String>,
+
+    /// Skip files matching this glob when walking directories (repeatable)
+    #[arg(long)]
+    pub exclude: Vec<String>,
+
+    /// Number of files to process in parallel when processing multiple files
+    /// (defaults to the number of CPUs)
+    #[arg(short, long)]
+    pub jobs: Option<usize>,
+}
+
+/// Arguments of the `parse` command
+#[derive(Args)]
+pub struct ParseArgs {
+    /// Scripture references (e.g., "Isa. 6:5", "2 Ne. 10:14-15"); "-" reads one or more
+    /// per line from stdin
+    #[arg(required = true)]
+    pub references: Vec<String>,
+
+    /// Output format: markdown prints the URL (the default), wikilink prints
+    /// [[Book Chapter]]:Verse and html prints an <a> element
+    #[arg(long, value_enum)]
+    pub format: Option<FormatArg>,
+}
+
+/// Arguments of the `validate` command
+#[derive(Args)]
+pub struct ValidateArgs {
+    /// Scripture references to validate; "-" reads one or more per line from stdin
+    #[arg(required = true)]
+    pub references: Vec<String>,
+}
+
+/// Arguments of the `extract` command
+#[derive(Args)]
+#[command(group(ArgGroup::new("source").required(true).args(["path", "text"])))]
+pub struct ExtractArgs {
+    /// File to read ("-" reads stdin)
+    pub path: Option<String>,
+
+    /// Find references in this text instead of a file
+    #[arg(short, long, conflicts_with = "path")]
+    pub text: Option<String>,
+}
+
+/// Arguments of the `info` command
+#[derive(Args)]
+pub struct InfoArgs {
+    /// Scripture reference or whole chapter (e.g., "Alma 32", "Ether 12:27")
+    pub reference: String,
+}
+
+/// Arguments of the `vault` command
+#[derive(Args)]
+pub struct VaultArgs {
+    /// Directory to create the notes in
+    pub dir: String,
+
+    /// Folders for the notes: flat (none), work (a folder per standard work, the
+    /// default) or book (folders per standard work and book)
+    #[arg(long, value_enum, default_value_t)]
+    pub layout: LayoutArg,
+
+    /// How verses are marked: heading (`## 1`, for `[[Genesis 1#1]]`, the default) or
+    /// block (`^1`, for `[[Genesis 1#^1]]`)
+    #[arg(long, value_enum, default_value_t)]
+    pub anchors: AnchorsArg,
+
+    /// Overwrite notes that already exist
+    #[arg(long)]
+    pub force: bool,
+}
+
 impl Cli {
     /// Execute the CLI command
     ///
     /// # Errors
     /// Returns an error if file operations fail or if invalid arguments are provided
     pub fn execute(self) -> Result<(), CliError> {
-        if self.in_place && self.file.is_none() {
-            Self::output_error("--in-place can only be used with --file");
+        if self.command.is_some() && self.has_legacy_flags() {
+            Self::command()
+                .error(
+                    ErrorKind::ArgumentConflict,
+                    "deprecated options cannot be combined with a command",
+                )
+                .exit();
+        }
+        let config = match self.load_config().and_then(|mut config| {
+            for language in &self.book_names {
+                config.aliases.enable_language(*language)?;
+            }
+            Ok(config)
+        }) {
+            Ok(config) => config,
+            Err(error) => {
+                Options::output_error(&format!("Error: {error}"));
+                std::process::exit(1);
+            }
+        };
+        let mut options = Options::from_config(&config);
+        options.json = self.json;
+        for providers in self.url_provider.clone() {
+            options.processor.urls.extend(providers);
+        }
+        if let Some(lang) = self.lang {
+            options.processor.lang = lang;
+        }
+        options.processor.force_lang |= self.force_lang;
+        if let Some(display_lang) = self.display_lang {
+            options.processor.display_lang = display_lang;
+        }
+        if let Some(message_lang) = self.message_lang {
+            options.message_lang = Some(message_lang);
+        }
+        if let Some(wikilink) = self.wikilink.clone() {
+            options.processor.wikilink = wikilink;
+        }
+        if let Some(wikilink_path) = self.wikilink_path.clone() {
+            options.processor.wikilink_path = wikilink_path;
+        }
+        if let Some(study_helps_wikilink) = self.study_helps_wikilink.clone() {
+            options.processor.study_helps_wikilink = study_helps_wikilink;
+        }
+        if let Some(class) = &self.html_class {
+            options.processor.html.set_class(class);
+        }
+        for attributes in self.html_attribute.clone() {
+            options.processor.html.extend_attributes(attributes);
+        }
+        let action = match self.command {
+            Some(command) => command.into_action(&mut options),
+            None => self.legacy_action(&mut options),
+        };
+        match options.run(action) {
+            // The reader stopped early, as `scripture-links books | head` does
+            Err(error) if is_broken_pipe(&error) => Ok(()),
+            result => result,
+        }
+    }
+
+    /// Settings from `--config`, or discovered from the working directory and user config
+    fn load_config(&self) -> Result<Config, String> {
+        if self.no_config {
+            return Ok(Config::default());
+        }
+        if let Some(path) = &self.config {
+            return Config::load(path);
+        }
+        let cwd = std::env::current_dir().map_err(|e| e.to_string())?;
+        Config::discover(&cwd)
+    }
+
+    const fn has_legacy_flags(&self) -> bool {
+        self.reference.is_some()
+            || self.text.is_some()
+            || !self.file.is_empty()
+            || !self.watch.is_empty()
+            || !self.include.is_empty()
+            || !self.exclude.is_empty()
+            || self.jobs.is_some()
+            || self.in_place
+            || self.check
+            || self.diff
+            || self.format.is_some()
+            || self.validate_only
+            || self.batch.is_some()
+    }
+
+    /// Translate the deprecated flat flags into an action, warning on stderr
+    fn legacy_action(self, options: &mut Options) -> Action {
+        if self.in_place && self.file.is_empty() {
+            Options::output_error("--in-place can only be used with --file");
+            std::process::exit(1);
+        }
+        if self.check && self.file.is_empty() {
+            Options::output_error("--check can only be used with --file");
             std::process::exit(1);
         }
-        if let Some(ref reference) = self.reference {
-            self.handle_single_reference(reference)
-        } else if let Some(ref batch) = self.batch {
-            self.handle_batch_references(batch)
-        } else if let Some(ref text) = self.text {
-            self.handle_text_processing(text)
-        } else if let Some(ref file_path) = self.file {
-            self.handle_file_processing(file_path)
+        if self.diff && self.file.is_empty() {
+            Options::output_error("--diff can only be used with --file");
+            std::process::exit(1);
+        }
+
+        let watching = !self.watch.is_empty();
+        let validate_command = if self.validate_only {
+            "validate"
         } else {
-            Self::output_error("Please provide either --reference, --batch, --text, or --file");
+            "parse"
+        };
+        let (flag, replacement, action) = if let Some(reference) = self.reference {
+            (
+                "--reference",
+                validate_command,
+                Action::Reference(reference),
+            )
+        } else if let Some(batch) = self.batch {
+            let references = if batch == STDIN_ARG {
+                vec![batch]
+            } else {
+                batch.split(',').map(|r| r.trim().to_string()).collect()
+            };
+            ("--batch", validate_command, Action::References(references))
+        } else if let Some(text) = self.text {
+            ("--text", "link --text", Action::Text(text))
+        } else if !self.file.is_empty() {
+            let replacement = if self.check { "lint" } else { "link" };
+            ("--file", replacement, Action::Files(self.file))
+        } else if !self.watch.is_empty() {
+            ("--watch", "link --watch", Action::Watch(self.watch))
+        } else {
+            Options::output_error(
+                "Please provide a command (link, lint, parse, validate, extract, books); see --help",
+            );
             std::process::exit(1);
+        };
+        eprintln!("Warning: {flag} is deprecated; use `scripture-links {replacement}` instead");
+
+        if let Some(format) = self.format {
+            options.processor.format = format.into();
         }
+        options.set_walk(WalkArgs {
+            include: self.include,
+            exclude: self.exclude,
+            jobs: self.jobs,
+        });
+        options.validate_only = self.validate_only;
+        // Watching always links in place
+        options.in_place = self.in_place || watching;
+        options.check = self.check;
+        options.diff = self.diff;
+        action
+    }
+}
+
+impl Command {
+    /// Apply the command's flags on top of `options` and return what to run
+    fn into_action(self, options: &mut Options) -> Action {
+        match self {
+            Self::Link(args) => {
+                options.set_format(args.format);
+                if args.study_helps || args.no_study_helps {
+                    options.processor.include_study_helps = args.study_helps;
+                }
+                options.set_walk(args.walk);
+                options.processor.canonicalize |= args.canonicalize;
+                options.processor.convert = args.convert;
+                options.processor.unlink = args.unlink;
+                options.in_place = args.in_place || args.watch;
+                options.diff = args.diff;
+                match args.text {
+                    Some(text) => Action::Text(text),
+                    None if args.watch => Action::Watch(args.paths),
+                    None => Action::Files(args.paths),
+                }
+            }
+            Self::Lint(args) => {
+                options.set_walk(args.walk);
+                options.check = true;
+                Action::Files(args.paths)
+            }
+            Self::Parse(args) => {
+                options.set_format(args.format);
+                Action::from_references(args.references)
+            }
+            Self::Validate(args) => {
+                options.validate_only = true;
+                Action::from_references(args.references)
+            }
+            Self::Extract(args) => match (args.text, args.path) {
+                (Some(text), _) => Action::ExtractText(text),
+                (None, path) => Action::ExtractFile(path.unwrap_or_default()),
+            },
+            Self::Info(args) => Action::Info(args.reference),
+            Self::Books => Action::Books,
+            Self::Vault(args) => Action::Vault(args),
+        }
+    }
+}
+
+/// What to run, independent of whether it came from a subcommand or the deprecated flags
+enum Action {
+    Reference(String),
+    References(Vec<String>),
+    Text(String),
+    Files(Vec<String>),
+    Watch(Vec<String>),
+    ExtractText(String),
+    ExtractFile(String),
+    Info(String),
+    Books,
+    Vault(VaultArgs),
+}
+
+impl Action {
+    /// A single reference prints just its result; several (or stdin) print a batch report
+    fn from_references(mut references: Vec<String>) -> Self {
+        if let [reference] = references.as_slice()
+            && reference != STDIN_ARG
+        {
+            Self::Reference(references.remove(0))
+        } else {
+            Self::References(references)
+        }
+    }
+}
+
+/// Settings shared by the handlers
+#[derive(Default)]
+#[allow(clippy::struct_excessive_bools)]
+struct Options {
+    /// How text is linked: the config's settings with the command-line flags on top
+    processor: ProcessorOptions,
+    /// Language of error messages, when one is configured or given
+    message_lang: Option<Language>,
+    json: bool,
+    validate_only: bool,
+    include: Vec<String>,
+    exclude: Vec<String>,
+    jobs: Option<usize>,
+    in_place: bool,
+    check: bool,
+    diff: bool,
+}
+
+impl Options {
+    /// Defaults taken from the config, before command-line flags are applied
+    fn from_config(config: &Config) -> Self {
+        Self {
+            processor: config.processor_options(),
+            message_lang: config.message_lang,
+            include: config.include.clone().unwrap_or_default(),
+            exclude: config.exclude.clone().unwrap_or_default(),
+            jobs: config.jobs,
+            ..Self::default()
+        }
+    }
+
+    fn set_format(&mut self, format: Option<FormatArg>) {
+        if let Some(format) = format {
+            self.processor.format = format.into();
+        }
+    }
+
+    /// Globs given on the command line replace the configured ones
+    fn set_walk(&mut self, walk: WalkArgs) {
+        if !walk.include.is_empty() {
+            self.include = walk.include;
+        }
+        if !walk.exclude.is_empty() {
+            self.exclude = walk.exclude;
+        }
+        if walk.jobs.is_some() {
+            self.jobs = walk.jobs;
+        }
+    }
+
+    fn run(&self, action: Action) -> Result<(), CliError> {
+        match action {
+            Action::Reference(reference) => self.handle_single_reference(&reference),
+            Action::References(references) => self.handle_batch_references(&references),
+            Action::Text(text) => self.handle_text_processing(&text),
+            Action::Files(paths) => {
+                if self.in_place && paths.iter().any(|path| path == STDIN_ARG) {
+                    Self::output_error("--in-place cannot be used with standard input");
+                    std::process::exit(1);
+                }
+                if let [file_path] = paths.as_slice()
+                    && !Path::new(file_path).is_dir()
+                {
+                    self.handle_file_processing(file_path)
+                } else {
+                    self.handle_multi_file_processing(&paths)
+                }
+            }
+            Action::Watch(paths) => {
+                if paths.iter().any(|path| path == STDIN_ARG) {
+                    Self::output_error("--watch cannot be used with standard input");
+                    std::process::exit(1);
+                }
+                self.handle_watch(&paths)
+            }
+            Action::ExtractText(text) if text == STDIN_ARG => self.handle_extract(&read_stdin()?),
+            Action::ExtractText(text) => self.handle_extract(&text),
+            Action::ExtractFile(path) => self.handle_extract_file(&path),
+            Action::Info(reference) => self.handle_info(&reference),
+            Action::Books => self.handle_books(),
+            Action::Vault(args) => self.handle_vault(&args),
+        }
+    }
+
+    /// Parse a reference with the configured aliases and works
+    fn parse(&self, reference: &str) -> Result<ScriptureReference, ReferenceError> {
+        parse_scripture_reference_with_works(
+            reference,
+            &self.processor.aliases,
+            &self.processor.works,
+        )
+    }
+
+    /// URL of a parsed reference, in the language its book name is written in
+    /// unless the language is forced
+    fn reference_url(&self, reference: &str, scripture: &ScriptureReference) -> String {
+        let lang = self
+            .processor
+            .url_language(reference_language(reference, &self.processor.aliases));
+        self.processor.url(scripture, lang)
     }
 
     fn handle_single_reference(&self, reference: &str) -> Result<(), CliError> {
+        let mut out = std::io::stdout().lock();
         if self.validate_only {
             self.handle_validation(reference)?;
         } else {
-            match parse_scripture_reference(reference) {
+            match self.parse(reference) {
                 Ok(scripture) => {
-                    let output_format: OutputFormat = self.format.into();
                     if self.json {
-                        let url = generate_url(&scripture);
+                        let url = self.reference_url(reference, &scripture);
                         #[allow(clippy::redundant_clone)]
                         let response = SingleReferenceResponse {
                             success: true,
@@ -110,34 +760,48 @@ impl Cli {
                             url: Some(url),
                             error: None,
                         };
-                        println!("{}", serde_json::to_string_pretty(&response)?);
+                        write_json(&response)?;
                     } else {
-                        match output_format {
+                        match self.processor.format {
                             OutputFormat::Wikilink => {
-                                let display_name = book_slug_to_display_name(&scripture.book)
-                                    .unwrap_or(scripture.book.as_str());
-                                let verse_suffix = scripture.verse_end.map_or_else(
-                                    || scripture.verse_start.to_string(),
-                                    |end| format!("{}-{end}", scripture.verse_start),
-                                );
-                                println!(
-                                    "[[{} {}]]:{verse_suffix}",
-                                    display_name, scripture.chapter
-                                );
+                                writeln!(
+                                    out,
+                                    "{}",
+                                    self.processor.wikilink.render(
+                                        &scripture,
+                                        reference.trim(),
+                                        &self.processor.wikilink_path,
+                                        &self.processor.book_name(&scripture.book),
+                                    )
+                                )?;
                             }
                             OutputFormat::Markdown => {
-                                let url = generate_url(&scripture);
-                                println!("{url}");
+                                let url = self.reference_url(reference, &scripture);
+                                writeln!(out, "{url}")?;
+                            }
+                            OutputFormat::Html => {
+                                let url = self.reference_url(reference, &scripture);
+                                writeln!(
+                                    out,
+                                    "{}",
+                                    self.processor.html.render(
+                                        &scripture,
+                                        reference.trim(),
+                                        &url,
+                                        &self.processor.book_name(&scripture.book),
+                                    )
+                                )?;
                             }
                         }
                     }
                 }
                 Err(error) => {
                     if self.json {
-                        let response = create_error_response(reference, &error);
-                        println!("{}", serde_json::to_string_pretty(&response)?);
+                        let response =
+                            create_error_response_in(reference, &error, self.message_language());
+                        write_json(&response)?;
                     } else {
-                        Self::output_error(&format!("Error: {error}"));
+                        Self::output_error(&format!("Error: {}", self.error_message(&error)));
                         std::process::exit(1);
                     }
                 }
@@ -146,23 +810,37 @@ impl Cli {
         Ok(())
     }
 
-    fn handle_batch_references(&self, batch: &str) -> Result<(), CliError> {
-        let references: Vec<&str> = batch.split(',').map(str::trim).collect();
+    fn handle_batch_references(&self, batch: &[String]) -> Result<(), CliError> {
+        let mut out = std::io::stdout().lock();
+        let mut references = Vec::new();
+        for reference in batch {
+            if reference == STDIN_ARG {
+                references.extend(
+                    read_stdin()?
+                        .split([',', '\n'])
+                        .map(str::trim)
+                        .filter(|reference| !reference.is_empty())
+                        .map(str::to_string),
+                );
+            } else {
+                references.push(reference.clone());
+            }
+        }
         let mut results = Vec::new();
         let mut successful = 0;
         let mut failed = 0;
 
         for reference in &references {
-            match parse_scripture_reference(reference) {
+            match self.parse(reference) {
                 Ok(scripture) => {
                     let url = if self.validate_only {
                         None
                     } else {
-                        Some(generate_url(&scripture))
+                        Some(self.reference_url(reference, &scripture))
                     };
                     results.push(SingleReferenceResponse {
                         success: true,
-                        input: (*reference).to_string(),
+                        input: reference.clone(),
                         parsed: Some(scripture),
                         url,
                         error: None,
@@ -170,7 +848,11 @@ impl Cli {
                     successful += 1;
                 }
                 Err(error) => {
-                    results.push(create_error_response(reference, &error));
+                    results.push(create_error_response_in(
+                        reference,
+                        &error,
+                        self.message_language(),
+                    ));
                     failed += 1;
                 }
             }
@@ -184,28 +866,34 @@ impl Cli {
                 failed,
                 results,
             };
-            println!("{}", serde_json::to_string_pretty(&response)?);
+            write_json(&response)?;
         } else {
             // Human-readable batch output
             for result in &results {
                 if result.success {
                     if let Some(url) = &result.url {
-                        println!("{}: {}", result.input, url);
+                        writeln!(out, "{}: {}", result.input, url)?;
                     } else {
-                        println!("{}: Valid", result.input);
+                        writeln!(out, "{}: Valid", result.input)?;
                     }
                 } else if let Some(error) = &result.error {
-                    println!("{}: Error - {}", result.input, error.message);
+                    writeln!(out, "{}: Error - {}", result.input, error.message)?;
                 }
             }
-            println!("\nSummary: {}/{} successful", successful, references.len());
+            writeln!(
+                out,
+                "\nSummary: {}/{} successful",
+                successful,
+                references.len()
+            )?;
         }
 
         Ok(())
     }
 
     fn handle_validation(&self, reference: &str) -> Result<(), CliError> {
-        match parse_scripture_reference(reference) {
+        let mut out = std::io::stdout().lock();
+        match self.parse(reference) {
             Ok(scripture) => {
                 if self.json {
                     let response = ValidationResponse {
@@ -215,27 +903,23 @@ impl Cli {
                         parsed: Some(scripture),
                         error: None,
                     };
-                    println!("{}", serde_json::to_string_pretty(&response)?);
+                    write_json(&response)?;
                 } else {
-                    println!("Valid: {reference}");
+                    writeln!(out, "Valid: {reference}")?;
                 }
             }
             Err(error) => {
                 if self.json {
-                    let error_info = {
-                        let (code, category) = crate::json_output::categorize_error(&error);
-                        ErrorInfo::new(&code, &error, category)
-                    };
                     let response = ValidationResponse {
                         success: false,
                         input: reference.to_string(),
                         valid: false,
                         parsed: None,
-                        error: Some(error_info),
+                        error: Some(ErrorInfo::from_error(&error, self.message_language())),
                     };
-                    println!("{}", serde_json::to_string_pretty(&response)?);
+                    write_json(&response)?;
                 } else {
-                    println!("Invalid: {reference} - {error}");
+                    writeln!(out, "Invalid: {reference} - {}", self.error_message(&error))?;
                 }
             }
         }
@@ -243,90 +927,799 @@ impl Cli {
     }
 
     fn handle_text_processing(&self, text: &str) -> Result<(), CliError> {
-        let output_format: OutputFormat = self.format.into();
-        let processed_text = process_text_with_format(text, output_format, false);
+        let mut out = std::io::stdout().lock();
+        let from_stdin = text == STDIN_ARG;
+        let stdin_text;
+        let text = if from_stdin {
+            stdin_text = read_stdin()?;
+            stdin_text.as_str()
+        } else {
+            text
+        };
+        let ProcessedText {
+            text: processed_text,
+            references_linked,
+        } = TextProcessor::new(self.processor.clone()).process_with_stats(text);
 
         if self.json {
-            // Count references found (rough estimate)
-            let references_found = processed_text.matches('[').count();
-
             let response = TextProcessingResponse {
                 success: true,
                 input_text: text.to_string(),
                 output_text: processed_text,
-                references_found,
+                references_found: references_linked,
                 references: Vec::new(), // TODO: Could be enhanced to provide detailed reference info
+                error: None,
             };
-            println!("{}", serde_json::to_string_pretty(&response)?);
+            write_json(&response)?;
+        } else if from_stdin {
+            // Filters must not add a trailing newline
+            write!(out, "{processed_text}")?;
         } else {
-            println!("{processed_text}");
+            writeln!(out, "{processed_text}")?;
         }
 
         Ok(())
     }
 
     fn handle_file_processing(&self, file_path: &str) -> Result<(), CliError> {
-        let file_content = match fs::read_to_string(file_path) {
+        let mut out = std::io::stdout().lock();
+        if !self.json && !self.diff {
+            return self.stream_file_processing(file_path);
+        }
+
+        let (file_path, read_result) = if file_path == STDIN_ARG {
+            (STDIN_NAME, read_stdin())
+        } else {
+            (file_path, fs::read_to_string(file_path))
+        };
+        let file_content = match read_result {
             Ok(content) => content,
             Err(error) => {
-                if self.json {
-                    let _error_info = ErrorInfo::new(
-                        "FILE_READ_ERROR",
-                        &format!("Error reading file '{file_path}': {error}"),
-                        ErrorCategory::FileReadError,
-                    );
-                    let response = TextProcessingResponse {
+                let message = format!("Error reading file '{file_path}': {error}");
+                if !self.json {
+                    Self::output_error(&message);
+                    std::process::exit(1);
+                }
+                let error =
+                    ErrorInfo::new("FILE_READ_ERROR", &message, ErrorCategory::FileReadError);
+                if self.check {
+                    write_json(&CheckResponse {
                         success: false,
-                        input_text: String::new(),
-                        output_text: String::new(),
-                        references_found: 0,
-                        references: Vec::new(),
-                    };
-                    println!("{}", serde_json::to_string_pretty(&response)?);
-                } else {
-                    Self::output_error(&format!("Error reading file '{file_path}': {error}"));
+                        files_checked: 0,
+                        files_needing_changes: Vec::new(),
+                        error: Some(error),
+                    })?;
+                    // A check that could not run must not pass
                     std::process::exit(1);
                 }
+                write_json(&TextProcessingResponse {
+                    success: false,
+                    input_text: String::new(),
+                    output_text: String::new(),
+                    references_found: 0,
+                    references: Vec::new(),
+                    error: Some(error),
+                })?;
                 return Ok(());
             }
         };
 
-        let output_format: OutputFormat = self.format.into();
-        let processed = process_text_with_format(&file_content, output_format, false);
+        let ProcessedText {
+            text: processed,
+            references_linked,
+        } = TextProcessor::new(self.processor.clone()).process_with_stats(&file_content);
+
+        let would_change = processed != file_content;
+
+        if self.diff {
+            self.report_diff(file_path, &file_content, &processed)?;
+        } else if self.check {
+            self.report_check(file_path, would_change)?;
+        }
+
+        if self.check {
+            if would_change {
+                std::process::exit(CHECK_FAILED_EXIT_CODE);
+            }
+            return Ok(());
+        }
 
         if self.in_place {
-            if processed != file_content {
-                let path = Path::new(file_path);
-                let parent = path.parent().unwrap_or_else(|| Path::new("."));
-                let mut temp_file = tempfile::Builder::new()
-                    .prefix(".scripture-links.")
-                    .suffix(".tmp")
-                    .tempfile_in(parent)?;
-                temp_file.write_all(processed.as_bytes())?;
-                temp_file.as_file().sync_all()?;
-                // On Windows, fs::rename does not overwrite an existing file; remove first.
-                #[cfg(windows)]
-                fs::remove_file(path)?;
-                temp_file.persist(path)?;
+            if would_change {
+                Self::write_atomically(Path::new(file_path), &processed)?;
             }
+        } else if self.diff {
+            // Dry run: the diff is the whole output
         } else if self.json {
-            let references_found = processed.matches('[').count();
             let response = TextProcessingResponse {
                 success: true,
                 input_text: file_content,
                 output_text: processed,
-                references_found,
+                references_found: references_linked,
                 references: Vec::new(),
+                error: None,
             };
-            println!("{}", serde_json::to_string_pretty(&response)?);
+            write_json(&response)?;
         } else {
-            print!("{processed}");
+            write!(out, "{processed}")?;
         }
         Ok(())
     }
 
+    /// Process a single file in bounded memory; JSON and diff output need the whole file instead
+    fn stream_file_processing(&self, file_path: &str) -> Result<(), CliError> {
+        let reader: Box<dyn BufRead> = if file_path == STDIN_ARG {
+            Box::new(std::io::stdin().lock())
+        } else {
+            match fs::File::open(file_path) {
+                Ok(file) => Box::new(BufReader::new(file)),
+                Err(error) => {
+                    Self::output_error(&format!("Error reading file '{file_path}': {error}"));
+                    std::process::exit(1);
+                }
+            }
+        };
+        let file_path = if file_path == STDIN_ARG {
+            STDIN_NAME
+        } else {
+            file_path
+        };
+        let processor = TextProcessor::new(self.processor.clone());
+
+        if self.check {
+            let would_change = process_compared(&processor, reader, std::io::sink())?;
+            self.report_check(file_path, would_change)?;
+            if would_change {
+                std::process::exit(CHECK_FAILED_EXIT_CODE);
+            }
+        } else if self.in_place {
+            Self::replace_atomically(Path::new(file_path), |file| {
+                process_compared(&processor, reader, BufWriter::new(file))
+            })?;
+        } else {
+            processor.process_stream(reader, BufWriter::new(std::io::stdout().lock()))?;
+        }
+        Ok(())
+    }
+
+    fn handle_multi_file_processing(&self, paths: &[String]) -> Result<(), CliError> {
+        if !(self.in_place || self.check || self.diff) {
+            Self::output_error(
+                "Processing multiple files or directories requires --in-place, --check, or --diff",
+            );
+            std::process::exit(1);
+        }
+
+        let files = match collect_files(paths, &self.include, &self.exclude) {
+            Ok(files) => files,
+            Err(error) => {
+                Self::output_error(&format!("Error: {error}"));
+                std::process::exit(1);
+            }
+        };
+
+        // One precompiled processor is shared by all worker threads
+        let processor = TextProcessor::new(self.processor.clone());
+        let jobs = self.jobs.unwrap_or_else(default_jobs);
+        let results = map_ordered(&files, jobs, |path| self.process_one_file(path, &processor));
+
+        let changed = results.iter().filter(|r| r.changed).count();
+        let failed = results.iter().filter(|r| r.error.is_some()).count();
+        let references_linked = results.iter().map(|r| r.references_linked).sum();
+
+        if self.json {
+            let response = MultiFileResponse {
+                success: failed == 0 && !(self.check && changed > 0),
+                total_files: results.len(),
+                changed,
+                unchanged: results.len() - changed - failed,
+                failed,
+                references_linked,
+                files: results,
+            };
+            write_json(&response)?;
+        } else {
+            self.print_multi_file_summary(&results, changed, failed, references_linked)?;
+        }
+
+        if self.check && changed > 0 {
+            std::process::exit(CHECK_FAILED_EXIT_CODE);
+        }
+        if failed > 0 {
+            std::process::exit(1);
+        }
+        Ok(())
+    }
+
+    fn process_one_file(&self, path: &Path, processor: &TextProcessor) -> FileResult {
+        let display_path = path.display().to_string();
+        let mut result = FileResult {
+            path: display_path.clone(),
+            changed: false,
+            written: false,
+            references_linked: 0,
+            hunks: None,
+            error: None,
+        };
+
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(error) => {
+                result.error = Some(ErrorInfo::new(
+                    "FILE_READ_ERROR",
+                    &format!("Error reading file '{display_path}': {error}"),
+                    ErrorCategory::FileReadError,
+                ));
+                return result;
+            }
+        };
+
+        let ProcessedText {
+            text: processed,
+            references_linked,
+        } = processor.process_with_stats(&content);
+        result.changed = processed != content;
+        result.references_linked = references_linked;
+        if self.diff {
+            result.hunks = Some(diff_hunks(&content, &processed));
+        }
+
+        if self.in_place && !self.check && result.changed {
+            match Self::write_atomically(path, &processed) {
+                Ok(()) => result.written = true,
+                Err(error) => {
+                    result.error = Some(ErrorInfo::new(
+                        "FILE_WRITE_ERROR",
+                        &format!("Error writing file '{display_path}': {error}"),
+                        ErrorCategory::FileWriteError,
+                    ));
+                }
+            }
+        }
+        result
+    }
+
+    fn handle_watch(&self, paths: &[String]) -> Result<(), CliError> {
+        let processor = TextProcessor::new(self.processor.clone());
+        let mut filter = ChangeFilter::new(paths, &self.include, &self.exclude);
+        let files = match filter.matching_files() {
+            Ok(files) => files,
+            Err(error) => {
+                Self::output_error(&format!("Error: {error}"));
+                std::process::exit(1);
+            }
+        };
+
+        // Catch up on anything saved while the watcher was not running
+        self.process_watched_files(&files, &processor, &mut filter)?;
+        if !self.json {
+            eprintln!(
+                "Watching {} for changes (press Ctrl+C to stop)",
+                paths.join(", ")
+            );
+        }
+
+        watch(paths, DEFAULT_DEBOUNCE, |changed| {
+            let result = filter.files_to_process(changed).and_then(|files| {
+                self.process_watched_files(&files, &processor, &mut filter)
+                    .map_err(|error| {
+                        if error.kind() == std::io::ErrorKind::BrokenPipe {
+                            // Nobody is reading the output any more
+                            std::process::exit(0);
+                        }
+                        error.to_string()
+                    })
+            });
+            if let Err(error) = result {
+                Self::output_error(&format!("Error: {error}"));
+            }
+        })?;
+        Ok(())
+    }
+
+    fn handle_extract_file(&self, path: &str) -> Result<(), CliError> {
+        let (path, read_result) = if path == STDIN_ARG {
+            (STDIN_NAME, read_stdin())
+        } else {
+            (path, fs::read_to_string(path))
+        };
+        match read_result {
+            Ok(text) => self.handle_extract(&text),
+            Err(error) => {
+                Self::output_error(&format!("Error reading file '{path}': {error}"));
+                std::process::exit(1);
+            }
+        }
+    }
+
+    fn handle_extract(&self, text: &str) -> Result<(), CliError> {
+        let mut out = std::io::stdout().lock();
+        let processor = TextProcessor::new(self.processor.clone());
+        let found = processor.find_references(text);
+        let url = |found: &ReferenceMatch| {
+            self.processor.url(
+                &found.reference,
+                processor.options().url_language(found.language),
+            )
+        };
+
+        if self.json {
+            let references: Vec<FoundReference> = found
+                .into_iter()
+                .map(|found| FoundReference {
+                    url: Some(url(&found)),
+                    original_text: found.matched_text,
+                    language: found.language,
+                    parsed: Some(found.reference),
+                    position: Some(TextPosition {
+                        start: found.range.start,
+                        end: found.range.end,
+                    }),
+                })
+                .collect();
+            let response = ExtractResponse {
+                success: true,
+                references_found: references.len(),
+                references,
+            };
+            write_json(&response)?;
+        } else {
+            for found in &found {
+                writeln!(out, "{}: {}", found.matched_text, url(found))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_info(&self, reference: &str) -> Result<(), CliError> {
+        match reference_info_with_provider(
+            reference,
+            &self.processor.aliases,
+            self.processor.lang,
+            &self.processor.urls,
+        ) {
+            Ok(info) => {
+                if self.json {
+                    let response = InfoResponse {
+                        success: true,
+                        input: reference.to_string(),
+                        info: Some(info),
+                        error: None,
+                    };
+                    write_json(&response)?;
+                } else {
+                    Self::print_info(&info)?;
+                }
+            }
+            Err(error) => {
+                if self.json {
+                    let response = InfoResponse {
+                        success: false,
+                        input: reference.to_string(),
+                        info: None,
+                        error: Some(ErrorInfo::from_error(&error, self.message_language())),
+                    };
+                    write_json(&response)?;
+                } else {
+                    Self::output_error(&format!("Error: {}", self.error_message(&error)));
+                    std::process::exit(1);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn print_info(info: &ReferenceInfo) -> std::io::Result<()> {
+        let mut out = std::io::stdout().lock();
+        let verses = match (info.verse_start, info.verse_end) {
+            (Some(start), Some(end)) => format!(":{start}-{end}"),
+            (Some(start), None) => format!(":{start}"),
+            _ => String::new(),
+        };
+        let neighbor = |location: Option<&ChapterLocation>| {
+            location.map_or_else(
+                || "-".to_string(),
+                |location| format!("{} {}", location.name, location.chapter),
+            )
+        };
+
+        writeln!(
+            out,
+            "{} {}{verses} ({})",
+            info.name,
+            info.chapter,
+            info.standard_work.display_name()
+        )?;
+        writeln!(out, "  Chapters in book:  {}", info.chapters_in_book)?;
+        writeln!(
+            out,
+            "  Verses in chapter: {} (1-{})",
+            info.verses_in_chapter, info.verses_in_chapter
+        )?;
+        writeln!(out, "  Verses in range:   {}", info.verses_in_range)?;
+        writeln!(
+            out,
+            "  Previous chapter:  {}",
+            neighbor(info.previous_chapter.as_ref())
+        )?;
+        writeln!(
+            out,
+            "  Next chapter:      {}",
+            neighbor(info.next_chapter.as_ref())
+        )?;
+        writeln!(out, "  URL:               {}", info.chapter_url)
+    }
+
+    fn handle_vault(&self, args: &VaultArgs) -> Result<(), CliError> {
+        let mut out = std::io::stdout().lock();
+        let options = VaultOptions {
+            layout: args.layout.into(),
+            anchors: args.anchors.into(),
+            lang: self.processor.lang,
+            display_lang: self.processor.display_lang,
+        };
+        let summary = match write_vault(Path::new(&args.dir), &options, args.force) {
+            Ok(summary) => summary,
+            Err(error) => {
+                Self::output_error(&format!("Error writing vault '{}': {error}", args.dir));
+                std::process::exit(1);
+            }
+        };
+        if self.json {
+            let response = VaultResponse {
+                success: true,
+                directory: args.dir.clone(),
+                written: summary.written,
+                skipped: summary.skipped,
+            };
+            write_json(&response)?;
+        } else {
+            writeln!(out, "Wrote {} notes to {}", summary.written, args.dir)?;
+            if summary.skipped > 0 {
+                writeln!(
+                    out,
+                    "Kept {} existing notes (use --force to overwrite them)",
+                    summary.skipped
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_books(&self) -> Result<(), CliError> {
+        let mut out = std::io::stdout().lock();
+        let books = book_catalog();
+        if self.json {
+            write_json(&books)?;
+            return Ok(());
+        }
+
+        let mut current_work = None;
+        for book in &books {
+            if current_work != Some(&book.standard_work) {
+                if current_work.is_some() {
+                    writeln!(out)?;
+                }
+                writeln!(out, "{}", book.standard_work.display_name())?;
+                current_work = Some(&book.standard_work);
+            }
+            let counts = match (book.chapters, book.verses) {
+                (Some(chapters), Some(verses)) => {
+                    let unit = if chapters == 1 {
+                        "chapter "
+                    } else {
+                        "chapters"
+                    };
+                    format!("{chapters:>3} {unit} {verses:>5} verses")
+                }
+                // Linked without validation, so `info` has nothing to report either
+                _ => "no chapter and verse data".to_string(),
+            };
+            writeln!(
+                out,
+                "  {:<24} {:<8} {counts}  {}",
+                book.name,
+                book.slug,
+                book.aliases.join(", ")
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Link `files` in place, remembering the writes so they do not retrigger the watcher
+    fn process_watched_files(
+        &self,
+        files: &[PathBuf],
+        processor: &TextProcessor,
+        filter: &mut ChangeFilter,
+    ) -> std::io::Result<()> {
+        let mut out = std::io::stdout().lock();
+        let jobs = self.jobs.unwrap_or_else(default_jobs);
+        let results = map_ordered(files, jobs, |path| self.process_one_file(path, processor));
+        for (path, result) in files.iter().zip(results) {
+            if result.written {
+                filter.record_write(path);
+            }
+            if self.json {
+                // One JSON object per line so the output can be consumed as it happens
+                match serde_json::to_string(&result) {
+                    Ok(json) => writeln!(out, "{json}")?,
+                    Err(error) => Self::output_error(&format!("Error: {error}")),
+                }
+            } else if let Some(error) = &result.error {
+                Self::output_error(&format!("Error: {}", error.message));
+            } else if result.written {
+                writeln!(
+                    out,
+                    "Updated: {} ({} {})",
+                    result.path,
+                    result.references_linked,
+                    self.changes_label()
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    fn print_multi_file_summary(
+        &self,
+        results: &[FileResult],
+        changed: usize,
+        failed: usize,
+        references_linked: usize,
+    ) -> std::io::Result<()> {
+        let stdout = std::io::stdout();
+        let color = stdout.is_terminal();
+        for result in results {
+            if let Some(hunks) = &result.hunks {
+                write!(
+                    stdout.lock(),
+                    "{}",
+                    render_unified_diff(&result.path, hunks, color)
+                )?;
+            }
+        }
+
+        // Keep stdout a clean patch when printing diffs
+        let mut summary: Box<dyn Write> = if self.diff {
+            Box::new(std::io::stderr().lock())
+        } else {
+            Box::new(stdout.lock())
+        };
+        for result in results {
+            if let Some(error) = &result.error {
+                Self::output_error(&format!("Error: {}", error.message));
+            } else if result.written {
+                writeln!(summary, "Updated: {}", result.path)?;
+            } else if result.changed && self.check {
+                writeln!(
+                    summary,
+                    "{}: unlinked scripture references found",
+                    result.path
+                )?;
+            }
+        }
+        writeln!(
+            summary,
+            "\nSummary: {} files processed, {changed} changed, {} unchanged, {failed} failed, {references_linked} {}",
+            results.len(),
+            results.len() - changed - failed,
+            self.changes_label()
+        )
+    }
+
+    /// What the count of changes in a file counts
+    const fn changes_label(&self) -> &'static str {
+        if self.processor.unlink {
+            "links removed"
+        } else {
+            "references linked"
+        }
+    }
+
+    /// Replace `path` with `content` via a temporary file in the same directory
+    fn write_atomically(path: &Path, content: &str) -> std::io::Result<()> {
+        Self::replace_atomically(path, |file| {
+            file.write_all(content.as_bytes())?;
+            Ok(true)
+        })?;
+        Ok(())
+    }
+
+    /// Fill a temporary file in the same directory as `path` and move it over `path`
+    ///
+    /// `write` returns whether the new content should be kept; if not, the
+    /// temporary file is discarded and `path` is left untouched.
+    fn replace_atomically<F>(path: &Path, write: F) -> std::io::Result<bool>
+    where
+        F: FnOnce(&mut fs::File) -> std::io::Result<bool>,
+    {
+        let parent = path.parent().unwrap_or_else(|| Path::new("."));
+        let mut temp_file = tempfile::Builder::new()
+            .prefix(".scripture-links.")
+            .suffix(".tmp")
+            .tempfile_in(parent)?;
+        if !write(temp_file.as_file_mut())? {
+            return Ok(false);
+        }
+        temp_file.as_file().sync_all()?;
+        // On Windows, fs::rename does not overwrite an existing file; remove first.
+        #[cfg(windows)]
+        fs::remove_file(path)?;
+        temp_file.persist(path).map_err(|e| e.error)?;
+        Ok(true)
+    }
+
+    fn report_diff(
+        &self,
+        file_path: &str,
+        original: &str,
+        processed: &str,
+    ) -> Result<(), CliError> {
+        let mut out = std::io::stdout().lock();
+        let hunks = diff_hunks(original, processed);
+        if self.json {
+            let response = DiffResponse {
+                success: true,
+                file: file_path.to_string(),
+                changed: !hunks.is_empty(),
+                hunks,
+            };
+            write_json(&response)?;
+        } else {
+            let color = std::io::stdout().is_terminal();
+            write!(out, "{}", render_unified_diff(file_path, &hunks, color))?;
+        }
+        Ok(())
+    }
+
+    fn report_check(&self, file_path: &str, would_change: bool) -> Result<(), CliError> {
+        let mut out = std::io::stdout().lock();
+        if self.json {
+            let response = CheckResponse {
+                success: !would_change,
+                files_checked: 1,
+                files_needing_changes: if would_change {
+                    vec![file_path.to_string()]
+                } else {
+                    Vec::new()
+                },
+                error: None,
+            };
+            write_json(&response)?;
+        } else if would_change {
+            writeln!(out, "{file_path}: unlinked scripture references found")?;
+        }
+        Ok(())
+    }
+
+    /// The language errors are shown in: the configured or given one, otherwise
+    /// the environment's, except that JSON output stays in English so it does
+    /// not depend on the locale
+    fn message_language(&self) -> Language {
+        self.message_lang.unwrap_or_else(|| {
+            if self.json {
+                Language::ENGLISH
+            } else {
+                language_from_env()
+            }
+        })
+    }
+
+    /// An error message in the language chosen for messages
+    fn error_message(&self, error: &ReferenceError) -> String {
+        error.render(self.message_language())
+    }
+
     #[allow(clippy::branches_sharing_code)]
     fn output_error(message: &str) {
         eprintln!("{message}");
     }
 }
+
+/// Pretty-print `value` as JSON on standard output
+fn write_json<T: serde::Serialize>(value: &T) -> Result<(), CliError> {
+    let mut out = std::io::stdout().lock();
+    writeln!(out, "{}", serde_json::to_string_pretty(value)?)?;
+    Ok(())
+}
+
+/// Whether `error` is a write to a closed pipe, as when the output is piped into `head`
+fn is_broken_pipe(error: &CliError) -> bool {
+    error
+        .downcast_ref::<std::io::Error>()
+        .is_some_and(|error| error.kind() == std::io::ErrorKind::BrokenPipe)
+}
+
+/// Stream `reader` through `processor` into `writer`, returning whether the
+/// output differs from the input
+fn process_compared<R: Read, W: Write>(
+    processor: &TextProcessor,
+    reader: R,
+    writer: W,
+) -> std::io::Result<bool> {
+    let comparison = RefCell::new(StreamComparison::default());
+    processor.process_stream(
+        BufReader::new(RecordedReader {
+            inner: reader,
+            comparison: &comparison,
+        }),
+        ComparedWriter {
+            inner: writer,
+            comparison: &comparison,
+        },
+    )?;
+    Ok(comparison.into_inner().changed())
+}
+
+/// Compares a stream's output to its input without holding either in full
+///
+/// Input is kept from when it is read until the output reaches it.
+#[derive(Default)]
+struct StreamComparison {
+    pending: VecDeque<u8>,
+    differs: bool,
+}
+
+impl StreamComparison {
+    fn read(&mut self, bytes: &[u8]) {
+        if !self.differs {
+            self.pending.extend(bytes);
+        }
+    }
+
+    fn written(&mut self, bytes: &[u8]) {
+        if self.differs {
+            return;
+        }
+        if bytes.len() > self.pending.len()
+            || !self.pending.drain(..bytes.len()).eq(bytes.iter().copied())
+        {
+            self.differs = true;
+            self.pending.clear();
+        }
+    }
+
+    /// Whether the output differs, once all input is read and all output written
+    fn changed(&self) -> bool {
+        self.differs || !self.pending.is_empty()
+    }
+}
+
+/// A reader that records what it reads in a [`StreamComparison`]
+struct RecordedReader<'a, R> {
+    inner: R,
+    comparison: &'a RefCell<StreamComparison>,
+}
+
+impl<R: Read> Read for RecordedReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.comparison.borrow_mut().read(&buf[..read]);
+        Ok(read)
+    }
+}
+
+/// A writer that checks what it writes against a [`StreamComparison`]
+struct ComparedWriter<'a, W> {
+    inner: W,
+    comparison: &'a RefCell<StreamComparison>,
+}
+
+impl<W: Write> Write for ComparedWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.comparison.borrow_mut().written(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+fn read_stdin() -> std::io::Result<String> {
+    let mut input = String::new();
+    std::io::stdin().read_to_string(&mut input)?;
+    Ok(input)
+}
@@ -0,0 +1,406 @@
+//! Settings read from `.scripture-links.toml` project files and the user config
+
+use crate::aliases::BookAliases;
+use crate::html::{DEFAULT_HTML_CLASS, HtmlAttributes, HtmlLink};
+use crate::language::Language;
+use crate::localized_names::check_display_language;
+use crate::messages::parse_message_language;
+use crate::text_processor::ProcessorOptions;
+use crate::types::OutputFormat;
+use crate::url_provider::UrlProviders;
+use crate::wikilink::{StudyHelpsTemplate, WikilinkTemplate, validate_path};
+use crate::works::CustomWorks;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// File name looked up in the working directory and each of its ancestors
+pub const PROJECT_CONFIG_FILE: &str = ".scripture-links.toml";
+
+/// Settings that persist between invocations
+///
+/// Every setting is optional so that a project config only needs to name what
+/// it changes; unset settings fall back to the user config and then to the
+/// built-in defaults.
+///
+/// ```toml
+/// format = "wikilink"
+/// study-helps = true
+/// lang = "spa"
+/// book-names = ["spa", "por"]
+/// display-lang = "spa"
+/// exclude = ["templates/**"]
+///
+/// [aliases]
+/// "D and C" = "dc"
+/// "Mos." = "mosiah"
+///
+/// [urls]
+/// bible = "biblegateway"
+///
+/// [html-attributes]
+/// target = "_blank"
+///
+/// [works.lof]
+/// name = "Lectures on Faith"
+/// abbreviations = ["LoF"]
+/// url = "https://example.org/lectures-on-faith/{chapter}#p{verse}"
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Config {
+    /// Link style: "markdown", "wikilink" or "html"
+    pub format: Option<OutputFormat>,
+    /// Also link Study Helps references such as "TG Faith"
+    pub study_helps: Option<bool>,
+    /// Language of the linked pages, as a three-letter code such as "spa"
+    pub lang: Option<Language>,
+    /// Link every reference in `lang`, even those written with book names of another language
+    pub force_lang: Option<bool>,
+    /// Language of book names in wikilinks and canonical link text
+    pub display_lang: Option<Language>,
+    /// Replace book names as written with their full names in `display-lang`
+    pub canonicalize: Option<bool>,
+    /// How wikilinks are written: "plain", "alias", "heading", "block" or a template
+    pub wikilink: Option<WikilinkTemplate>,
+    /// Note path prefix for wikilinks, such as `Scriptures/{work_name}/`
+    pub wikilink_path: Option<String>,
+    /// How Study Helps topics are written as wikilinks: "folder", "name",
+    /// "abbreviation" or a template
+    pub study_helps_wikilink: Option<StudyHelpsTemplate>,
+    /// Class of HTML anchors; empty for none
+    pub html_class: Option<String>,
+    /// Language of error messages; defaults to the one named by `LANG`
+    pub message_lang: Option<Language>,
+    /// Globs of files to process when walking directories
+    pub include: Option<Vec<String>>,
+    /// Globs of files to skip when walking directories
+    pub exclude: Option<Vec<String>>,
+    /// Number of files to process in parallel
+    pub jobs: Option<usize>,
+    /// Languages whose book names are recognized, such as "spa" for "Juan 3:16"
+    #[serde(default)]
+    pub book_names: Vec<Language>,
+    /// Extra book names, mapped to book slugs such as "mosiah" or "js-h";
+    /// the book name languages are enabled here when parsing
+    #[serde(default)]
+    pub aliases: BookAliases,
+    /// URL providers keyed by "default" or a standard work, see [`UrlProviders::set`]
+    #[serde(default)]
+    pub urls: UrlProviders,
+    /// Attributes of HTML anchors keyed by name, see [`HtmlAttributes`]
+    #[serde(default)]
+    pub html_attributes: HtmlAttributes,
+    /// Extra works keyed by id, see [`CustomWork`](crate::works::CustomWork)
+    #[serde(default)]
+    pub works: CustomWorks,
+}
+
+impl Config {
+    /// Parse the TOML content of a config file
+    ///
+    /// # Errors
+    /// Returns an error if the content is not valid TOML, contains unknown settings
+    /// declares an invalid work or asks for book names in an unsupported language.
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let mut config: Self = toml::from_str(content).map_err(|e| e.to_string())?;
+        for language in &config.book_names {
+            config.aliases.enable_language(*language)?;
+        }
+        if let Some(language) = config.display_lang {
+            check_display_language(language)?;
+        }
+        if let Some(path) = &config.wikilink_path {
+            validate_path(path)?;
+        }
+        if let Some(language) = config.message_lang {
+            parse_message_language(language.code())?;
+        }
+        Ok(config)
+    }
+
+    /// Read a config file
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or is not a valid config.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Error reading config file '{}': {e}", path.display()))?;
+        Self::parse(&content).map_err(|e| format!("Invalid config file '{}': {e}", path.display()))
+    }
+
+    /// Load the user config and the nearest project config above `start`
+    ///
+    /// Settings in the project config take precedence over the user config.
+    ///
+    /// # Errors
+    /// Returns an error if a config file that exists cannot be read or parsed.
+    pub fn discover(start: &Path) -> Result<Self, String> {
+        let user = user_config_path()
+            .filter(|path| path.is_file())
+            .map(|path| Self::load(&path))
+            .transpose()?
+            .unwrap_or_default();
+        let project = find_project_config(start)
+            .map(|path| Self::load(&path))
+            .transpose()?
+            .unwrap_or_default();
+        Ok(user.merge(project))
+    }
+
+    /// Combine two configs, preferring the settings of `other`
+    ///
+    /// Aliases, URL providers, HTML attributes, works and book name languages
+    /// from both configs are kept; `other` wins where both define the same alias,
+    /// standard work, attribute or work id or name.
+    #[must_use]
+    pub fn merge(self, other: Self) -> Self {
+        let mut aliases = self.aliases;
+        aliases.extend(other.aliases);
+        let mut urls = self.urls;
+        urls.extend(other.urls);
+        let mut html_attributes = self.html_attributes;
+        html_attributes.extend(other.html_attributes);
+        let mut works = self.works;
+        works.extend(other.works);
+        let mut book_names = self.book_names;
+        for language in other.book_names {
+            if !book_names.contains(&language) {
+                book_names.push(language);
+            }
+        }
+        Self {
+            format: other.format.or(self.format),
+            study_helps: other.study_helps.or(self.study_helps),
+            lang: other.lang.or(self.lang),
+            force_lang: other.force_lang.or(self.force_lang),
+            display_lang: other.display_lang.or(self.display_lang),
+            canonicalize: other.canonicalize.or(self.canonicalize),
+            wikilink: other.wikilink.or(self.wikilink),
+            wikilink_path: other.wikilink_path.or(self.wikilink_path),
+            study_helps_wikilink: other.study_helps_wikilink.or(self.study_helps_wikilink),
+            html_class: other.html_class.or(self.html_class),
+            message_lang: other.message_lang.or(self.message_lang),
+            book_names,
+            include: other.include.or(self.include),
+            exclude: other.exclude.or(self.exclude),
+            jobs: other.jobs.or(self.jobs),
+            aliases,
+            urls,
+            html_attributes,
+            works,
+        }
+    }
+
+    /// Processor options with the configured settings applied
+    #[must_use]
+    pub fn processor_options(&self) -> ProcessorOptions {
+        ProcessorOptions {
+            format: self.format.unwrap_or_default(),
+            include_study_helps: self.study_helps.unwrap_or_default(),
+            aliases: self.aliases.clone(),
+            urls: self.urls.clone(),
+            lang: self.lang.unwrap_or_default(),
+            force_lang: self.force_lang.unwrap_or_default(),
+            display_lang: self.display_lang.unwrap_or_default(),
+            canonicalize: self.canonicalize.unwrap_or_default(),
+            wikilink: self.wikilink.clone().unwrap_or_default(),
+            wikilink_path: self.wikilink_path.clone().unwrap_or_default(),
+            study_helps_wikilink: self.study_helps_wikilink.clone().unwrap_or_default(),
+            html: self.html_link(),
+            works: self.works.clone(),
+            convert: false,
+            unlink: false,
+        }
+    }
+
+    /// HTML anchors with the configured class and attributes
+    #[must_use]
+    pub fn html_link(&self) -> HtmlLink {
+        HtmlLink::new(
+            self.html_class.as_deref().unwrap_or(DEFAULT_HTML_CLASS),
+            self.html_attributes.clone(),
+        )
+    }
+}
+
+/// Path of the user-level config (e.g., `~/.config/scripture-links/config.toml` on Linux)
+#[must_use]
+pub fn user_config_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("scripture-links").join("config.toml"))
+}
+
+/// The nearest `.scripture-links.toml` in `start` or one of its ancestors
+#[must_use]
+pub fn find_project_config(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .map(|dir| dir.join(PROJECT_CONFIG_FILE))
+        .find(|path| path.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_settings() {
+        let config = Config::parse(
+            r#"
+            format = "wikilink"
+            study-helps = true
+            lang = "SPA"
+            force-lang = true
+            display-lang = "fra"
+            canonicalize = true
+            message-lang = "deu"
+            wikilink = "block"
+            wikilink-path = "Scriptures/{work_name}/"
+            study-helps-wikilink = "abbreviation"
+            exclude = ["templates/**"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.message_lang, Some(Language::GERMAN));
+        assert_eq!(config.format, Some(OutputFormat::Wikilink));
+        assert_eq!(config.study_helps, Some(true));
+        assert_eq!(config.exclude, Some(vec!["templates/**".to_string()]));
+        assert_eq!(config.include, None);
+
+        let options = config.processor_options();
+        assert_eq!(options.format, OutputFormat::Wikilink);
+        assert!(options.include_study_helps);
+        assert_eq!(options.lang, Language::SPANISH);
+        assert!(options.force_lang);
+        assert_eq!(options.display_lang, Language::FRENCH);
+        assert!(options.canonicalize);
+        assert_eq!(options.wikilink, WikilinkTemplate::new("block").unwrap());
+        assert_eq!(options.wikilink_path, "Scriptures/{work_name}/");
+        assert_eq!(
+            options.study_helps_wikilink,
+            StudyHelpsTemplate::new("abbreviation").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_html_settings() {
+        let user = Config::parse(
+            "html-class = \"ref\"\n[html-attributes]\ntarget = \"_blank\"\ntitle = \"\"",
+        )
+        .unwrap();
+        let project =
+            Config::parse("format = \"html\"\n[html-attributes]\ntarget = \"_self\"").unwrap();
+        let options = user.merge(project).processor_options();
+        assert_eq!(options.format, OutputFormat::Html);
+        let reference = crate::parse_scripture_reference("Alma 32:21").unwrap();
+        assert_eq!(
+            options.html.render(&reference, "Alma 32:21", "u", "Alma"),
+            "<a href=\"u\" class=\"ref\" data-book=\"alma\" data-chapter=\"32\" \
+             data-verses=\"21\" target=\"_self\">Alma 32:21</a>"
+        );
+    }
+
+    #[test]
+    fn test_rejects_unknown_settings_and_values() {
+        assert!(
+            Config::parse("fromat = \"wikilink\"")
+                .unwrap_err()
+                .contains("fromat")
+        );
+        assert!(Config::parse("format = \"pdf\"").is_err());
+        assert!(
+            Config::parse("[html-attributes]\nhref = \"{url}\"")
+                .unwrap_err()
+                .contains("cannot be changed")
+        );
+        assert!(Config::parse("lang = \"es\"").is_err());
+        assert!(
+            Config::parse("display-lang = \"ita\"")
+                .unwrap_err()
+                .contains("Book names are not available in 'ita'")
+        );
+        assert!(Config::parse("wikilink = \"fancy\"").is_err());
+        assert!(
+            Config::parse("wikilink-path = \"Notes/{chapter}/\"")
+                .unwrap_err()
+                .contains("unknown placeholder '{chapter}'")
+        );
+        assert!(
+            Config::parse("[aliases]\n\"Mos.\" = \"mosaic\"")
+                .unwrap_err()
+                .contains("Alias 'Mos.' maps to unknown book 'mosaic'")
+        );
+    }
+
+    #[test]
+    fn test_parse_works() {
+        let config = Config::parse(
+            r#"
+            [works.teachings-jf]
+            name = "Teachings of Joseph F. Smith"
+            abbreviations = ["TJFS"]
+            url = "https://example.org/teachings/jfs/{chapter}"
+            chapters = 46
+            "#,
+        )
+        .unwrap();
+        let work = config.works.get("teachings-jf").unwrap();
+        assert_eq!(work.id, "teachings-jf");
+        assert_eq!(work.chapters, Some(46));
+
+        let error = Config::parse(
+            "[works.lof]\nname = \"Lectures on Faith\"\nurl = \"https://example.org/{page}\"",
+        )
+        .unwrap_err();
+        assert!(error.contains("Invalid URL for work 'lof'"));
+    }
+
+    #[test]
+    fn test_merge_prefers_the_later_config() {
+        let user = Config::parse(
+            "format = \"wikilink\"\njobs = 2\n[aliases]\nNe = \"1-ne\"\nJSH = \"js-h\"",
+        )
+        .unwrap();
+        let project = Config::parse("format = \"markdown\"\n[aliases]\nNe = \"2-ne\"").unwrap();
+        let merged = user.merge(project);
+        assert_eq!(merged.format, Some(OutputFormat::Markdown));
+        assert_eq!(merged.jobs, Some(2));
+        assert_eq!(merged.aliases.get("Ne").unwrap().0, "2-ne");
+        assert_eq!(merged.aliases.get("JSH").unwrap().0, "js-h");
+    }
+
+    #[test]
+    fn test_book_names() {
+        let user = Config::parse("book-names = [\"spa\"]").unwrap();
+        let project = Config::parse("book-names = [\"por\", \"spa\"]").unwrap();
+        let merged = user.merge(project);
+        assert_eq!(merged.book_names, [Language::SPANISH, Language::PORTUGUESE]);
+        let options = merged.processor_options();
+        assert_eq!(options.aliases.languages(), merged.book_names);
+        assert_eq!(options.aliases.get_localized("João").unwrap().0.0, "john");
+
+        assert!(
+            Config::parse("book-names = [\"ita\"]")
+                .unwrap_err()
+                .contains("Book names are not available in 'ita'")
+        );
+    }
+
+    #[test]
+    fn test_finds_nearest_project_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("notes/daily");
+        fs::create_dir_all(&nested).unwrap();
+        assert_eq!(find_project_config(&nested), None);
+
+        fs::write(dir.path().join(PROJECT_CONFIG_FILE), "jobs = 1").unwrap();
+        fs::write(
+            dir.path().join("notes").join(PROJECT_CONFIG_FILE),
+            "jobs = 3",
+        )
+        .unwrap();
+        let found = find_project_config(&nested).unwrap();
+        assert_eq!(found, dir.path().join("notes").join(PROJECT_CONFIG_FILE));
+        assert_eq!(Config::load(&found).unwrap().jobs, Some(3));
+    }
+}
@@ -0,0 +1,223 @@
+//! Unified diffs between original and processed text
+
+use serde::{Deserialize, Serialize};
+use similar::{ChangeTag, TextDiff};
+use std::fmt::Write;
+
+/// Number of unchanged lines shown around each change
+const CONTEXT_LINES: usize = 3;
+
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const CYAN: &str = "\x1b[36m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+/// Kind of line in a diff hunk
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffLineKind {
+    Context,
+    Removed,
+    Added,
+}
+
+impl DiffLineKind {
+    const fn prefix(self) -> char {
+        match self {
+            Self::Context => ' ',
+            Self::Removed => '-',
+            Self::Added => '+',
+        }
+    }
+}
+
+/// A single line of a diff hunk, without its trailing newline
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiffLine {
+    pub kind: DiffLineKind,
+    pub content: String,
+    /// True when this is the last line of the text and it has no trailing newline
+    pub missing_newline: bool,
+}
+
+/// A contiguous region of changes with surrounding context (1-based line numbers)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiffHunk {
+    pub old_start: usize,
+    pub old_lines: usize,
+    pub new_start: usize,
+    pub new_lines: usize,
+    pub lines: Vec<DiffLine>,
+}
+
+/// Compute the line-based hunks that turn `original` into `processed`
+///
+/// # Examples
+///
+/// ```
+/// use scripture_links_lib::diff::diff_hunks;
+///
+/// let hunks = diff_hunks("See Alma 5:6.\n", "See [Alma 5:6](https://example.com).\n");
+/// assert_eq!(hunks.len(), 1);
+/// assert_eq!(hunks[0].old_start, 1);
+/// ```
+#[must_use]
+pub fn diff_hunks(original: &str, processed: &str) -> Vec<DiffHunk> {
+    let diff = TextDiff::from_lines(original, processed);
+
+    diff.grouped_ops(CONTEXT_LINES)
+        .iter()
+        .filter_map(|group| {
+            let first = group.first()?;
+            let last = group.last()?;
+            let old_range = first.old_range().start..last.old_range().end;
+            let new_range = first.new_range().start..last.new_range().end;
+
+            let lines = group
+                .iter()
+                .flat_map(|op| diff.iter_changes(op))
+                .map(|change| DiffLine {
+                    kind: match change.tag() {
+                        ChangeTag::Equal => DiffLineKind::Context,
+                        ChangeTag::Delete => DiffLineKind::Removed,
+                        ChangeTag::Insert => DiffLineKind::Added,
+                    },
+                    content: change.value().trim_end_matches(['\n', '\r']).to_string(),
+                    missing_newline: change.missing_newline(),
+                })
+                .collect();
+
+            Some(DiffHunk {
+                old_start: hunk_start(&old_range),
+                old_lines: old_range.len(),
+                new_start: hunk_start(&new_range),
+                new_lines: new_range.len(),
+                lines,
+            })
+        })
+        .collect()
+}
+
+/// Unified diff convention: empty ranges report the line before them
+const fn hunk_start(range: &std::ops::Range<usize>) -> usize {
+    if range.start == range.end {
+        range.start
+    } else {
+        range.start + 1
+    }
+}
+
+/// Render hunks as a unified diff for `path`, optionally with ANSI colors
+///
+/// Returns an empty string when there are no hunks.
+#[must_use]
+pub fn render_unified_diff(path: &str, hunks: &[DiffHunk], color: bool) -> String {
+    let mut output = String::new();
+    if hunks.is_empty() {
+        return output;
+    }
+
+    let (bold, red, green, cyan, reset) = if color {
+        (BOLD, RED, GREEN, CYAN, RESET)
+    } else {
+        ("", "", "", "", "")
+    };
+
+    // Writing to a String cannot fail
+    let _ = writeln!(output, "{bold}--- {path}{reset}");
+    let _ = writeln!(output, "{bold}+++ {path}{reset}");
+    for hunk in hunks {
+        let _ = writeln!(
+            output,
+            "{cyan}@@ -{},{} +{},{} @@{reset}",
+            hunk.old_start, hunk.old_lines, hunk.new_start, hunk.new_lines
+        );
+        for line in &hunk.lines {
+            let line_color = match line.kind {
+                DiffLineKind::Context => "",
+                DiffLineKind::Removed => red,
+                DiffLineKind::Added => green,
+            };
+            let line_reset = if line_color.is_empty() { "" } else { reset };
+            let _ = writeln!(
+                output,
+                "{line_color}{}{}{line_reset}",
+                line.kind.prefix(),
+                line.content
+            );
+            if line.missing_newline {
+                output.push_str("\\ No newline at end of file\n");
+            }
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_hunks_for_identical_text() {
+        assert!(diff_hunks("Same text.\n", "Same text.\n").is_empty());
+        assert_eq!(render_unified_diff("notes.md", &[], false), "");
+    }
+
+    #[test]
+    fn test_single_changed_line_with_context() {
+        let original = "one\ntwo\nSee Alma 5:6.\nfour\n";
+        let processed = "one\ntwo\nSee [Alma 5:6](url).\nfour\n";
+        let hunks = diff_hunks(original, processed);
+
+        assert_eq!(hunks.len(), 1);
+        let hunk = &hunks[0];
+        assert_eq!((hunk.old_start, hunk.old_lines), (1, 4));
+        assert_eq!((hunk.new_start, hunk.new_lines), (1, 4));
+        assert!(hunk.lines.contains(&DiffLine {
+            kind: DiffLineKind::Removed,
+            content: "See Alma 5:6.".to_string(),
+            missing_newline: false,
+        }));
+        assert!(hunk.lines.contains(&DiffLine {
+            kind: DiffLineKind::Added,
+            content: "See [Alma 5:6](url).".to_string(),
+            missing_newline: false,
+        }));
+    }
+
+    #[test]
+    fn test_distant_changes_produce_separate_hunks() {
+        let mut original: Vec<String> = (1..=20).map(|n| format!("line {n}")).collect();
+        let mut processed = original.clone();
+        original[1] = "Alma 5:6".to_string();
+        processed[1] = "[Alma 5:6](url)".to_string();
+        original[18] = "Moro 10:4".to_string();
+        processed[18] = "[Moro 10:4](url)".to_string();
+
+        let hunks = diff_hunks(&original.join("\n"), &processed.join("\n"));
+        assert_eq!(hunks.len(), 2);
+        assert_eq!(hunks[1].old_start, 16);
+    }
+
+    #[test]
+    fn test_render_plain_unified_diff() {
+        let hunks = diff_hunks("See Alma 5:6.", "See [Alma 5:6](url).");
+        let rendered = render_unified_diff("notes.md", &hunks, false);
+
+        assert_eq!(
+            rendered,
+            "--- notes.md\n+++ notes.md\n@@ -1,1 +1,1 @@\n-See Alma 5:6.\n\\ No newline at end of file\n+See [Alma 5:6](url).\n\\ No newline at end of file\n"
+        );
+    }
+
+    #[test]
+    fn test_render_colored_diff() {
+        let hunks = diff_hunks("Alma 5:6\n", "[Alma 5:6](url)\n");
+        let rendered = render_unified_diff("notes.md", &hunks, true);
+
+        assert!(rendered.contains("\x1b[31m-Alma 5:6\x1b[0m"));
+        assert!(rendered.contains("\x1b[32m+[Alma 5:6](url)\x1b[0m"));
+        assert!(rendered.contains("\x1b[36m@@ -1,1 +1,1 @@\x1b[0m"));
+    }
+}
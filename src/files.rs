@@ -0,0 +1,279 @@
+//! Discovery of the files to process from paths, directories and globs
+
+use ignore::gitignore::GitignoreBuilder;
+use ignore::overrides::{Override, OverrideBuilder};
+use ignore::{Match, WalkBuilder};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Globs used when walking directories and no `--include` glob is given
+pub const DEFAULT_INCLUDE_GLOBS: &[&str] = &["*.md", "*.markdown", "*.txt"];
+
+/// Per-directory ignore file honored in addition to `.gitignore` and `.ignore`
+pub const IGNORE_FILENAME: &str = ".scripture-links-ignore";
+
+/// Expand paths into the sorted list of files to process
+///
+/// Directories are walked recursively, skipping hidden entries and anything
+/// matched by `.gitignore`, `.ignore` or `.scripture-links-ignore` files.
+/// Files found while walking must match one of `include` (or the Markdown and
+/// text defaults when `include` is empty) and none of `exclude`. Files named
+/// explicitly are always returned.
+///
+/// # Errors
+/// Returns an error if a glob is invalid or a path cannot be read.
+pub fn collect_files(
+    paths: &[String],
+    include: &[String],
+    exclude: &[String],
+) -> Result<Vec<PathBuf>, String> {
+    let mut files = Vec::new();
+    for path in paths {
+        // Globs are applied after the walk: as walker overrides, include globs
+        // would take precedence over ignore files.
+        let globs = build_overrides(path, include, exclude)?;
+        let walker = WalkBuilder::new(path)
+            .require_git(false)
+            .add_custom_ignore_filename(IGNORE_FILENAME)
+            .build();
+
+        for entry in walker {
+            let entry = entry.map_err(|e| e.to_string())?;
+            let is_file = entry
+                .file_type()
+                .is_some_and(|file_type| file_type.is_file());
+            let explicit = entry.depth() == 0;
+            if is_file && (explicit || !globs.matched(entry.path(), false).is_ignore()) {
+                files.push(entry.into_path());
+            }
+        }
+    }
+    files.sort();
+    files.dedup();
+    Ok(files)
+}
+
+/// Whether [`collect_files`] would return `path` when walking `root`, found
+/// without walking the rest of the tree
+///
+/// `path` is the file as the walk would name it, `root` joined with its
+/// relative path. It must exist, not be hidden below `root`, match the globs,
+/// and not be ignored by an ignore file in its directory or any parent.
+///
+/// # Errors
+/// Returns an error if a glob is invalid.
+pub fn would_collect(
+    root: &str,
+    path: &Path,
+    include: &[String],
+    exclude: &[String],
+) -> Result<bool, String> {
+    let Ok(relative) = path.strip_prefix(root) else {
+        return Ok(false);
+    };
+    if !path.is_file() {
+        return Ok(false);
+    }
+    if relative.as_os_str().is_empty() {
+        // Files named explicitly are always returned
+        return Ok(true);
+    }
+    let hidden = relative
+        .components()
+        .any(|component| component.as_os_str().to_string_lossy().starts_with('.'));
+    if hidden
+        || build_overrides(root, include, exclude)?
+            .matched(path, false)
+            .is_ignore()
+    {
+        return Ok(false);
+    }
+    Ok(!is_ignored(path))
+}
+
+/// Whether an ignore file in the directory of `path` or a parent ignores it,
+/// the nearest matching rule winning as it does when walking
+fn is_ignored(path: &Path) -> bool {
+    let Ok(path) = fs::canonicalize(path) else {
+        return false;
+    };
+    for dir in path.ancestors().skip(1) {
+        // In the order the walker gives them precedence
+        for name in [IGNORE_FILENAME, ".ignore", ".gitignore"] {
+            let file = dir.join(name);
+            if !file.is_file() {
+                continue;
+            }
+            let mut builder = GitignoreBuilder::new(dir);
+            if builder.add(&file).is_some() {
+                continue;
+            }
+            let Ok(matcher) = builder.build() else {
+                continue;
+            };
+            match matcher.matched_path_or_any_parents(&path, false) {
+                Match::Ignore(_) => return true,
+                Match::Whitelist(_) => return false,
+                Match::None => {}
+            }
+        }
+    }
+    false
+}
+
+/// Globs containing a `/` are anchored at the walked directory
+fn build_overrides(root: &str, include: &[String], exclude: &[String]) -> Result<Override, String> {
+    let mut overrides = OverrideBuilder::new(root);
+    let include_globs: Vec<&str> = if include.is_empty() {
+        DEFAULT_INCLUDE_GLOBS.to_vec()
+    } else {
+        include.iter().map(String::as_str).collect()
+    };
+    for glob in include_globs {
+        overrides
+            .add(glob)
+            .map_err(|e| format!("Invalid include glob '{glob}': {e}"))?;
+    }
+    for glob in exclude {
+        overrides
+            .add(&format!("!{glob}"))
+            .map_err(|e| format!("Invalid exclude glob '{glob}': {e}"))?;
+    }
+    overrides.build().map_err(|e| format!("Invalid glob: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::Path;
+
+    fn write(root: &Path, relative: &str) {
+        let path = root.join(relative);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, "See Alma 5:6.").unwrap();
+    }
+
+    fn relative_names(root: &Path, files: &[PathBuf]) -> Vec<String> {
+        files
+            .iter()
+            .map(|f| {
+                f.strip_prefix(root)
+                    .unwrap()
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_walks_directories_with_default_globs() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(root, "a.md");
+        write(root, "notes/b.markdown");
+        write(root, "notes/deep/c.txt");
+        write(root, "image.png");
+        write(root, ".obsidian/workspace.md");
+
+        let files = collect_files(&[root.to_string_lossy().to_string()], &[], &[]).unwrap();
+        assert_eq!(
+            relative_names(root, &files),
+            vec!["a.md", "notes/b.markdown", "notes/deep/c.txt"]
+        );
+    }
+
+    #[test]
+    fn test_include_and_exclude_globs() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(root, "a.md");
+        write(root, "drafts/b.md");
+        write(root, "c.txt");
+
+        let files = collect_files(
+            &[root.to_string_lossy().to_string()],
+            &["*.md".to_string()],
+            &["drafts/**".to_string()],
+        )
+        .unwrap();
+        assert_eq!(relative_names(root, &files), vec!["a.md"]);
+    }
+
+    #[test]
+    fn test_honors_ignore_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(root, "keep.md");
+        write(root, "archive/old.md");
+        write(root, "private.md");
+        fs::write(root.join(".gitignore"), "archive/\n").unwrap();
+        fs::write(root.join(IGNORE_FILENAME), "private.md\n").unwrap();
+
+        let files = collect_files(&[root.to_string_lossy().to_string()], &[], &[]).unwrap();
+        assert_eq!(relative_names(root, &files), vec!["keep.md"]);
+    }
+
+    #[test]
+    fn test_explicit_files_are_kept_and_deduplicated() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(root, "script.rs");
+        let path = root.join("script.rs").to_string_lossy().to_string();
+
+        let files = collect_files(&[path.clone(), path], &[], &[]).unwrap();
+        assert_eq!(relative_names(root, &files), vec!["script.rs"]);
+    }
+
+    #[test]
+    fn test_would_collect_agrees_with_the_walk() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for relative in [
+            "keep.md",
+            "image.png",
+            "drafts/wip.md",
+            "archive/old.md",
+            "archive/pinned.md",
+            "notes/private.md",
+            "notes/deep/kept.txt",
+            ".obsidian/workspace.md",
+        ] {
+            write(root, relative);
+        }
+        fs::write(root.join(".gitignore"), "archive/*\n!archive/pinned.md\n").unwrap();
+        fs::write(root.join("notes").join(IGNORE_FILENAME), "private.md\n").unwrap();
+
+        let root_arg = root.to_string_lossy().to_string();
+        let exclude = ["drafts/**".to_string()];
+        let collected = collect_files(std::slice::from_ref(&root_arg), &[], &exclude).unwrap();
+        assert_eq!(
+            relative_names(root, &collected),
+            vec!["archive/pinned.md", "keep.md", "notes/deep/kept.txt"]
+        );
+        for relative in [
+            "keep.md",
+            "image.png",
+            "drafts/wip.md",
+            "archive/old.md",
+            "archive/pinned.md",
+            "notes/private.md",
+            "notes/deep/kept.txt",
+            ".obsidian/workspace.md",
+            "deleted.md",
+        ] {
+            let path = root.join(relative);
+            assert_eq!(
+                would_collect(&root_arg, &path, &[], &exclude).unwrap(),
+                collected.contains(&path),
+                "{relative}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_glob_is_an_error() {
+        let error = collect_files(&[".".to_string()], &["[".to_string()], &[]).unwrap_err();
+        assert!(error.contains("Invalid include glob"));
+    }
+}
@@ -0,0 +1,402 @@
+//! HTML anchors for publishing links on web pages, with data attributes that
+//! describe the reference for scripts and stylesheets
+
+use crate::abbreviations::study_help_name;
+use crate::types::ScriptureReference;
+use crate::wikilink::validate_placeholders;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// Placeholders that may appear in an attribute template
+pub const HTML_PLACEHOLDERS: &[&str] = &[
+    "url",
+    "work",
+    "work_name",
+    "book",
+    "book_name",
+    "chapter",
+    "verse",
+    "end_verse",
+    "verses",
+    "topic",
+    "reference",
+    "text",
+];
+
+/// Class of the anchors when none is chosen
+pub const DEFAULT_HTML_CLASS: &str = "scripture-ref";
+
+/// Attributes written after the class unless overridden
+const DEFAULT_ATTRIBUTES: &[(&str, &str)] = &[
+    ("data-book", "{book}"),
+    ("data-chapter", "{chapter}"),
+    ("data-verses", "{verses}"),
+    ("title", "{reference}"),
+];
+
+/// Attributes the link sets itself: the URL and the class given to [`HtmlLink::new`]
+const RESERVED_ATTRIBUTES: &[&str] = &["href", "class"];
+
+/// Escape `text` for use in HTML content and quoted attribute values
+///
+/// # Examples
+///
+/// ```
+/// use scripture_links_lib::html::escape_html;
+///
+/// assert_eq!(escape_html("a < b & \"c\""), "a &lt; b &amp; &quot;c&quot;");
+/// ```
+#[must_use]
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Reverse [`escape_html`], for reading back the text and URLs of anchors
+#[must_use]
+pub fn unescape_html(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Attributes added to or replacing the default ones, each a template
+///
+/// The defaults are `data-book="{book}"`, `data-chapter="{chapter}"`,
+/// `data-verses="{verses}"` and `title="{reference}"`. Attributes whose value
+/// renders empty are left out, so an empty template removes a default one.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "BTreeMap<String, String>")]
+pub struct HtmlAttributes {
+    attributes: Vec<(String, String)>,
+}
+
+impl HtmlAttributes {
+    /// Set attribute `name` to `template`
+    ///
+    /// # Errors
+    /// Returns an error if `name` is not a valid attribute name, is `href` or
+    /// `class`, or `template` uses an unknown placeholder.
+    pub fn set(&mut self, name: &str, template: &str) -> Result<(), String> {
+        let name = name.trim().to_ascii_lowercase();
+        let valid = name.starts_with(|c: char| c.is_ascii_alphabetic())
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ':' | '.'));
+        if !valid {
+            return Err(format!("Invalid HTML attribute name '{name}'"));
+        }
+        if RESERVED_ATTRIBUTES.contains(&name.as_str()) {
+            return Err(format!(
+                "The '{name}' attribute cannot be changed; the class is set apart from the attributes"
+            ));
+        }
+        validate_placeholders(template, HTML_PLACEHOLDERS)?;
+        self.attributes.retain(|(existing, _)| *existing != name);
+        self.attributes.push((name, template.to_string()));
+        Ok(())
+    }
+
+    /// Parse a `NAME=TEMPLATE` command-line value, e.g. "target=_blank"
+    ///
+    /// # Errors
+    /// Returns an error if the value has no `=` or is not a valid attribute.
+    pub fn parse_arg(arg: &str) -> Result<Self, String> {
+        let (name, template) = arg
+            .split_once('=')
+            .ok_or_else(|| format!("Expected NAME=VALUE for an HTML attribute, got '{arg}'"))?;
+        let mut attributes = Self::default();
+        attributes.set(name, template)?;
+        Ok(attributes)
+    }
+
+    /// Take the attributes set in `other`, replacing those with the same name
+    pub fn extend(&mut self, other: Self) {
+        for (name, template) in other.attributes {
+            self.attributes.retain(|(existing, _)| *existing != name);
+            self.attributes.push((name, template));
+        }
+    }
+
+    /// The default attributes with these applied, in order
+    fn resolved(&self) -> Vec<(&str, &str)> {
+        let mut resolved: Vec<(&str, &str)> = DEFAULT_ATTRIBUTES
+            .iter()
+            .map(|&(name, template)| {
+                let template = self
+                    .attributes
+                    .iter()
+                    .find(|(own, _)| own == name)
+                    .map_or(template, |(_, own)| own.as_str());
+                (name, template)
+            })
+            .collect();
+        resolved.extend(
+            self.attributes
+                .iter()
+                .filter(|(name, _)| {
+                    !DEFAULT_ATTRIBUTES
+                        .iter()
+                        .any(|(default, _)| default == name)
+                })
+                .map(|(name, template)| (name.as_str(), template.as_str())),
+        );
+        resolved
+    }
+}
+
+impl TryFrom<BTreeMap<String, String>> for HtmlAttributes {
+    type Error = String;
+
+    fn try_from(map: BTreeMap<String, String>) -> Result<Self, String> {
+        let mut attributes = Self::default();
+        for (name, template) in &map {
+            attributes.set(name, template)?;
+        }
+        Ok(attributes)
+    }
+}
+
+/// How a reference is written as an HTML anchor
+///
+/// Attribute placeholders are `{url}`, `{work}` (e.g., "bofm"), `{work_name}`
+/// (e.g., "Book of Mormon"), `{book}` (e.g., "alma"), `{book_name}` (e.g.,
+/// "Alma", in the display language), `{chapter}`, `{verse}`, `{end_verse}`,
+/// `{verses}` (e.g., "3" or "3-5"), `{topic}` (for Study Helps), `{reference}`
+/// (e.g., "Alma 32:21" or "Topical Guide: Faith") and `{text}` (the link text).
+/// Chapter and verse placeholders are empty for Study Helps topics.
+///
+/// # Examples
+///
+/// ```
+/// use scripture_links_lib::html::HtmlLink;
+/// use scripture_links_lib::parse_scripture_reference;
+///
+/// let reference = parse_scripture_reference("Alma 32:21").unwrap();
+/// assert_eq!(
+///     HtmlLink::default().render(&reference, "Alma 32:21", "https://example.org/?a=1&b=2", "Alma"),
+///     "<a href=\"https://example.org/?a=1&amp;b=2\" class=\"scripture-ref\" data-book=\"alma\" \
+///      data-chapter=\"32\" data-verses=\"21\" title=\"Alma 32:21\">Alma 32:21</a>"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HtmlLink {
+    class: String,
+    attributes: HtmlAttributes,
+}
+
+impl HtmlLink {
+    /// Anchors with `class` (none if empty) and `attributes` over the default ones
+    #[must_use]
+    pub fn new(class: &str, attributes: HtmlAttributes) -> Self {
+        Self {
+            class: class.trim().to_string(),
+            attributes,
+        }
+    }
+
+    #[must_use]
+    pub fn class(&self) -> &str {
+        &self.class
+    }
+
+    #[must_use]
+    pub const fn attributes(&self) -> &HtmlAttributes {
+        &self.attributes
+    }
+
+    pub fn set_class(&mut self, class: &str) {
+        class.trim().clone_into(&mut self.class);
+    }
+
+    /// Add or replace attributes, as [`HtmlAttributes::extend`]
+    pub fn extend_attributes(&mut self, attributes: HtmlAttributes) {
+        self.attributes.extend(attributes);
+    }
+
+    /// Write `scripture` as an anchor to `url` showing `text`, with `book_name`
+    /// as the name of its book, escaping all values
+    #[must_use]
+    pub fn render(
+        &self,
+        scripture: &ScriptureReference,
+        text: &str,
+        url: &str,
+        book_name: &str,
+    ) -> String {
+        let mut html = format!("<a href=\"{}\"", escape_html(url));
+        if !self.class.is_empty() {
+            let _ = write!(html, " class=\"{}\"", escape_html(&self.class));
+        }
+        for (name, template) in self.attributes.resolved() {
+            let value = render_template(template, scripture, text, url, book_name);
+            if !value.is_empty() {
+                let _ = write!(html, " {name}=\"{}\"", escape_html(&value));
+            }
+        }
+        let _ = write!(html, ">{}</a>", escape_html(text));
+        html
+    }
+}
+
+impl Default for HtmlLink {
+    fn default() -> Self {
+        Self::new(DEFAULT_HTML_CLASS, HtmlAttributes::default())
+    }
+}
+
+/// Values of the placeholders that depend on the kind of reference
+#[derive(Default)]
+struct Parts {
+    book_name: String,
+    reference: String,
+    chapter: String,
+    verse: String,
+    end_verse: String,
+    verses: String,
+}
+
+impl Parts {
+    /// A Study Helps topic has no chapter or verses
+    fn topic(scripture: &ScriptureReference, topic: &str) -> Self {
+        let help_name = study_help_name(&scripture.book).unwrap_or(&scripture.book);
+        Self {
+            book_name: help_name.to_string(),
+            reference: format!("{help_name}: {topic}"),
+            ..Self::default()
+        }
+    }
+
+    fn verses(scripture: &ScriptureReference, book_name: &str) -> Self {
+        let verses = scripture.verse_end.map_or_else(
+            || scripture.verse_start.to_string(),
+            |end| format!("{}-{end}", scripture.verse_start),
+        );
+        Self {
+            reference: format!("{book_name} {}:{verses}", scripture.chapter),
+            book_name: book_name.to_string(),
+            chapter: scripture.chapter.to_string(),
+            verse: scripture.verse_start.to_string(),
+            end_verse: scripture
+                .verse_end
+                .unwrap_or(scripture.verse_start)
+                .to_string(),
+            verses,
+        }
+    }
+}
+
+#[allow(clippy::literal_string_with_formatting_args)]
+fn render_template(
+    template: &str,
+    scripture: &ScriptureReference,
+    text: &str,
+    url: &str,
+    book_name: &str,
+) -> String {
+    let parts = scripture.topic.as_deref().map_or_else(
+        || Parts::verses(scripture, book_name),
+        |topic| Parts::topic(scripture, topic),
+    );
+    template
+        .replace("{url}", url)
+        .replace("{work_name}", scripture.standard_work.display_name())
+        .replace("{work}", scripture.standard_work.to_url_path())
+        .replace("{book_name}", &parts.book_name)
+        .replace("{book}", &scripture.book)
+        .replace("{chapter}", &parts.chapter)
+        .replace("{verse}", &parts.verse)
+        .replace("{end_verse}", &parts.end_verse)
+        .replace("{verses}", &parts.verses)
+        .replace("{topic}", scripture.topic.as_deref().unwrap_or_default())
+        .replace("{reference}", &parts.reference)
+        .replace("{text}", text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_scripture_reference;
+    use crate::types::StandardWork;
+
+    #[test]
+    fn test_attributes_override_and_remove_defaults() {
+        let reference = parse_scripture_reference("Gen. 1:1-3").unwrap();
+        let mut attributes = HtmlAttributes::parse_arg("title=").unwrap();
+        attributes.extend(HtmlAttributes::parse_arg("target=_blank").unwrap());
+        attributes.extend(HtmlAttributes::parse_arg("data-book={book_name}").unwrap());
+        let link = HtmlLink::new("", attributes);
+        assert_eq!(
+            link.render(&reference, "Gen. 1:1-3", "https://example.org", "Génesis"),
+            "<a href=\"https://example.org\" data-book=\"Génesis\" data-chapter=\"1\" \
+             data-verses=\"1-3\" target=\"_blank\">Gen. 1:1-3</a>"
+        );
+    }
+
+    #[test]
+    fn test_values_are_escaped() {
+        let reference = parse_scripture_reference("D&C 4:2").unwrap();
+        let link = HtmlLink::new(
+            "ref \"x\"",
+            HtmlAttributes::parse_arg("data-note=<{text}>").unwrap(),
+        );
+        assert_eq!(
+            link.render(
+                &reference,
+                "D&C 4:2",
+                "https://example.org/?a=1&b='2'",
+                "D&C"
+            ),
+            "<a href=\"https://example.org/?a=1&amp;b=&#39;2&#39;\" class=\"ref &quot;x&quot;\" \
+             data-book=\"dc\" data-chapter=\"4\" data-verses=\"2\" title=\"D&amp;C 4:2\" \
+             data-note=\"&lt;D&amp;C 4:2&gt;\">D&amp;C 4:2</a>"
+        );
+        assert_eq!(unescape_html(&escape_html("<a & 'b'>")), "<a & 'b'>");
+    }
+
+    #[test]
+    fn test_study_helps_leave_out_chapter_and_verses() {
+        let reference = ScriptureReference {
+            book: "tg".to_string(),
+            chapter: 1,
+            verse_start: 1,
+            verse_end: None,
+            standard_work: StandardWork::StudyHelps,
+            topic: Some("Faith".to_string()),
+        };
+        assert_eq!(
+            HtmlLink::default().render(&reference, "TG Faith", "https://example.org", "tg"),
+            "<a href=\"https://example.org\" class=\"scripture-ref\" data-book=\"tg\" \
+             title=\"Topical Guide: Faith\">TG Faith</a>"
+        );
+    }
+
+    #[test]
+    fn test_invalid_attributes() {
+        assert!(HtmlAttributes::parse_arg("target").is_err());
+        assert!(HtmlAttributes::parse_arg("on click=x").is_err());
+        assert!(
+            HtmlAttributes::parse_arg("href={url}")
+                .unwrap_err()
+                .contains("cannot be changed")
+        );
+        assert!(
+            HtmlAttributes::parse_arg("data-x={page}")
+                .unwrap_err()
+                .contains("unknown placeholder '{page}'")
+        );
+    }
+}
@@ -0,0 +1,347 @@
+//! Chapter and verse facts about a reference, such as verse counts and neighboring chapters
+
+use crate::abbreviations::book_slug_to_display_name;
+use crate::aliases::BookAliases;
+use crate::books::book_catalog;
+use crate::language::Language;
+use crate::messages::{MessageId, ReferenceError};
+use crate::parser::lookup_book;
+use crate::scripture_data::{self, get_book_info};
+use crate::types::StandardWork;
+use crate::url_provider::{ChurchUrlProvider, UrlProvider};
+use crate::works::CustomWorks;
+use crate::{ScriptureReference, parse_scripture_reference_with_aliases};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+
+/// A reference to a whole chapter, such as "Alma 32"
+static CHAPTER_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(.+?)\s*(\d+)$").unwrap());
+
+/// A chapter next to the one asked about
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChapterLocation {
+    pub book: String,
+    pub name: String,
+    pub chapter: u32,
+    pub url: String,
+}
+
+/// Facts about the chapter and verses of a reference
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReferenceInfo {
+    pub book: String,
+    pub name: String,
+    pub standard_work: StandardWork,
+    pub chapter: u32,
+    /// Number of chapters in the book
+    pub chapters_in_book: u32,
+    /// Number of verses in the chapter, which is also its last verse
+    pub verses_in_chapter: u32,
+    /// Requested verses, or `None` when the reference names a whole chapter
+    pub verse_start: Option<u32>,
+    pub verse_end: Option<u32>,
+    /// Verses covered by the reference (the whole chapter when no verse is given)
+    pub verses_in_range: u32,
+    /// Previous chapter in the same standard work, crossing into the previous book if needed
+    pub previous_chapter: Option<ChapterLocation>,
+    /// Next chapter in the same standard work, crossing into the next book if needed
+    pub next_chapter: Option<ChapterLocation>,
+    pub chapter_url: String,
+}
+
+/// Describe the chapter and verses of a reference
+///
+/// Accepts anything [`parse_scripture_reference`] accepts, plus whole
+/// chapters such as "Alma 32".
+///
+/// # Errors
+/// Returns an error if the reference cannot be parsed, names a chapter or verse
+/// that does not exist, or names a book without verse data.
+///
+/// # Panics
+/// Panics if the internal regex pattern is invalid (should never happen).
+///
+/// # Examples
+///
+/// ```
+/// use scripture_links_lib::info::reference_info;
+///
+/// let info = reference_info("Ether 12").unwrap();
+/// assert_eq!(info.verses_in_chapter, 41);
+/// assert_eq!(info.next_chapter.unwrap().chapter, 13);
+///
+/// let info = reference_info("Alma 32:21-23").unwrap();
+/// assert_eq!(info.verses_in_range, 3);
+/// ```
+pub fn reference_info(reference: &str) -> Result<ReferenceInfo, ReferenceError> {
+    reference_info_with_aliases(reference, &BookAliases::default())
+}
+
+/// Describe the chapter and verses of a reference, looking up user-defined book aliases first
+///
+/// # Errors
+/// Returns an error if the reference cannot be parsed, names a chapter or verse
+/// that does not exist, or names a book without verse data.
+///
+/// # Panics
+/// Panics if the internal regex pattern is invalid (should never happen).
+pub fn reference_info_with_aliases(
+    reference: &str,
+    aliases: &BookAliases,
+) -> Result<ReferenceInfo, ReferenceError> {
+    reference_info_with_lang(reference, aliases, Language::ENGLISH)
+}
+
+/// Describe the chapter and verses of a reference, with chapter URLs in `lang`
+///
+/// # Errors
+/// Returns an error if the reference cannot be parsed, names a chapter or verse
+/// that does not exist, or names a book without verse data.
+///
+/// # Panics
+/// Panics if the internal regex pattern is invalid (should never happen).
+pub fn reference_info_with_lang(
+    reference: &str,
+    aliases: &BookAliases,
+    lang: Language,
+) -> Result<ReferenceInfo, ReferenceError> {
+    reference_info_with_provider(reference, aliases, lang, &ChurchUrlProvider)
+}
+
+/// Describe the chapter and verses of a reference, with chapter URLs from `provider` in `lang`
+///
+/// # Errors
+/// Returns an error if the reference cannot be parsed, names a chapter or verse
+/// that does not exist, or names a book without verse data.
+///
+/// # Panics
+/// Panics if the internal regex pattern is invalid (should never happen).
+///
+/// # Examples
+///
+/// ```
+/// use scripture_links_lib::Language;
+/// use scripture_links_lib::aliases::BookAliases;
+/// use scripture_links_lib::info::reference_info_with_provider;
+/// use scripture_links_lib::url_provider::UrlProviders;
+///
+/// let providers = UrlProviders::parse_arg("biblegateway").unwrap();
+/// let info =
+///     reference_info_with_provider("John 3", &BookAliases::default(), Language::ENGLISH, &providers)
+///         .unwrap();
+/// assert!(info.chapter_url.starts_with("https://www.biblegateway.com/passage/?search=John+3:1"));
+/// ```
+pub fn reference_info_with_provider(
+    reference: &str,
+    aliases: &BookAliases,
+    lang: Language,
+    provider: &dyn UrlProvider,
+) -> Result<ReferenceInfo, ReferenceError> {
+    let reference = reference.trim();
+    let (book, standard_work, chapter, verses) = if reference.contains(':') {
+        let ScriptureReference {
+            book,
+            standard_work,
+            chapter,
+            verse_start,
+            verse_end,
+            ..
+        } = parse_scripture_reference_with_aliases(reference, aliases)?;
+        (book, standard_work, chapter, Some((verse_start, verse_end)))
+    } else {
+        let captures = CHAPTER_RE.captures(reference).ok_or_else(|| {
+            ReferenceError::new(
+                MessageId::InvalidChapterReferenceFormat,
+                &[("reference", reference)],
+            )
+        })?;
+        let chapter: u32 = captures[2].parse().map_err(|_| {
+            ReferenceError::new(MessageId::InvalidChapterNumber, &[("reference", reference)])
+        })?;
+        // Custom works have no chapter data to report, so only built-in books are looked up
+        let (book, standard_work) = lookup_book(&captures[1], aliases, &CustomWorks::default())?;
+        scripture_data::check_chapter_range(&book, chapter)?;
+        (book, standard_work, chapter, None)
+    };
+
+    let book_info = get_book_info(&book).ok_or_else(|| {
+        let name = book_slug_to_display_name(&book).unwrap_or(&book);
+        ReferenceError::new(MessageId::NoVerseData, &[("book", name)])
+    })?;
+    let verses_in_chapter = book_info.chapters[chapter as usize - 1];
+    let verses_in_range = match verses {
+        Some((start, Some(end))) => end - start + 1,
+        Some((_, None)) => 1,
+        None => verses_in_chapter,
+    };
+
+    Ok(ReferenceInfo {
+        name: book_slug_to_display_name(&book)
+            .unwrap_or(book_info.name)
+            .to_string(),
+        chapters_in_book: chapter_count(&book),
+        verses_in_chapter,
+        verse_start: verses.map(|(start, _)| start),
+        verse_end: verses.and_then(|(_, end)| end),
+        verses_in_range,
+        previous_chapter: previous_chapter(&book, &standard_work, chapter, lang, provider),
+        next_chapter: next_chapter(&book, &standard_work, chapter, lang, provider),
+        chapter_url: provider.chapter_url(&standard_work, &book, chapter, lang),
+        book,
+        standard_work,
+        chapter,
+    })
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn chapter_count(book: &str) -> u32 {
+    get_book_info(book).map_or(0, |info| info.chapters.len() as u32)
+}
+
+fn location(
+    book: &str,
+    standard_work: &StandardWork,
+    chapter: u32,
+    lang: Language,
+    provider: &dyn UrlProvider,
+) -> ChapterLocation {
+    ChapterLocation {
+        book: book.to_string(),
+        name: book_slug_to_display_name(book).unwrap_or(book).to_string(),
+        chapter,
+        url: provider.chapter_url(standard_work, book, chapter, lang),
+    }
+}
+
+/// Books with verse data in the same standard work, in canonical order
+fn books_in_work(standard_work: &StandardWork) -> Vec<&'static str> {
+    book_catalog()
+        .into_iter()
+        .filter(|book| book.chapters.is_some() && book.standard_work == *standard_work)
+        .map(|book| book.slug)
+        .collect()
+}
+
+fn previous_chapter(
+    book: &str,
+    standard_work: &StandardWork,
+    chapter: u32,
+    lang: Language,
+    provider: &dyn UrlProvider,
+) -> Option<ChapterLocation> {
+    if chapter > 1 {
+        return Some(location(book, standard_work, chapter - 1, lang, provider));
+    }
+    let books = books_in_work(standard_work);
+    let index = books.iter().position(|slug| *slug == book)?;
+    let previous = books.get(index.checked_sub(1)?)?;
+    Some(location(
+        previous,
+        standard_work,
+        chapter_count(previous),
+        lang,
+        provider,
+    ))
+}
+
+fn next_chapter(
+    book: &str,
+    standard_work: &StandardWork,
+    chapter: u32,
+    lang: Language,
+    provider: &dyn UrlProvider,
+) -> Option<ChapterLocation> {
+    if chapter < chapter_count(book) {
+        return Some(location(book, standard_work, chapter + 1, lang, provider));
+    }
+    let books = books_in_work(standard_work);
+    let index = books.iter().position(|slug| *slug == book)?;
+    let next = books.get(index + 1)?;
+    Some(location(next, standard_work, 1, lang, provider))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_whole_chapter() {
+        let info = reference_info("Alma 32").unwrap();
+        assert_eq!(info.book, "alma");
+        assert_eq!(info.name, "Alma");
+        assert_eq!(info.chapters_in_book, 63);
+        assert_eq!(info.verses_in_chapter, 43);
+        assert_eq!(info.verses_in_range, 43);
+        assert_eq!(info.verse_start, None);
+        assert_eq!(
+            info.chapter_url,
+            "https://www.churchofjesuschrist.org/study/scriptures/bofm/alma/32?lang=eng"
+        );
+        assert_eq!(info.previous_chapter.unwrap().chapter, 31);
+        assert_eq!(info.next_chapter.unwrap().chapter, 33);
+    }
+
+    #[test]
+    fn test_chapter_urls_in_other_languages() {
+        let info = reference_info_with_lang("Alma 32", &BookAliases::default(), Language::SPANISH)
+            .unwrap();
+        assert_eq!(
+            info.chapter_url,
+            "https://www.churchofjesuschrist.org/study/scriptures/bofm/alma/32?lang=spa"
+        );
+        assert!(
+            info.next_chapter
+                .unwrap()
+                .url
+                .ends_with("/alma/33?lang=spa")
+        );
+    }
+
+    #[test]
+    fn test_verse_ranges() {
+        let info = reference_info("Moro. 10:3-5").unwrap();
+        assert_eq!(info.verses_in_range, 3);
+        assert_eq!((info.verse_start, info.verse_end), (Some(3), Some(5)));
+
+        let info = reference_info("Moro. 10:4").unwrap();
+        assert_eq!(info.verses_in_range, 1);
+    }
+
+    #[test]
+    fn test_neighbors_cross_book_boundaries_within_a_work() {
+        let info = reference_info("Alma 63").unwrap();
+        let next = info.next_chapter.unwrap();
+        assert_eq!((next.book.as_str(), next.chapter), ("hel", 1));
+
+        let info = reference_info("Hel 1").unwrap();
+        let previous = info.previous_chapter.unwrap();
+        assert_eq!((previous.book.as_str(), previous.chapter), ("alma", 63));
+
+        // Standard works do not run into each other
+        assert!(reference_info("Gen 1").unwrap().previous_chapter.is_none());
+        assert!(reference_info("Mal 4").unwrap().next_chapter.is_none());
+    }
+
+    #[test]
+    fn test_invalid_references() {
+        assert!(
+            reference_info("Alma 64")
+                .unwrap_err()
+                .to_string()
+                .contains("does not exist")
+        );
+        assert!(
+            reference_info("Nothing 1")
+                .unwrap_err()
+                .to_string()
+                .contains("Unknown book")
+        );
+        assert!(
+            reference_info("Alma")
+                .unwrap_err()
+                .to_string()
+                .contains("Invalid scripture reference format")
+        );
+    }
+}
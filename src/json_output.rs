@@ -1,5 +1,9 @@
 //! JSON output structures for machine-readable responses
 
+use crate::diff::DiffHunk;
+use crate::info::ReferenceInfo;
+use crate::language::Language;
+use crate::messages::{MessageId, ReferenceError};
 use crate::types::ScriptureReference;
 use serde::{Deserialize, Serialize};
 
@@ -31,6 +35,7 @@ pub struct TextProcessingResponse {
     pub output_text: String,
     pub references_found: usize,
     pub references: Vec<FoundReference>,
+    pub error: Option<ErrorInfo>,
 }
 
 /// Information about a found reference in text
@@ -40,6 +45,16 @@ pub struct FoundReference {
     pub parsed: Option<ScriptureReference>,
     pub url: Option<String>,
     pub position: Option<TextPosition>,
+    /// Language of the book name when it is not English, e.g. "spa" for "Juan 3:16"
+    pub language: Option<Language>,
+}
+
+/// Response for the `extract` command
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExtractResponse {
+    pub success: bool,
+    pub references_found: usize,
+    pub references: Vec<FoundReference>,
 }
 
 /// Position information for found references
@@ -49,6 +64,67 @@ pub struct TextPosition {
     pub end: usize,
 }
 
+/// Response for `--check` mode
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CheckResponse {
+    pub success: bool,
+    pub files_checked: usize,
+    pub files_needing_changes: Vec<String>,
+    pub error: Option<ErrorInfo>,
+}
+
+/// Response for `--diff` mode
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DiffResponse {
+    pub success: bool,
+    pub file: String,
+    pub changed: bool,
+    pub hunks: Vec<DiffHunk>,
+}
+
+/// Outcome for one file in multi-file processing
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FileResult {
+    pub path: String,
+    pub changed: bool,
+    pub written: bool,
+    pub references_linked: usize,
+    pub hunks: Option<Vec<DiffHunk>>,
+    pub error: Option<ErrorInfo>,
+}
+
+/// Aggregated response for multi-file processing
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MultiFileResponse {
+    pub success: bool,
+    pub total_files: usize,
+    pub changed: usize,
+    pub unchanged: usize,
+    pub failed: usize,
+    pub references_linked: usize,
+    pub files: Vec<FileResult>,
+}
+
+/// Response for the `info` command
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InfoResponse {
+    pub success: bool,
+    pub input: String,
+    pub info: Option<ReferenceInfo>,
+    pub error: Option<ErrorInfo>,
+}
+
+/// Response for the `vault` command
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VaultResponse {
+    pub success: bool,
+    pub directory: String,
+    /// Notes created or overwritten
+    pub written: usize,
+    /// Existing notes left untouched
+    pub skipped: usize,
+}
+
 /// Validation-only response
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ValidationResponse {
@@ -77,6 +153,7 @@ pub enum ErrorCategory {
     InvalidVerse,
     FileNotFound,
     FileReadError,
+    FileWriteError,
     ParseError,
 }
 
@@ -96,6 +173,19 @@ impl ErrorInfo {
         self.suggestions = Some(suggestions);
         self
     }
+
+    /// Describe a reference error, with its message in `language`
+    #[must_use]
+    pub fn from_error(error: &ReferenceError, language: Language) -> Self {
+        let (code, category) = error_category(error.id());
+        let info = Self::new(code, &error.render(language), category);
+        match error.arg("suggestions") {
+            Some(suggestions) => {
+                info.with_suggestions(suggestions.split(", ").map(str::to_string).collect())
+            }
+            None => info,
+        }
+    }
 }
 
 /// Helper function to create error responses
@@ -120,6 +210,22 @@ pub fn create_error_response(input: &str, error_msg: &str) -> SingleReferenceRes
     }
 }
 
+/// Create the response for a reference error, with its message in `language`
+#[must_use]
+pub fn create_error_response_in(
+    input: &str,
+    error: &ReferenceError,
+    language: Language,
+) -> SingleReferenceResponse {
+    SingleReferenceResponse {
+        success: false,
+        input: input.to_string(),
+        parsed: None,
+        url: None,
+        error: Some(ErrorInfo::from_error(error, language)),
+    }
+}
+
 /// Extract suggestions from error messages
 fn extract_suggestions(error_msg: &str) -> Option<Vec<String>> {
     if error_msg.contains("Did you mean:") {
@@ -139,6 +245,21 @@ fn extract_suggestions(error_msg: &str) -> Option<Vec<String>> {
     }
 }
 
+/// Code and category of a reference error
+const fn error_category(id: MessageId) -> (&'static str, ErrorCategory) {
+    match id {
+        MessageId::InvalidFormat | MessageId::InvalidChapterReferenceFormat => {
+            ("INVALID_FORMAT", ErrorCategory::InvalidFormat)
+        }
+        MessageId::UnknownBook | MessageId::UnknownBookWithSuggestions => {
+            ("UNKNOWN_BOOK", ErrorCategory::UnknownBook)
+        }
+        MessageId::ChapterNotFound => ("INVALID_CHAPTER", ErrorCategory::InvalidChapter),
+        MessageId::VerseNotFound => ("INVALID_VERSE", ErrorCategory::InvalidVerse),
+        _ => ("PARSE_ERROR", ErrorCategory::ParseError),
+    }
+}
+
 /// Categorize error messages for structured responses
 #[must_use]
 pub fn categorize_error(error_msg: &str) -> (String, ErrorCategory) {
@@ -254,7 +375,9 @@ mod tests {
                 parsed: None,
                 url: Some("https://example.com".to_string()),
                 position: Some(TextPosition { start: 4, end: 15 }),
+                language: None,
             }],
+            error: None,
         };
 
         let json = serde_json::to_string(&response).unwrap();
@@ -325,4 +448,37 @@ mod tests {
             Some(vec!["Genesis".to_string(), "Exodus".to_string()])
         );
     }
+
+    #[test]
+    fn test_create_localized_error_response() {
+        let response = create_error_response_in(
+            "Gn 1:1",
+            &ReferenceError::new(
+                MessageId::UnknownBookWithSuggestions,
+                &[("book", "Gn"), ("suggestions", "Gen, Gal")],
+            ),
+            Language::SPANISH,
+        );
+
+        let error = response.error.unwrap();
+        assert_eq!(error.code, "UNKNOWN_BOOK");
+        assert_eq!(
+            error.message,
+            "Abreviatura de libro desconocida: 'Gn'. ¿Quiso decir: Gen, Gal?"
+        );
+        assert_eq!(
+            error.suggestions,
+            Some(vec!["Gen".to_string(), "Gal".to_string()])
+        );
+
+        // Errors are categorized by message whatever language they are shown in
+        let error = crate::parse_scripture_reference_with_aliases(
+            "1 Ne 3:40",
+            &crate::aliases::BookAliases::default(),
+        )
+        .unwrap_err();
+        let info = ErrorInfo::from_error(&error, Language::GERMAN);
+        assert_eq!(info.code, "INVALID_VERSE");
+        assert!(info.message.starts_with("Vers 40"));
+    }
 }
@@ -0,0 +1,113 @@
+//! Languages of the scripture site, identified by ISO 639-3 codes such as "eng" or "spa"
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// A language code as used in the site's `lang` URL parameter
+///
+/// # Examples
+///
+/// ```
+/// use scripture_links_lib::language::Language;
+///
+/// let spanish: Language = "SPA".parse().unwrap();
+/// assert_eq!(spanish.code(), "spa");
+/// assert_eq!(Language::default(), Language::ENGLISH);
+/// assert!("es".parse::<Language>().is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Language([u8; 3]);
+
+impl Language {
+    pub const ENGLISH: Self = Self(*b"eng");
+    pub const SPANISH: Self = Self(*b"spa");
+    pub const PORTUGUESE: Self = Self(*b"por");
+    pub const FRENCH: Self = Self(*b"fra");
+    pub const GERMAN: Self = Self(*b"deu");
+
+    /// Parse a three-letter ISO 639-3 code, ignoring case
+    ///
+    /// # Errors
+    /// Returns an error if `code` is not three ASCII letters.
+    pub fn new(code: &str) -> Result<Self, String> {
+        let code = code.trim();
+        match code.as_bytes() {
+            &[a, b, c] if code.bytes().all(|byte| byte.is_ascii_alphabetic()) => Ok(Self([
+                a.to_ascii_lowercase(),
+                b.to_ascii_lowercase(),
+                c.to_ascii_lowercase(),
+            ])),
+            _ => Err(format!(
+                "Invalid language code '{code}'. Expected a three-letter ISO 639-3 code such as 'eng' or 'spa'"
+            )),
+        }
+    }
+
+    /// The lowercase three-letter code
+    #[must_use]
+    pub fn code(&self) -> &str {
+        // Only ASCII letters are ever stored
+        std::str::from_utf8(&self.0).unwrap_or("eng")
+    }
+}
+
+impl Default for Language {
+    fn default() -> Self {
+        Self::ENGLISH
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+impl FromStr for Language {
+    type Err = String;
+
+    fn from_str(code: &str) -> Result<Self, String> {
+        Self::new(code)
+    }
+}
+
+impl TryFrom<String> for Language {
+    type Error = String;
+
+    fn try_from(code: String) -> Result<Self, String> {
+        Self::new(&code)
+    }
+}
+
+impl From<Language> for String {
+    fn from(language: Language) -> Self {
+        language.code().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_codes_are_normalized() {
+        assert_eq!(Language::new(" Por ").unwrap(), Language::PORTUGUESE);
+        assert_eq!(Language::GERMAN.to_string(), "deu");
+        assert_eq!(serde_json::to_string(&Language::FRENCH).unwrap(), "\"fra\"");
+        assert_eq!(
+            serde_json::from_str::<Language>("\"SPA\"").unwrap(),
+            Language::SPANISH
+        );
+    }
+
+    #[test]
+    fn test_rejects_invalid_codes() {
+        assert!(Language::new("en").is_err());
+        assert!(Language::new("engl").is_err());
+        assert!(Language::new("e1g").is_err());
+        assert!(Language::new("ñoñ").is_err());
+        assert!(serde_json::from_str::<Language>("\"xx\"").is_err());
+    }
+}
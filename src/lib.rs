@@ -3,24 +3,45 @@
 //! A library for converting scripture references to URLs and processing text containing scripture references.
 
 pub mod abbreviations;
+pub mod aliases;
+pub mod books;
 pub mod cli;
+pub mod config;
+pub mod diff;
+pub mod files;
+pub mod html;
+pub mod info;
 pub mod json_output;
+pub mod language;
+pub mod localized_names;
+pub mod messages;
+pub mod parallel;
 pub mod parser;
 pub mod scripture_data;
 pub mod text_processor;
 pub mod types;
 pub mod url_generator;
+pub mod url_provider;
+pub mod vault;
+pub mod watch;
+pub mod wikilink;
+pub mod works;
 
 #[cfg(target_arch = "wasm32")]
 pub mod wasm;
 
 // Re-export the main types and functions for easy use
-pub use parser::parse_scripture_reference;
+pub use language::Language;
+pub use messages::ReferenceError;
+pub use parser::{
+    parse_scripture_reference, parse_scripture_reference_with_aliases,
+    parse_scripture_reference_with_works,
+};
 pub use text_processor::{
     process_text_for_scripture_references, process_text_with_format, process_text_with_options,
 };
 pub use types::{OutputFormat, ScriptureReference, StandardWork};
-pub use url_generator::generate_url;
+pub use url_generator::{generate_url, generate_url_with_lang, generate_url_with_works};
 
 #[cfg(test)]
 mod integration_tests;
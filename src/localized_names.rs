@@ -0,0 +1,666 @@
+//! Book names and abbreviations in other languages, such as "Juan" or "1 Nefi"
+//!
+//! Localized names map to the same slugs as the English ones and are matched
+//! without regard to case or accents, so "Genesis", "génesis" and "GÉNESIS"
+//! all find Spanish "Génesis".
+
+use crate::abbreviations::{BookMapping, book_slug_to_display_name, create_abbreviation_map};
+use crate::language::Language;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+/// A book slug and its names in one language; the first name is the full name
+pub type LocalizedBook = (&'static str, &'static [&'static str]);
+
+/// Languages with book name tables
+pub const BOOK_NAME_LANGUAGES: &[Language] = &[
+    Language::SPANISH,
+    Language::PORTUGUESE,
+    Language::FRENCH,
+    Language::GERMAN,
+];
+
+const SPANISH: &[LocalizedBook] = &[
+    // Antiguo Testamento
+    ("gen", &["Génesis", "Gén"]),
+    ("ex", &["Éxodo", "Éx"]),
+    ("lev", &["Levítico", "Lev"]),
+    ("num", &["Números", "Núm"]),
+    ("deut", &["Deuteronomio", "Deut"]),
+    ("josh", &["Josué"]),
+    ("judg", &["Jueces", "Jue"]),
+    ("ruth", &["Rut"]),
+    ("1-sam", &["1 Samuel", "1 Sam"]),
+    ("2-sam", &["2 Samuel", "2 Sam"]),
+    ("1-kgs", &["1 Reyes", "1 Rey"]),
+    ("2-kgs", &["2 Reyes", "2 Rey"]),
+    ("1-chr", &["1 Crónicas", "1 Cró"]),
+    ("2-chr", &["2 Crónicas", "2 Cró"]),
+    ("ezra", &["Esdras", "Esd"]),
+    ("neh", &["Nehemías", "Neh"]),
+    ("esth", &["Ester"]),
+    ("job", &["Job"]),
+    ("ps", &["Salmos", "Sal"]),
+    ("prov", &["Proverbios", "Prov"]),
+    ("eccl", &["Eclesiastés", "Ecle"]),
+    ("song", &["Cantares", "Cant"]),
+    ("isa", &["Isaías", "Isa"]),
+    ("jer", &["Jeremías", "Jer"]),
+    ("lam", &["Lamentaciones", "Lam"]),
+    ("ezek", &["Ezequiel", "Ezeq"]),
+    ("dan", &["Daniel", "Dan"]),
+    ("hosea", &["Oseas"]),
+    ("joel", &["Joel"]),
+    ("amos", &["Amós"]),
+    ("obad", &["Abdías", "Abd"]),
+    ("jonah", &["Jonás"]),
+    ("micah", &["Miqueas", "Miq"]),
+    ("nahum", &["Nahúm"]),
+    ("hab", &["Habacuc", "Hab"]),
+    ("zeph", &["Sofonías", "Sof"]),
+    ("hag", &["Hageo", "Hag"]),
+    ("zech", &["Zacarías", "Zac"]),
+    ("mal", &["Malaquías", "Mal"]),
+    // Nuevo Testamento
+    ("matt", &["Mateo", "Mat"]),
+    ("mark", &["Marcos"]),
+    ("luke", &["Lucas"]),
+    ("john", &["Juan"]),
+    ("acts", &["Hechos"]),
+    ("rom", &["Romanos", "Rom"]),
+    ("1-cor", &["1 Corintios", "1 Cor"]),
+    ("2-cor", &["2 Corintios", "2 Cor"]),
+    ("gal", &["Gálatas", "Gál"]),
+    ("eph", &["Efesios", "Efe"]),
+    ("philip", &["Filipenses", "Filip"]),
+    ("col", &["Colosenses", "Col"]),
+    ("1-thes", &["1 Tesalonicenses", "1 Tes"]),
+    ("2-thes", &["2 Tesalonicenses", "2 Tes"]),
+    ("1-tim", &["1 Timoteo", "1 Tim"]),
+    ("2-tim", &["2 Timoteo", "2 Tim"]),
+    ("titus", &["Tito"]),
+    ("philem", &["Filemón", "Filem"]),
+    ("heb", &["Hebreos", "Heb"]),
+    ("james", &["Santiago", "Sant"]),
+    ("1-pet", &["1 Pedro", "1 Pe"]),
+    ("2-pet", &["2 Pedro", "2 Pe"]),
+    ("1-jn", &["1 Juan"]),
+    ("2-jn", &["2 Juan"]),
+    ("3-jn", &["3 Juan"]),
+    ("jude", &["Judas"]),
+    ("rev", &["Apocalipsis", "Apoc"]),
+    // Libro de Mormón
+    ("1-ne", &["1 Nefi", "1 Ne"]),
+    ("2-ne", &["2 Nefi", "2 Ne"]),
+    ("jacob", &["Jacob"]),
+    ("enos", &["Enós"]),
+    ("jarom", &["Jarom"]),
+    ("omni", &["Omni"]),
+    ("w-of-m", &["Palabras de Mormón", "P de Morm"]),
+    ("mosiah", &["Mosíah"]),
+    ("alma", &["Alma"]),
+    ("hel", &["Helamán", "Hel"]),
+    ("3-ne", &["3 Nefi", "3 Ne"]),
+    ("4-ne", &["4 Nefi", "4 Ne"]),
+    ("morm", &["Mormón", "Morm"]),
+    ("ether", &["Éter"]),
+    ("moro", &["Moroni", "Moro"]),
+    // Doctrina y Convenios
+    ("dc", &["Doctrina y Convenios", "DyC", "D y C"]),
+    ("od", &["Declaración Oficial"]),
+    // Perla de Gran Precio
+    ("moses", &["Moisés"]),
+    ("abr", &["Abraham", "Abr"]),
+    ("js-m", &["José Smith—Mateo", "JS—M"]),
+    ("js-h", &["José Smith—Historia", "JS—H"]),
+    ("a-of-f", &["Artículos de Fe", "AdeF"]),
+];
+
+const PORTUGUESE: &[LocalizedBook] = &[
+    // Velho Testamento
+    ("gen", &["Gênesis", "Gên"]),
+    ("ex", &["Êxodo", "Êx"]),
+    ("lev", &["Levítico", "Lev"]),
+    ("num", &["Números", "Núm"]),
+    ("deut", &["Deuteronômio", "Deut"]),
+    ("josh", &["Josué"]),
+    ("judg", &["Juízes", "Juí"]),
+    ("ruth", &["Rute"]),
+    ("1-sam", &["1 Samuel", "1 Sam"]),
+    ("2-sam", &["2 Samuel", "2 Sam"]),
+    ("1-kgs", &["1 Reis"]),
+    ("2-kgs", &["2 Reis"]),
+    ("1-chr", &["1 Crônicas", "1 Crô"]),
+    ("2-chr", &["2 Crônicas", "2 Crô"]),
+    ("ezra", &["Esdras", "Esd"]),
+    ("neh", &["Neemias", "Nee"]),
+    ("esth", &["Ester"]),
+    ("job", &["Jó"]),
+    ("ps", &["Salmos", "Sal"]),
+    ("prov", &["Provérbios", "Prov"]),
+    ("eccl", &["Eclesiastes", "Ecl"]),
+    ("song", &["Cantares de Salomão", "Cantares", "Cant"]),
+    ("isa", &["Isaías", "Isa"]),
+    ("jer", &["Jeremias", "Jer"]),
+    ("lam", &["Lamentações", "Lam"]),
+    ("ezek", &["Ezequiel", "Ezeq"]),
+    ("dan", &["Daniel", "Dan"]),
+    ("hosea", &["Oséias", "Os"]),
+    ("joel", &["Joel"]),
+    ("amos", &["Amós"]),
+    ("obad", &["Obadias", "Obad"]),
+    ("jonah", &["Jonas"]),
+    ("micah", &["Miquéias", "Miq"]),
+    ("nahum", &["Naum"]),
+    ("hab", &["Habacuque", "Hab"]),
+    ("zeph", &["Sofonias", "Sof"]),
+    ("hag", &["Ageu"]),
+    ("zech", &["Zacarias", "Zac"]),
+    ("mal", &["Malaquias", "Mal"]),
+    // Novo Testamento
+    ("matt", &["Mateus", "Mat"]),
+    ("mark", &["Marcos"]),
+    ("luke", &["Lucas"]),
+    ("john", &["João"]),
+    ("acts", &["Atos"]),
+    ("rom", &["Romanos", "Rom"]),
+    ("1-cor", &["1 Coríntios", "1 Cor"]),
+    ("2-cor", &["2 Coríntios", "2 Cor"]),
+    ("gal", &["Gálatas", "Gál"]),
+    ("eph", &["Efésios", "Ef"]),
+    ("philip", &["Filipenses", "Filip"]),
+    ("col", &["Colossenses", "Col"]),
+    ("1-thes", &["1 Tessalonicenses", "1 Tess"]),
+    ("2-thes", &["2 Tessalonicenses", "2 Tess"]),
+    ("1-tim", &["1 Timóteo", "1 Tim"]),
+    ("2-tim", &["2 Timóteo", "2 Tim"]),
+    ("titus", &["Tito"]),
+    ("philem", &["Filemom", "Filem"]),
+    ("heb", &["Hebreus", "Heb"]),
+    ("james", &["Tiago"]),
+    ("1-pet", &["1 Pedro", "1 Ped"]),
+    ("2-pet", &["2 Pedro", "2 Ped"]),
+    ("1-jn", &["1 João"]),
+    ("2-jn", &["2 João"]),
+    ("3-jn", &["3 João"]),
+    ("jude", &["Judas"]),
+    ("rev", &["Apocalipse", "Apoc"]),
+    // Livro de Mórmon
+    ("1-ne", &["1 Néfi", "1 Né"]),
+    ("2-ne", &["2 Néfi", "2 Né"]),
+    ("jacob", &["Jacó"]),
+    ("enos", &["Enos"]),
+    ("jarom", &["Jarom"]),
+    ("omni", &["Ômni"]),
+    ("w-of-m", &["Palavras de Mórmon", "P de Mórm"]),
+    ("mosiah", &["Mosias"]),
+    ("alma", &["Alma"]),
+    ("hel", &["Helamã", "Hel"]),
+    ("3-ne", &["3 Néfi", "3 Né"]),
+    ("4-ne", &["4 Néfi", "4 Né"]),
+    ("morm", &["Mórmon", "Mórm"]),
+    ("ether", &["Éter"]),
+    ("moro", &["Morôni", "Morô"]),
+    // Doutrina e Convênios
+    ("dc", &["Doutrina e Convênios"]),
+    ("od", &["Declaração Oficial"]),
+    // Pérola de Grande Valor
+    ("moses", &["Moisés"]),
+    ("abr", &["Abraão"]),
+    ("js-m", &["Joseph Smith—Mateus"]),
+    ("js-h", &["Joseph Smith—História"]),
+    ("a-of-f", &["Regras de Fé"]),
+];
+
+const FRENCH: &[LocalizedBook] = &[
+    // Ancien Testament
+    ("gen", &["Genèse", "Gn"]),
+    ("ex", &["Exode"]),
+    ("lev", &["Lévitique", "Lv"]),
+    ("num", &["Nombres", "Nb"]),
+    ("deut", &["Deutéronome", "Dt"]),
+    ("josh", &["Josué", "Jos"]),
+    ("judg", &["Juges", "Jg"]),
+    ("ruth", &["Ruth", "Rt"]),
+    ("1-sam", &["1 Samuel", "1 S"]),
+    ("2-sam", &["2 Samuel", "2 S"]),
+    ("1-kgs", &["1 Rois", "1 R"]),
+    ("2-kgs", &["2 Rois", "2 R"]),
+    ("1-chr", &["1 Chroniques", "1 Ch"]),
+    ("2-chr", &["2 Chroniques", "2 Ch"]),
+    ("ezra", &["Esdras", "Esd"]),
+    ("neh", &["Néhémie", "Né"]),
+    ("esth", &["Esther", "Est"]),
+    ("job", &["Job", "Jb"]),
+    ("ps", &["Psaumes", "Ps"]),
+    ("prov", &["Proverbes", "Pr"]),
+    ("eccl", &["Ecclésiaste", "Ec"]),
+    ("song", &["Cantique des cantiques", "Ca"]),
+    ("isa", &["Ésaïe", "És"]),
+    ("jer", &["Jérémie", "Jé"]),
+    ("lam", &["Lamentations", "La"]),
+    ("ezek", &["Ézéchiel", "Éz"]),
+    ("dan", &["Daniel", "Da"]),
+    ("hosea", &["Osée", "Os"]),
+    ("joel", &["Joël", "Joë"]),
+    ("amos", &["Amos", "Am"]),
+    ("obad", &["Abdias", "Ab"]),
+    ("jonah", &["Jonas", "Jon"]),
+    ("micah", &["Michée", "Mi"]),
+    ("nahum", &["Nahum", "Na"]),
+    ("hab", &["Habakuk", "Ha"]),
+    ("zeph", &["Sophonie", "So"]),
+    ("hag", &["Aggée", "Ag"]),
+    ("zech", &["Zacharie", "Za"]),
+    ("mal", &["Malachie", "Mal"]),
+    // Nouveau Testament
+    ("matt", &["Matthieu", "Mt"]),
+    ("mark", &["Marc", "Mc"]),
+    ("luke", &["Luc", "Lc"]),
+    ("john", &["Jean", "Jn"]),
+    ("acts", &["Actes", "Ac"]),
+    ("rom", &["Romains", "Ro"]),
+    ("1-cor", &["1 Corinthiens", "1 Co"]),
+    ("2-cor", &["2 Corinthiens", "2 Co"]),
+    ("gal", &["Galates", "Ga"]),
+    ("eph", &["Éphésiens", "Ép"]),
+    ("philip", &["Philippiens", "Ph"]),
+    ("col", &["Colossiens", "Col"]),
+    ("1-thes", &["1 Thessaloniciens", "1 Th"]),
+    ("2-thes", &["2 Thessaloniciens", "2 Th"]),
+    ("1-tim", &["1 Timothée", "1 Ti"]),
+    ("2-tim", &["2 Timothée", "2 Ti"]),
+    ("titus", &["Tite", "Tt"]),
+    ("philem", &["Philémon", "Phm"]),
+    ("heb", &["Hébreux", "Hé"]),
+    ("james", &["Jacques", "Jc"]),
+    ("1-pet", &["1 Pierre", "1 P"]),
+    ("2-pet", &["2 Pierre", "2 P"]),
+    ("1-jn", &["1 Jean", "1 Jn"]),
+    ("2-jn", &["2 Jean", "2 Jn"]),
+    ("3-jn", &["3 Jean", "3 Jn"]),
+    ("jude", &["Jude"]),
+    ("rev", &["Apocalypse", "Ap"]),
+    // Livre de Mormon
+    ("1-ne", &["1 Néphi", "1 Né"]),
+    ("2-ne", &["2 Néphi", "2 Né"]),
+    ("jacob", &["Jacob"]),
+    ("enos", &["Énos"]),
+    ("jarom", &["Jarom"]),
+    ("omni", &["Omni"]),
+    ("w-of-m", &["Paroles de Mormon", "P de M"]),
+    ("mosiah", &["Mosiah"]),
+    ("alma", &["Alma"]),
+    ("hel", &["Hélaman", "Hél"]),
+    ("3-ne", &["3 Néphi", "3 Né"]),
+    ("4-ne", &["4 Néphi", "4 Né"]),
+    ("morm", &["Mormon", "Mrm"]),
+    ("ether", &["Éther", "Ét"]),
+    ("moro", &["Moroni", "Mro"]),
+    // Doctrine et Alliances
+    ("dc", &["Doctrine et Alliances", "D&A"]),
+    ("od", &["Déclaration officielle"]),
+    // Perle de Grand Prix
+    ("moses", &["Moïse"]),
+    ("abr", &["Abraham", "Abr"]),
+    ("js-m", &["Joseph Smith—Matthieu"]),
+    ("js-h", &["Joseph Smith—Histoire"]),
+    ("a-of-f", &["Articles de foi", "AF"]),
+];
+
+const GERMAN: &[LocalizedBook] = &[
+    // Altes Testament
+    ("gen", &["Genesis", "1 Mose", "1. Mose"]),
+    ("ex", &["Exodus", "2 Mose", "2. Mose"]),
+    ("lev", &["Levitikus", "3 Mose", "3. Mose"]),
+    ("num", &["Numeri", "4 Mose", "4. Mose"]),
+    ("deut", &["Deuteronomium", "5 Mose", "5. Mose"]),
+    ("josh", &["Josua", "Jos"]),
+    ("judg", &["Richter", "Ri"]),
+    ("ruth", &["Rut"]),
+    ("1-sam", &["1 Samuel", "1 Sam"]),
+    ("2-sam", &["2 Samuel", "2 Sam"]),
+    ("1-kgs", &["1 Könige", "1 Kön"]),
+    ("2-kgs", &["2 Könige", "2 Kön"]),
+    ("1-chr", &["1 Chronik", "1 Chr"]),
+    ("2-chr", &["2 Chronik", "2 Chr"]),
+    ("ezra", &["Esra", "Esr"]),
+    ("neh", &["Nehemia", "Neh"]),
+    ("esth", &["Ester", "Est"]),
+    ("job", &["Ijob", "Hiob"]),
+    ("ps", &["Psalmen", "Ps"]),
+    ("prov", &["Sprichwörter", "Sprüche", "Spr"]),
+    ("eccl", &["Kohelet", "Prediger", "Koh"]),
+    ("song", &["Hoheslied", "Hld"]),
+    ("isa", &["Jesaja", "Jes"]),
+    ("jer", &["Jeremia", "Jer"]),
+    ("lam", &["Klagelieder", "Klgl"]),
+    ("ezek", &["Ezechiel", "Hesekiel", "Ez"]),
+    ("dan", &["Daniel", "Dan"]),
+    ("hosea", &["Hosea", "Hos"]),
+    ("joel", &["Joël"]),
+    ("amos", &["Amos", "Am"]),
+    ("obad", &["Obadja", "Obd"]),
+    ("jonah", &["Jona", "Jon"]),
+    ("micah", &["Micha", "Mi"]),
+    ("nahum", &["Nahum", "Nah"]),
+    ("hab", &["Habakuk", "Hab"]),
+    ("zeph", &["Zefanja", "Zef"]),
+    ("hag", &["Haggai", "Hag"]),
+    ("zech", &["Sacharja", "Sach"]),
+    ("mal", &["Maleachi", "Mal"]),
+    // Neues Testament
+    ("matt", &["Matthäus", "Mt"]),
+    ("mark", &["Markus", "Mk"]),
+    ("luke", &["Lukas", "Lk"]),
+    ("john", &["Johannes", "Joh"]),
+    ("acts", &["Apostelgeschichte", "Apg"]),
+    ("rom", &["Römer", "Röm"]),
+    ("1-cor", &["1 Korinther", "1 Kor"]),
+    ("2-cor", &["2 Korinther", "2 Kor"]),
+    ("gal", &["Galater", "Gal"]),
+    ("eph", &["Epheser", "Eph"]),
+    ("philip", &["Philipper", "Phil"]),
+    ("col", &["Kolosser", "Kol"]),
+    ("1-thes", &["1 Thessalonicher", "1 Thess"]),
+    ("2-thes", &["2 Thessalonicher", "2 Thess"]),
+    ("1-tim", &["1 Timotheus", "1 Tim"]),
+    ("2-tim", &["2 Timotheus", "2 Tim"]),
+    ("titus", &["Titus", "Tit"]),
+    ("philem", &["Philemon", "Phlm"]),
+    ("heb", &["Hebräer", "Hebr"]),
+    ("james", &["Jakobus", "Jak"]),
+    ("1-pet", &["1 Petrus", "1 Petr"]),
+    ("2-pet", &["2 Petrus", "2 Petr"]),
+    ("1-jn", &["1 Johannes", "1 Joh"]),
+    ("2-jn", &["2 Johannes", "2 Joh"]),
+    ("3-jn", &["3 Johannes", "3 Joh"]),
+    ("jude", &["Judas", "Jud"]),
+    ("rev", &["Offenbarung", "Offb"]),
+    // Buch Mormon
+    ("1-ne", &["1 Nephi", "1 Ne"]),
+    ("2-ne", &["2 Nephi", "2 Ne"]),
+    ("jacob", &["Jakob"]),
+    ("enos", &["Enos"]),
+    ("jarom", &["Jarom"]),
+    ("omni", &["Omni"]),
+    ("w-of-m", &["Worte Mormons", "WMorm"]),
+    ("mosiah", &["Mosia"]),
+    ("alma", &["Alma"]),
+    ("hel", &["Helaman", "Hel"]),
+    ("3-ne", &["3 Nephi", "3 Ne"]),
+    ("4-ne", &["4 Nephi", "4 Ne"]),
+    ("morm", &["Mormon", "Morm"]),
+    ("ether", &["Ether"]),
+    ("moro", &["Moroni", "Moro"]),
+    // Lehre und Bündnisse
+    ("dc", &["Lehre und Bündnisse", "LuB"]),
+    ("od", &["Amtliche Erklärung"]),
+    // Köstliche Perle
+    ("moses", &["Mose"]),
+    ("abr", &["Abraham", "Abr"]),
+    ("js-m", &["Joseph Smith—Matthäus"]),
+    ("js-h", &["Joseph Smith—Lebensgeschichte"]),
+    ("a-of-f", &["Glaubensartikel"]),
+];
+
+/// Languages and book mappings for each folded name, in [`BOOK_NAME_LANGUAGES`] order
+type LocalizedIndex = HashMap<String, Vec<(Language, BookMapping)>>;
+
+static LOCALIZED_INDEX: LazyLock<LocalizedIndex> = LazyLock::new(|| {
+    let abbreviations = create_abbreviation_map();
+    let mut index = LocalizedIndex::new();
+    for &language in BOOK_NAME_LANGUAGES {
+        for (slug, names) in localized_book_names(language).unwrap_or_default() {
+            let Some((book, standard_work)) = abbreviations.values().find(|(book, _)| book == slug)
+            else {
+                continue;
+            };
+            for name in *names {
+                index
+                    .entry(fold(name))
+                    .or_default()
+                    .push((language, (*book, standard_work.clone())));
+            }
+        }
+    }
+    index
+});
+
+/// The book names of a language, or `None` if there is no table for it
+#[must_use]
+pub const fn localized_book_names(language: Language) -> Option<&'static [LocalizedBook]> {
+    match language {
+        Language::SPANISH => Some(SPANISH),
+        Language::PORTUGUESE => Some(PORTUGUESE),
+        Language::FRENCH => Some(FRENCH),
+        Language::GERMAN => Some(GERMAN),
+        _ => None,
+    }
+}
+
+/// Check that book names are available in `language`
+///
+/// # Errors
+/// Returns an error listing the supported languages.
+pub fn check_book_name_language(language: Language) -> Result<(), String> {
+    if localized_book_names(language).is_some() {
+        return Ok(());
+    }
+    let supported: Vec<&str> = BOOK_NAME_LANGUAGES.iter().map(Language::code).collect();
+    Err(format!(
+        "Book names are not available in '{language}'; expected one of {}",
+        supported.join(", ")
+    ))
+}
+
+/// Check that book names can be displayed in `language`, which is English or
+/// one of [`BOOK_NAME_LANGUAGES`]
+///
+/// # Errors
+/// Returns an error listing the supported languages.
+pub fn check_display_language(language: Language) -> Result<(), String> {
+    if language == Language::ENGLISH {
+        return Ok(());
+    }
+    check_book_name_language(language)
+}
+
+/// Parse a language code for displaying book names, such as "spa"
+///
+/// # Errors
+/// Returns an error if the code is invalid or book names are not available in it.
+pub fn parse_display_language(code: &str) -> Result<Language, String> {
+    let language = Language::new(code)?;
+    check_display_language(language)?;
+    Ok(language)
+}
+
+/// Full name of a book in `language`, or its English name when there are no
+/// book names in `language`
+///
+/// # Examples
+///
+/// ```
+/// use scripture_links_lib::Language;
+/// use scripture_links_lib::localized_names::localized_display_name;
+///
+/// assert_eq!(localized_display_name("gen", Language::SPANISH), Some("Génesis"));
+/// assert_eq!(localized_display_name("gen", Language::ENGLISH), Some("Genesis"));
+/// assert_eq!(localized_display_name("tg", Language::SPANISH), None);
+/// ```
+#[must_use]
+pub fn localized_display_name(slug: &str, language: Language) -> Option<&'static str> {
+    let english = book_slug_to_display_name(slug)?;
+    Some(
+        localized_book_names(language)
+            .and_then(|books| books.iter().find(|(book, _)| *book == slug))
+            .and_then(|(_, names)| names.first().copied())
+            .unwrap_or(english),
+    )
+}
+
+/// Find a book by its name in one of `languages`, trying them in order
+///
+/// # Examples
+///
+/// ```
+/// use scripture_links_lib::Language;
+/// use scripture_links_lib::localized_names::lookup_localized_book;
+///
+/// let ((book, _), language) = lookup_localized_book("genesis", &[Language::SPANISH]).unwrap();
+/// assert_eq!((book, language), ("gen", Language::SPANISH));
+/// assert!(lookup_localized_book("Juan", &[Language::GERMAN]).is_none());
+/// ```
+#[must_use]
+pub fn lookup_localized_book(
+    name: &str,
+    languages: &[Language],
+) -> Option<(BookMapping, Language)> {
+    let candidates = LOCALIZED_INDEX.get(&fold(name))?;
+    languages.iter().find_map(|language| {
+        candidates
+            .iter()
+            .find(|(candidate, _)| candidate == language)
+            .map(|(_, mapping)| (mapping.clone(), *language))
+    })
+}
+
+/// Lowercase `name`, strip accents and drop a trailing period
+#[must_use]
+pub fn fold(name: &str) -> String {
+    name.trim()
+        .trim_end_matches('.')
+        .to_lowercase()
+        .chars()
+        .map(base_letter)
+        .collect()
+}
+
+/// Regex matching `name` regardless of case and accents
+pub(crate) fn name_pattern(name: &str) -> String {
+    let mut pattern = String::from("(?i:");
+    for c in name.chars() {
+        let base = base_letter(c.to_lowercase().next().unwrap_or(c));
+        match ACCENTED.iter().find(|(letter, _)| *letter == base) {
+            Some((letter, accented)) => {
+                pattern.push('[');
+                pattern.push(*letter);
+                pattern.push_str(accented);
+                pattern.push(']');
+            }
+            None => pattern.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    pattern.push(')');
+    pattern
+}
+
+/// Lowercase letters and their accented forms
+const ACCENTED: &[(char, &str)] = &[
+    ('a', "àáâãä"),
+    ('c', "ç"),
+    ('e', "èéêë"),
+    ('i', "ìíîï"),
+    ('n', "ñ"),
+    ('o', "òóôõö"),
+    ('u', "ùúûü"),
+    ('y', "ýÿ"),
+];
+
+fn base_letter(c: char) -> char {
+    ACCENTED
+        .iter()
+        .find(|(_, accented)| accented.contains(c))
+        .map_or(c, |(letter, _)| *letter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::abbreviations::BOOK_DISPLAY_NAMES;
+    use regex::Regex;
+
+    #[test]
+    fn test_tables_cover_every_book() {
+        for &language in BOOK_NAME_LANGUAGES {
+            let names = localized_book_names(language).unwrap();
+            let slugs: Vec<&str> = names.iter().map(|(slug, _)| *slug).collect();
+            let expected: Vec<&str> = BOOK_DISPLAY_NAMES.iter().map(|(slug, _)| *slug).collect();
+            assert_eq!(slugs, expected, "{language}");
+            assert!(names.iter().all(|(_, names)| !names.is_empty()));
+        }
+        assert!(localized_book_names(Language::ENGLISH).is_none());
+    }
+
+    #[test]
+    fn test_lookup_ignores_case_and_accents() {
+        let spanish = [Language::SPANISH];
+        assert_eq!(lookup_localized_book("Juan", &spanish).unwrap().0.0, "john");
+        assert_eq!(
+            lookup_localized_book("1 nefi", &spanish).unwrap().0.0,
+            "1-ne"
+        );
+        assert_eq!(lookup_localized_book("DyC", &spanish).unwrap().0.0, "dc");
+        assert_eq!(lookup_localized_book("ÉXODO", &spanish).unwrap().0.0, "ex");
+        assert_eq!(
+            lookup_localized_book("Éter.", &spanish).unwrap().0.0,
+            "ether"
+        );
+
+        // "Éter" is also Portuguese; the first listed language wins
+        let both = [Language::PORTUGUESE, Language::SPANISH];
+        assert_eq!(
+            lookup_localized_book("Eter", &both).unwrap().1,
+            Language::PORTUGUESE
+        );
+        assert!(lookup_localized_book("Johannes", &spanish).is_none());
+    }
+
+    #[test]
+    fn test_english_abbreviations_are_not_localized() {
+        // "Mos." is written in English too, so it must not mark a reference as localized
+        assert!(lookup_localized_book("Mos.", BOOK_NAME_LANGUAGES).is_none());
+        assert_eq!(
+            lookup_localized_book("Mosíah", BOOK_NAME_LANGUAGES)
+                .unwrap()
+                .0
+                .0,
+            "mosiah"
+        );
+    }
+
+    #[test]
+    fn test_name_pattern() {
+        let re = Regex::new(&format!("^{}$", name_pattern("Génesis"))).unwrap();
+        assert!(re.is_match("Génesis"));
+        assert!(re.is_match("genesis"));
+        assert!(re.is_match("GÉNESIS"));
+        assert!(!re.is_match("Genisis"));
+    }
+
+    #[test]
+    fn test_display_names() {
+        assert_eq!(
+            localized_display_name("1-ne", Language::PORTUGUESE),
+            Some("1 Néfi")
+        );
+        assert_eq!(
+            localized_display_name("dc", Language::GERMAN),
+            Some("Lehre und Bündnisse")
+        );
+        let italian = Language::new("ita").unwrap();
+        assert_eq!(localized_display_name("john", italian), Some("John"));
+        assert!(parse_display_language("eng").is_ok());
+        assert!(parse_display_language("ita").is_err());
+    }
+
+    #[test]
+    fn test_unsupported_language() {
+        assert!(check_book_name_language(Language::FRENCH).is_ok());
+        assert_eq!(
+            check_book_name_language(Language::new("ita").unwrap()).unwrap_err(),
+            "Book names are not available in 'ita'; expected one of spa, por, fra, deu"
+        );
+    }
+}
@@ -0,0 +1,581 @@
+//! Catalog of reference error messages in the supported languages
+//!
+//! Errors are [`ReferenceError`]s holding a [`MessageId`] and the values of its
+//! placeholders, so they are categorized by id and rendered once, in the
+//! language they are shown in.
+
+use crate::abbreviations::BOOK_DISPLAY_NAMES;
+use crate::language::Language;
+use crate::localized_names::localized_display_name;
+use std::fmt;
+
+/// Errors that have translations
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageId {
+    InvalidFormat,
+    InvalidChapterReferenceFormat,
+    InvalidChapterNumber,
+    InvalidVerseNumber,
+    UnknownBook,
+    UnknownBookWithSuggestions,
+    ChapterZero,
+    ChapterNotFound,
+    VerseZero,
+    VerseNotFound,
+    EndVerseBeforeStart,
+    NoVerseData,
+}
+
+/// A message in English followed by its translations
+type CatalogEntry = (MessageId, &'static [(Language, &'static str)]);
+
+/// Literal text of a template followed by the name of the placeholder after it
+type TemplatePart<'a> = (&'a str, Option<&'a str>);
+
+/// Languages messages are translated into, besides English
+pub const MESSAGE_LANGUAGES: &[Language] = &[
+    Language::SPANISH,
+    Language::PORTUGUESE,
+    Language::FRENCH,
+    Language::GERMAN,
+];
+
+const CATALOG: &[CatalogEntry] = &[
+    (
+        MessageId::InvalidFormat,
+        &[
+            (
+                Language::ENGLISH,
+                "Invalid scripture reference format: '{reference}'. Expected format: 'Book Chapter:Verse' or 'Book Chapter:Verse-Verse'",
+            ),
+            (
+                Language::SPANISH,
+                "Formato de referencia no válido: '{reference}'. Formato esperado: 'Libro Capítulo:Versículo' o 'Libro Capítulo:Versículo-Versículo'",
+            ),
+            (
+                Language::PORTUGUESE,
+                "Formato de referência inválido: '{reference}'. Formato esperado: 'Livro Capítulo:Versículo' ou 'Livro Capítulo:Versículo-Versículo'",
+            ),
+            (
+                Language::FRENCH,
+                "Format de référence non valide : '{reference}'. Format attendu : 'Livre Chapitre:Verset' ou 'Livre Chapitre:Verset-Verset'",
+            ),
+            (
+                Language::GERMAN,
+                "Ungültiges Format der Schriftstelle: '{reference}'. Erwartet: 'Buch Kapitel:Vers' oder 'Buch Kapitel:Vers-Vers'",
+            ),
+        ],
+    ),
+    (
+        MessageId::InvalidChapterReferenceFormat,
+        &[
+            (
+                Language::ENGLISH,
+                "Invalid scripture reference format: '{reference}'. Expected format: 'Book Chapter', 'Book Chapter:Verse' or 'Book Chapter:Verse-Verse'",
+            ),
+            (
+                Language::SPANISH,
+                "Formato de referencia no válido: '{reference}'. Formato esperado: 'Libro Capítulo', 'Libro Capítulo:Versículo' o 'Libro Capítulo:Versículo-Versículo'",
+            ),
+            (
+                Language::PORTUGUESE,
+                "Formato de referência inválido: '{reference}'. Formato esperado: 'Livro Capítulo', 'Livro Capítulo:Versículo' ou 'Livro Capítulo:Versículo-Versículo'",
+            ),
+            (
+                Language::FRENCH,
+                "Format de référence non valide : '{reference}'. Format attendu : 'Livre Chapitre', 'Livre Chapitre:Verset' ou 'Livre Chapitre:Verset-Verset'",
+            ),
+            (
+                Language::GERMAN,
+                "Ungültiges Format der Schriftstelle: '{reference}'. Erwartet: 'Buch Kapitel', 'Buch Kapitel:Vers' oder 'Buch Kapitel:Vers-Vers'",
+            ),
+        ],
+    ),
+    (
+        MessageId::InvalidChapterNumber,
+        &[
+            (
+                Language::ENGLISH,
+                "Invalid chapter number in reference: {reference}",
+            ),
+            (
+                Language::SPANISH,
+                "Número de capítulo no válido en la referencia: {reference}",
+            ),
+            (
+                Language::PORTUGUESE,
+                "Número de capítulo inválido na referência: {reference}",
+            ),
+            (
+                Language::FRENCH,
+                "Numéro de chapitre non valide dans la référence : {reference}",
+            ),
+            (
+                Language::GERMAN,
+                "Ungültige Kapitelnummer in der Schriftstelle: {reference}",
+            ),
+        ],
+    ),
+    (
+        MessageId::InvalidVerseNumber,
+        &[
+            (
+                Language::ENGLISH,
+                "Invalid verse number in reference: {reference}",
+            ),
+            (
+                Language::SPANISH,
+                "Número de versículo no válido en la referencia: {reference}",
+            ),
+            (
+                Language::PORTUGUESE,
+                "Número de versículo inválido na referência: {reference}",
+            ),
+            (
+                Language::FRENCH,
+                "Numéro de verset non valide dans la référence : {reference}",
+            ),
+            (
+                Language::GERMAN,
+                "Ungültige Versnummer in der Schriftstelle: {reference}",
+            ),
+        ],
+    ),
+    (
+        MessageId::UnknownBook,
+        &[
+            (
+                Language::ENGLISH,
+                "Unknown book abbreviation: '{book}'. Please check the spelling.",
+            ),
+            (
+                Language::SPANISH,
+                "Abreviatura de libro desconocida: '{book}'. Compruebe la ortografía.",
+            ),
+            (
+                Language::PORTUGUESE,
+                "Abreviatura de livro desconhecida: '{book}'. Verifique a ortografia.",
+            ),
+            (
+                Language::FRENCH,
+                "Abréviation de livre inconnue : '{book}'. Vérifiez l'orthographe.",
+            ),
+            (
+                Language::GERMAN,
+                "Unbekannte Buchabkürzung: '{book}'. Bitte prüfen Sie die Schreibweise.",
+            ),
+        ],
+    ),
+    (
+        MessageId::UnknownBookWithSuggestions,
+        &[
+            (
+                Language::ENGLISH,
+                "Unknown book abbreviation: '{book}'. Did you mean: {suggestions}?",
+            ),
+            (
+                Language::SPANISH,
+                "Abreviatura de libro desconocida: '{book}'. ¿Quiso decir: {suggestions}?",
+            ),
+            (
+                Language::PORTUGUESE,
+                "Abreviatura de livro desconhecida: '{book}'. Você quis dizer: {suggestions}?",
+            ),
+            (
+                Language::FRENCH,
+                "Abréviation de livre inconnue : '{book}'. Vouliez-vous dire : {suggestions} ?",
+            ),
+            (
+                Language::GERMAN,
+                "Unbekannte Buchabkürzung: '{book}'. Meinten Sie: {suggestions}?",
+            ),
+        ],
+    ),
+    (
+        MessageId::ChapterZero,
+        &[
+            (Language::ENGLISH, "Chapter number must be greater than 0"),
+            (
+                Language::SPANISH,
+                "El número de capítulo debe ser mayor que 0",
+            ),
+            (
+                Language::PORTUGUESE,
+                "O número do capítulo deve ser maior que 0",
+            ),
+            (
+                Language::FRENCH,
+                "Le numéro de chapitre doit être supérieur à 0",
+            ),
+            (Language::GERMAN, "Die Kapitelnummer muss größer als 0 sein"),
+        ],
+    ),
+    (
+        MessageId::ChapterNotFound,
+        &[
+            (
+                Language::ENGLISH,
+                "Chapter {chapter} does not exist in {book}. {book} has {total} chapters (1-{total})",
+            ),
+            (
+                Language::SPANISH,
+                "El capítulo {chapter} no existe en {book}. {book} tiene {total} capítulos (1-{total})",
+            ),
+            (
+                Language::PORTUGUESE,
+                "O capítulo {chapter} não existe em {book}. {book} tem {total} capítulos (1-{total})",
+            ),
+            (
+                Language::FRENCH,
+                "Le chapitre {chapter} n'existe pas dans {book}. {book} compte {total} chapitres (1-{total})",
+            ),
+            (
+                Language::GERMAN,
+                "Kapitel {chapter} gibt es in {book} nicht. {book} hat {total} Kapitel (1-{total})",
+            ),
+        ],
+    ),
+    (
+        MessageId::VerseZero,
+        &[
+            (Language::ENGLISH, "Verse number must be greater than 0"),
+            (
+                Language::SPANISH,
+                "El número de versículo debe ser mayor que 0",
+            ),
+            (
+                Language::PORTUGUESE,
+                "O número do versículo deve ser maior que 0",
+            ),
+            (
+                Language::FRENCH,
+                "Le numéro de verset doit être supérieur à 0",
+            ),
+            (Language::GERMAN, "Die Versnummer muss größer als 0 sein"),
+        ],
+    ),
+    (
+        MessageId::VerseNotFound,
+        &[
+            (
+                Language::ENGLISH,
+                "Verse {verse} does not exist in {book} {chapter}. Chapter {chapter} has {total} verses (1-{total})",
+            ),
+            (
+                Language::SPANISH,
+                "El versículo {verse} no existe en {book} {chapter}. El capítulo {chapter} tiene {total} versículos (1-{total})",
+            ),
+            (
+                Language::PORTUGUESE,
+                "O versículo {verse} não existe em {book} {chapter}. O capítulo {chapter} tem {total} versículos (1-{total})",
+            ),
+            (
+                Language::FRENCH,
+                "Le verset {verse} n'existe pas dans {book} {chapter}. Le chapitre {chapter} compte {total} versets (1-{total})",
+            ),
+            (
+                Language::GERMAN,
+                "Vers {verse} gibt es in {book} {chapter} nicht. Kapitel {chapter} hat {total} Verse (1-{total})",
+            ),
+        ],
+    ),
+    (
+        MessageId::EndVerseBeforeStart,
+        &[
+            (
+                Language::ENGLISH,
+                "End verse ({end}) cannot be less than start verse ({start})",
+            ),
+            (
+                Language::SPANISH,
+                "El versículo final ({end}) no puede ser menor que el inicial ({start})",
+            ),
+            (
+                Language::PORTUGUESE,
+                "O versículo final ({end}) não pode ser menor que o inicial ({start})",
+            ),
+            (
+                Language::FRENCH,
+                "Le verset final ({end}) ne peut pas être inférieur au verset initial ({start})",
+            ),
+            (
+                Language::GERMAN,
+                "Der letzte Vers ({end}) darf nicht kleiner als der erste Vers ({start}) sein",
+            ),
+        ],
+    ),
+    (
+        MessageId::NoVerseData,
+        &[
+            (
+                Language::ENGLISH,
+                "No chapter and verse data is available for {book}",
+            ),
+            (
+                Language::SPANISH,
+                "No hay datos de capítulos y versículos para {book}",
+            ),
+            (
+                Language::PORTUGUESE,
+                "Não há dados de capítulos e versículos para {book}",
+            ),
+            (
+                Language::FRENCH,
+                "Aucune donnée de chapitres et de versets n'est disponible pour {book}",
+            ),
+            (
+                Language::GERMAN,
+                "Für {book} sind keine Kapitel- und Versdaten verfügbar",
+            ),
+        ],
+    ),
+];
+
+/// An error from the catalog with the values of its placeholders
+///
+/// It displays in English; [`render`](Self::render) shows it in another language.
+///
+/// # Examples
+///
+/// ```
+/// use scripture_links_lib::aliases::BookAliases;
+/// use scripture_links_lib::messages::MessageId;
+/// use scripture_links_lib::{Language, parse_scripture_reference_with_aliases};
+///
+/// let error =
+///     parse_scripture_reference_with_aliases("Genesis 51:1", &BookAliases::default()).unwrap_err();
+/// assert_eq!(error.id(), MessageId::ChapterNotFound);
+/// assert_eq!(
+///     error.to_string(),
+///     "Chapter 51 does not exist in Genesis. Genesis has 50 chapters (1-50)"
+/// );
+/// assert_eq!(
+///     error.render(Language::SPANISH),
+///     "El capítulo 51 no existe en Génesis. Génesis tiene 50 capítulos (1-50)"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReferenceError {
+    id: MessageId,
+    args: Vec<(&'static str, String)>,
+}
+
+impl ReferenceError {
+    /// The message `id` with the values of its placeholders
+    #[must_use]
+    pub fn new(id: MessageId, args: &[(&'static str, &str)]) -> Self {
+        Self {
+            id,
+            args: args
+                .iter()
+                .map(|(name, value)| (*name, (*value).to_string()))
+                .collect(),
+        }
+    }
+
+    /// Which message this is
+    #[must_use]
+    pub const fn id(&self) -> MessageId {
+        self.id
+    }
+
+    /// The value of placeholder `name`, such as the suggestions for an unknown book
+    #[must_use]
+    pub fn arg(&self, name: &str) -> Option<&str> {
+        self.args
+            .iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The message in `language`, or in English if it has no translation
+    ///
+    /// Book names in the message are translated too.
+    #[must_use]
+    pub fn render(&self, language: Language) -> String {
+        let mut rendered = String::new();
+        for (literal, placeholder) in template_parts(template(self.id, language)) {
+            rendered.push_str(literal);
+            if let Some(name) = placeholder {
+                let value = self.arg(name).unwrap_or_default();
+                if name == "book" && language != Language::ENGLISH {
+                    rendered.push_str(&translate_book_name(value, language));
+                } else {
+                    rendered.push_str(value);
+                }
+            }
+        }
+        rendered
+    }
+}
+
+impl fmt::Display for ReferenceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render(Language::ENGLISH))
+    }
+}
+
+impl std::error::Error for ReferenceError {}
+
+/// The message language named by a locale such as "es_MX.UTF-8" or "pt-BR"
+///
+/// Two-letter and three-letter language codes are recognized. Returns `None`
+/// for English, "C", "POSIX" and languages without translations.
+#[must_use]
+pub fn language_from_locale(locale: &str) -> Option<Language> {
+    let code = locale
+        .split(['_', '-', '.', '@'])
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+    let language = match code.as_str() {
+        "es" => Language::SPANISH,
+        "pt" => Language::PORTUGUESE,
+        "fr" => Language::FRENCH,
+        "de" => Language::GERMAN,
+        _ => Language::new(&code).ok()?,
+    };
+    MESSAGE_LANGUAGES.contains(&language).then_some(language)
+}
+
+/// The message language of the environment, from `LC_ALL`, `LC_MESSAGES` or `LANG`
+#[must_use]
+pub fn language_from_env() -> Language {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty())
+        .and_then(|locale| language_from_locale(&locale))
+        .unwrap_or_default()
+}
+
+/// Parse a language code for messages, such as "spa"
+///
+/// # Errors
+/// Returns an error if the code is invalid or messages are not translated into it.
+pub fn parse_message_language(code: &str) -> Result<Language, String> {
+    let language = Language::new(code)?;
+    if language == Language::ENGLISH || MESSAGE_LANGUAGES.contains(&language) {
+        return Ok(language);
+    }
+    let supported: Vec<&str> = std::iter::once(Language::ENGLISH.code())
+        .chain(MESSAGE_LANGUAGES.iter().map(Language::code))
+        .collect();
+    Err(format!(
+        "Messages are not available in '{language}'; expected one of {}",
+        supported.join(", ")
+    ))
+}
+
+fn template(id: MessageId, language: Language) -> &'static str {
+    let translations = CATALOG
+        .iter()
+        .find(|(candidate, _)| *candidate == id)
+        .map_or(&[][..], |(_, translations)| *translations);
+    translations
+        .iter()
+        .find(|(candidate, _)| *candidate == language)
+        .or_else(|| translations.first())
+        .map_or("", |(_, template)| template)
+}
+
+/// Split a template into literal text, each followed by the placeholder after it
+fn template_parts(template: &str) -> Vec<TemplatePart<'_>> {
+    let mut parts = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        parts.push((&rest[..start], Some(&rest[start + 1..start + end])));
+        rest = &rest[start + end + 1..];
+    }
+    parts.push((rest, None));
+    parts
+}
+
+/// The name of a built-in book in `language`, given its English display name
+fn translate_book_name(name: &str, language: Language) -> String {
+    BOOK_DISPLAY_NAMES
+        .iter()
+        .find(|(_, english)| *english == name)
+        .and_then(|(slug, _)| localized_display_name(slug, language))
+        .unwrap_or(name)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_message_is_translated() {
+        for (id, translations) in CATALOG {
+            let english: Vec<_> = template_parts(template(*id, Language::ENGLISH))
+                .into_iter()
+                .filter_map(|(_, placeholder)| placeholder)
+                .collect();
+            for &language in MESSAGE_LANGUAGES {
+                let (_, translated) = translations
+                    .iter()
+                    .find(|(candidate, _)| *candidate == language)
+                    .unwrap_or_else(|| panic!("{id:?} has no {language} translation"));
+                for placeholder in template_parts(translated)
+                    .into_iter()
+                    .filter_map(|(_, placeholder)| placeholder)
+                {
+                    assert!(english.contains(&placeholder), "{id:?} {language}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_errors_render_in_each_language() {
+        let error = ReferenceError::new(
+            MessageId::VerseNotFound,
+            &[
+                ("verse", "30"),
+                ("book", "1 Nephi"),
+                ("chapter", "3"),
+                ("total", "31"),
+            ],
+        );
+        assert_eq!(
+            error.to_string(),
+            "Verse 30 does not exist in 1 Nephi 3. Chapter 3 has 31 verses (1-31)"
+        );
+        assert_eq!(
+            error.render(Language::PORTUGUESE),
+            "O versículo 30 não existe em 1 Néfi 3. O capítulo 3 tem 31 versículos (1-31)"
+        );
+        assert_eq!(error.render(Language::ENGLISH), error.to_string());
+
+        let error = ReferenceError::new(
+            MessageId::UnknownBookWithSuggestions,
+            &[("book", "Gn"), ("suggestions", "Gen, Gal")],
+        );
+        assert_eq!(
+            error.render(Language::GERMAN),
+            "Unbekannte Buchabkürzung: 'Gn'. Meinten Sie: Gen, Gal?"
+        );
+        assert_eq!(error.arg("suggestions"), Some("Gen, Gal"));
+        // Languages without translations fall back to English
+        assert_eq!(
+            error.render(Language::new("ita").unwrap()),
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn test_language_from_locale() {
+        assert_eq!(language_from_locale("es_MX.UTF-8"), Some(Language::SPANISH));
+        assert_eq!(language_from_locale("pt-BR"), Some(Language::PORTUGUESE));
+        assert_eq!(language_from_locale("deu"), Some(Language::GERMAN));
+        assert_eq!(language_from_locale("en_US.UTF-8"), None);
+        assert_eq!(language_from_locale("C"), None);
+        assert_eq!(language_from_locale("it_IT"), None);
+        assert!(parse_message_language("ita").is_err());
+        assert_eq!(parse_message_language("FRA").unwrap(), Language::FRENCH);
+    }
+}
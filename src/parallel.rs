@@ -0,0 +1,102 @@
+//! Ordered parallel mapping over a fixed number of worker threads
+
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Number of worker threads to use when none is requested
+#[must_use]
+pub fn default_jobs() -> usize {
+    std::thread::available_parallelism().map_or(1, NonZeroUsize::get)
+}
+
+/// Apply `f` to every item using up to `jobs` threads
+///
+/// Workers pull the next unprocessed item from a shared counter, so slow items
+/// do not hold up a whole batch. Results are returned in the order of `items`
+/// regardless of which thread produced them.
+///
+/// # Panics
+/// Panics if `f` panics on any worker thread.
+///
+/// # Examples
+///
+/// ```
+/// use scripture_links_lib::parallel::map_ordered;
+///
+/// let squares = map_ordered(&[1, 2, 3, 4], 2, |n| n * n);
+/// assert_eq!(squares, vec![1, 4, 9, 16]);
+/// ```
+pub fn map_ordered<T, R, F>(items: &[T], jobs: usize, f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let jobs = jobs.clamp(1, items.len().max(1));
+    if jobs == 1 {
+        return items.iter().map(f).collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let mut results: Vec<(usize, R)> = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..jobs)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = Vec::new();
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(item) = items.get(index) else {
+                            break;
+                        };
+                        done.push((index, f(item)));
+                    }
+                    done
+                })
+            })
+            .collect();
+
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("worker thread panicked"))
+            .collect()
+    });
+
+    results.sort_unstable_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_results_keep_input_order() {
+        let items: Vec<u64> = (0..200).collect();
+        let results = map_ordered(&items, 8, |n| {
+            // Uneven work so threads finish out of order
+            std::thread::sleep(std::time::Duration::from_micros((200 - n) * 10));
+            n * 2
+        });
+        assert_eq!(results, items.iter().map(|n| n * 2).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_uses_multiple_threads() {
+        let threads = Mutex::new(HashSet::new());
+        let items: Vec<u32> = (0..64).collect();
+        let _ = map_ordered(&items, 4, |_| {
+            std::thread::sleep(std::time::Duration::from_millis(1));
+            threads.lock().unwrap().insert(std::thread::current().id());
+        });
+        assert!(threads.into_inner().unwrap().len() > 1);
+    }
+
+    #[test]
+    fn test_empty_input_and_zero_jobs() {
+        let empty: Vec<u32> = Vec::new();
+        assert!(map_ordered(&empty, 4, |n| *n).is_empty());
+        assert_eq!(map_ordered(&[1, 2], 0, |n| n + 1), vec![2, 3]);
+    }
+}
@@ -1,9 +1,24 @@
 //! Scripture reference parsing functionality
 
-use crate::abbreviations::create_abbreviation_map;
+use crate::abbreviations::{AbbreviationMap, BOOK_DISPLAY_NAMES, create_abbreviation_map};
+use crate::aliases::BookAliases;
+use crate::language::Language;
+use crate::localized_names::localized_display_name;
+use crate::messages::{MessageId, ReferenceError};
 use crate::scripture_data;
 use crate::types::{ScriptureReference, StandardWork};
+use crate::works::CustomWorks;
 use regex::Regex;
+use std::sync::LazyLock;
+
+static ABBREVIATIONS: LazyLock<AbbreviationMap> = LazyLock::new(create_abbreviation_map);
+
+// Regex to match scripture references with optional verse ranges
+// Examples: "Isa. 6:5", "Isa.6:5", "2 Ne. 10:14-15", "2Ne.10:14-15", "D&C 128:22-23"
+// This regex captures everything before the chapter:verse pattern as the book
+// The \s* makes the space between book and chapter optional
+static REFERENCE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(.+?)\s*(\d+):(\d+)(?:-(\d+))?$").unwrap());
 
 /// Parse a scripture reference string into a structured format
 ///
@@ -19,96 +34,307 @@ use regex::Regex;
 /// ```
 ///
 /// # Errors
-/// Returns an error if the reference format is invalid, the book is unknown,
-/// or if chapter/verse numbers are invalid.
+/// Returns an error in English if the reference format is invalid, the book is
+/// unknown, or if chapter/verse numbers are invalid; the other parse functions
+/// return a [`ReferenceError`] that can be shown in other languages.
 ///
 /// # Panics
 /// Panics if the internal regex pattern is invalid (should never happen).
 pub fn parse_scripture_reference(reference: &str) -> Result<ScriptureReference, String> {
-    let abbreviations = create_abbreviation_map();
+    parse_scripture_reference_with_aliases(reference, &BookAliases::default())
+        .map_err(|error| error.to_string())
+}
 
-    // Regex to match scripture references with optional verse ranges
-    // Examples: "Isa. 6:5", "Isa.6:5", "2 Ne. 10:14-15", "2Ne.10:14-15", "D&C 128:22-23"
-    // This regex captures everything before the chapter:verse pattern as the book
-    // The \s* makes the space between book and chapter optional
-    let re = Regex::new(r"^(.+?)\s*(\d+):(\d+)(?:-(\d+))?$").unwrap();
+/// Parse a scripture reference, looking up user-defined book aliases first
+///
+/// # Examples
+///
+/// ```
+/// use scripture_links_lib::aliases::BookAliases;
+/// use scripture_links_lib::parse_scripture_reference_with_aliases;
+///
+/// let aliases = BookAliases::new([("Mos.", "mosiah")]).unwrap();
+/// let result = parse_scripture_reference_with_aliases("Mos. 3:19", &aliases).unwrap();
+/// assert_eq!(result.book, "mosiah");
+/// ```
+///
+/// # Errors
+/// Returns an error if the reference format is invalid, the book is unknown,
+/// or if chapter/verse numbers are invalid.
+///
+/// # Panics
+/// Panics if the internal regex pattern is invalid (should never happen).
+pub fn parse_scripture_reference_with_aliases(
+    reference: &str,
+    aliases: &BookAliases,
+) -> Result<ScriptureReference, ReferenceError> {
+    parse_scripture_reference_with_works(reference, aliases, &CustomWorks::default())
+}
 
-    if let Some(captures) = re.captures(reference.trim()) {
+/// Parse a scripture reference, looking up user-defined book aliases first and
+/// accepting references to `works`
+///
+/// # Examples
+///
+/// ```
+/// use scripture_links_lib::aliases::BookAliases;
+/// use scripture_links_lib::parse_scripture_reference_with_works;
+/// use scripture_links_lib::works::{CustomWork, CustomWorks};
+///
+/// let works = CustomWorks::new([CustomWork {
+///     id: "lof".to_string(),
+///     name: "Lectures on Faith".to_string(),
+///     abbreviations: vec!["LoF".to_string()],
+///     url: "https://example.org/lectures-on-faith/{chapter}#p{verse}".to_string(),
+///     chapters: Some(7),
+///     verses: None,
+/// }])
+/// .unwrap();
+/// let aliases = BookAliases::default();
+/// assert_eq!(parse_scripture_reference_with_works("LoF 3:4", &aliases, &works).unwrap().book, "lof");
+/// assert!(parse_scripture_reference_with_works("LoF 8:1", &aliases, &works).is_err());
+/// ```
+///
+/// # Errors
+/// Returns an error if the reference format is invalid, the book is unknown,
+/// or if chapter/verse numbers are invalid.
+///
+/// # Panics
+/// Panics if the internal regex pattern is invalid (should never happen).
+pub fn parse_scripture_reference_with_works(
+    reference: &str,
+    aliases: &BookAliases,
+    works: &CustomWorks,
+) -> Result<ScriptureReference, ReferenceError> {
+    if let Some(captures) = REFERENCE_RE.captures(reference.trim()) {
         let book_abbrev = captures
             .get(1)
             .unwrap()
             .as_str()
             .trim()
             .trim_end_matches('.');
-        let chapter: u32 = captures
-            .get(2)
-            .unwrap()
-            .as_str()
-            .parse()
-            .map_err(|_| format!("Invalid chapter number in reference: {reference}"))?;
-        let verse_start: u32 = captures
-            .get(3)
-            .unwrap()
-            .as_str()
-            .parse()
-            .map_err(|_| format!("Invalid verse number in reference: {reference}"))?;
+        let chapter: u32 = captures.get(2).unwrap().as_str().parse().map_err(|_| {
+            ReferenceError::new(MessageId::InvalidChapterNumber, &[("reference", reference)])
+        })?;
+        let verse_start: u32 = captures.get(3).unwrap().as_str().parse().map_err(|_| {
+            ReferenceError::new(MessageId::InvalidVerseNumber, &[("reference", reference)])
+        })?;
         let verse_end: Option<u32> = captures.get(4).and_then(|m| m.as_str().parse().ok());
 
-        // Case-insensitive lookup
-        let lookup_result = abbreviations
-            .iter()
-            .find(|(key, _)| key.to_lowercase() == book_abbrev.to_lowercase())
-            .map(|(_, value)| value);
+        let (book_url, standard_work) = lookup_book(book_abbrev, aliases, works)?;
 
-        if let Some((book_url, standard_work)) = lookup_result {
+        if standard_work == StandardWork::Custom {
+            // Custom works carry their own (optional) chapter and verse counts
+            if let Some(work) = works.get(&book_url) {
+                work.validate_reference(chapter, verse_start, verse_end)?;
+            }
+        } else {
             // Validate chapter range
-            scripture_data::validate_chapter_range(book_url, chapter)?;
+            scripture_data::check_chapter_range(&book_url, chapter)?;
 
             // Validate verse range
-            scripture_data::validate_verse_range(book_url, chapter, verse_start, verse_end)?;
-
-            Ok(ScriptureReference {
-                book: (*book_url).to_string(),
-                chapter,
-                verse_start,
-                verse_end,
-                standard_work: match standard_work {
-                    StandardWork::OldTestament => StandardWork::OldTestament,
-                    StandardWork::NewTestament => StandardWork::NewTestament,
-                    StandardWork::BookOfMormon => StandardWork::BookOfMormon,
-                    StandardWork::DoctrineAndCovenants => StandardWork::DoctrineAndCovenants,
-                    StandardWork::PearlOfGreatPrice => StandardWork::PearlOfGreatPrice,
-                    StandardWork::StudyHelps => StandardWork::StudyHelps,
-                },
-                topic: None, // Regular scripture references don't have topics
-            })
-        } else {
-            // Find similar abbreviations for suggestions (case-insensitive)
-            let similar: Vec<&str> = abbreviations
-                .keys()
-                .filter(|&key| {
-                    key.to_lowercase().contains(&book_abbrev.to_lowercase())
-                        || book_abbrev.to_lowercase().contains(&key.to_lowercase())
-                })
-                .take(3)
-                .copied()
-                .collect();
-
-            if similar.is_empty() {
-                Err(format!(
-                    "Unknown book abbreviation: '{book_abbrev}'. Please check the spelling."
-                ))
-            } else {
-                Err(format!(
-                    "Unknown book abbreviation: '{}'. Did you mean: {}?",
-                    book_abbrev,
-                    similar.join(", ")
-                ))
-            }
+            scripture_data::check_verse_range(&book_url, chapter, verse_start, verse_end)?;
         }
+
+        Ok(ScriptureReference {
+            book: book_url,
+            chapter,
+            verse_start,
+            verse_end,
+            standard_work,
+            topic: None, // Regular scripture references don't have topics
+        })
     } else {
-        Err(format!(
-            "Invalid scripture reference format: '{reference}'. Expected format: 'Book Chapter:Verse' or 'Book Chapter:Verse-Verse'"
+        Err(ReferenceError::new(
+            MessageId::InvalidFormat,
+            &[("reference", reference)],
+        ))
+    }
+}
+
+/// Verse URLs on ChurchofJesusChrist.org, as built by [`crate::generate_url_with_lang`]
+static SCRIPTURE_URL_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^https://www\.churchofjesuschrist\.org/study/scriptures/([a-z-]+)/([a-z0-9-]+)/(\d+)\?lang=([a-z]{3})&id=p(\d+)(?:-p?(\d+))?(?:#p\d+)?$",
+    )
+    .unwrap()
+});
+
+/// The reference and page language of a verse URL on ChurchofJesusChrist.org
+///
+/// Only verse URLs in the form this crate generates are recognized (ranges may
+/// also be written `id=p14-p15`, as the Gospel Library shares them); chapter,
+/// Study Helps and other sites' URLs return `None`, as do verses that do not exist.
+///
+/// # Examples
+///
+/// ```
+/// use scripture_links_lib::Language;
+/// use scripture_links_lib::parser::parse_scripture_url;
+///
+/// let (reference, lang) = parse_scripture_url(
+///     "https://www.churchofjesuschrist.org/study/scriptures/bofm/2-ne/10?lang=spa&id=p14-15#p14",
+/// )
+/// .unwrap();
+/// assert_eq!((reference.book.as_str(), reference.chapter), ("2-ne", 10));
+/// assert_eq!((reference.verse_start, reference.verse_end), (14, Some(15)));
+/// assert_eq!(lang, Language::SPANISH);
+/// assert!(parse_scripture_url("https://example.org/ot/gen/1?lang=eng&id=p1").is_none());
+/// ```
+///
+/// # Panics
+/// Panics if the internal regex pattern is invalid (should never happen).
+#[must_use]
+pub fn parse_scripture_url(url: &str) -> Option<(ScriptureReference, Language)> {
+    let captures = SCRIPTURE_URL_RE.captures(url.trim())?;
+    let (book, standard_work) = ABBREVIATIONS.values().find(|(book, work)| {
+        *book == &captures[2] && !work.is_study_help() && work.to_url_path() == &captures[1]
+    })?;
+    let chapter: u32 = captures[3].parse().ok()?;
+    let verse_start: u32 = captures[5].parse().ok()?;
+    let verse_end: Option<u32> = match captures.get(6) {
+        Some(end) => Some(end.as_str().parse().ok()?),
+        None => None,
+    };
+    scripture_data::validate_verse_range(book, chapter, verse_start, verse_end).ok()?;
+    let reference = ScriptureReference {
+        book: (*book).to_string(),
+        chapter,
+        verse_start,
+        verse_end,
+        standard_work: standard_work.clone(),
+        topic: None,
+    };
+    Some((reference, Language::new(&captures[4]).ok()?))
+}
+
+/// The book a note is named after, such as "Genesis" in `[[Genesis 1]]`: a name
+/// [`lookup_book`] accepts or the display name of a book in `display_lang`, with
+/// the language of the name when it is a localized one
+pub(crate) fn resolve_note_book(
+    name: &str,
+    aliases: &BookAliases,
+    works: &CustomWorks,
+    display_lang: Language,
+) -> Option<LocalizedResolvedBook> {
+    if let Some(resolved) = resolve_book(name, aliases, works) {
+        return Some(resolved);
+    }
+    let (slug, _) = BOOK_DISPLAY_NAMES
+        .iter()
+        .find(|(slug, _)| localized_display_name(slug, display_lang) == Some(name))?;
+    ABBREVIATIONS
+        .values()
+        .find(|(book, _)| book == slug)
+        .map(|(book, work)| (((*book).to_string(), work.clone()), Some(display_lang)))
+}
+
+/// The book slug and standard work a book name resolves to
+pub(crate) type ResolvedBook = (String, StandardWork);
+
+/// A resolved book and the language of its name, if localized
+pub(crate) type LocalizedResolvedBook = (ResolvedBook, Option<Language>);
+
+/// The language a reference's book name is written in, when it is a name from
+/// one of the languages enabled in `aliases` rather than an English name or alias
+///
+/// Custom works cannot take a name of a book in any language, so their names
+/// never have one.
+///
+/// # Examples
+///
+/// ```
+/// use scripture_links_lib::Language;
+/// use scripture_links_lib::aliases::BookAliases;
+/// use scripture_links_lib::parser::reference_language;
+///
+/// let mut aliases = BookAliases::default();
+/// aliases.enable_language(Language::SPANISH).unwrap();
+/// assert_eq!(reference_language("Juan 3:16", &aliases), Some(Language::SPANISH));
+/// assert_eq!(reference_language("John 3:16", &aliases), None);
+/// ```
+///
+/// # Panics
+/// Panics if the internal regex pattern is invalid (should never happen).
+#[must_use]
+pub fn reference_language(reference: &str, aliases: &BookAliases) -> Option<Language> {
+    let captures = REFERENCE_RE.captures(reference.trim())?;
+    resolve_book(
+        captures.get(1).unwrap().as_str(),
+        aliases,
+        &CustomWorks::default(),
+    )
+    .and_then(|(_, language)| language)
+}
+
+/// Look a book name up in the order described on [`lookup_book`], with the
+/// language of the name when it is a localized one
+fn resolve_book(
+    book_abbrev: &str,
+    aliases: &BookAliases,
+    works: &CustomWorks,
+) -> Option<LocalizedResolvedBook> {
+    let book_abbrev = book_abbrev.trim().trim_end_matches('.');
+    if let Some((book_url, standard_work)) = aliases.get(book_abbrev) {
+        return Some(((book_url.to_string(), standard_work), None));
+    }
+
+    // Case-insensitive lookup
+    let lookup_result = ABBREVIATIONS
+        .iter()
+        .find(|(key, _)| key.to_lowercase() == book_abbrev.to_lowercase())
+        .map(|(_, value)| value);
+
+    if let Some((book_url, standard_work)) = lookup_result {
+        return Some((((*book_url).to_string(), standard_work.clone()), None));
+    }
+    if let Some(work) = works.lookup(book_abbrev) {
+        return Some(((work.id.clone(), StandardWork::Custom), None));
+    }
+    aliases
+        .get_localized(book_abbrev)
+        .map(|((book_url, standard_work), language)| {
+            ((book_url.to_string(), standard_work), Some(language))
+        })
+}
+
+/// Find a book by alias, abbreviation, full name, name of one of `works` or name
+/// in an enabled language (case-insensitive, trailing period ignored)
+///
+/// # Errors
+/// Returns an "Unknown book abbreviation" error, with suggestions when some
+/// known abbreviations are similar.
+pub(crate) fn lookup_book(
+    book_abbrev: &str,
+    aliases: &BookAliases,
+    works: &CustomWorks,
+) -> Result<ResolvedBook, ReferenceError> {
+    if let Some((resolved, _)) = resolve_book(book_abbrev, aliases, works) {
+        return Ok(resolved);
+    }
+    let book_abbrev = book_abbrev.trim().trim_end_matches('.');
+    let abbreviations = &*ABBREVIATIONS;
+
+    // Find similar abbreviations for suggestions (case-insensitive)
+    let similar: Vec<&str> = abbreviations
+        .keys()
+        .filter(|&key| {
+            key.to_lowercase().contains(&book_abbrev.to_lowercase())
+                || book_abbrev.to_lowercase().contains(&key.to_lowercase())
+        })
+        .take(3)
+        .copied()
+        .collect();
+
+    if similar.is_empty() {
+        Err(ReferenceError::new(
+            MessageId::UnknownBook,
+            &[("book", book_abbrev)],
+        ))
+    } else {
+        Err(ReferenceError::new(
+            MessageId::UnknownBookWithSuggestions,
+            &[("book", book_abbrev), ("suggestions", &similar.join(", "))],
         ))
     }
 }
@@ -306,6 +532,22 @@ mod tests {
         assert!(!error.contains("Did you mean:"));
     }
 
+    #[test]
+    fn test_localized_book_names() {
+        let mut aliases = BookAliases::default();
+        assert!(parse_scripture_reference_with_aliases("Juan 3:16", &aliases).is_err());
+        aliases.enable_language(crate::Language::SPANISH).unwrap();
+
+        let result = parse_scripture_reference_with_aliases("Juan 3:16", &aliases).unwrap();
+        assert_eq!(result.book, "john");
+        let result = parse_scripture_reference_with_aliases("génesis 1:1", &aliases).unwrap();
+        assert_eq!(result.book, "gen");
+        let result = parse_scripture_reference_with_aliases("DyC 4:2", &aliases).unwrap();
+        assert_eq!(result.standard_work, StandardWork::DoctrineAndCovenants);
+        // Verse counts are checked as for English names
+        assert!(parse_scripture_reference_with_aliases("1 Nefi 23:1", &aliases).is_err());
+    }
+
     #[test]
     fn test_invalid_chapter_number() {
         let result = parse_scripture_reference("Genesis 0:1");
@@ -1,3 +1,4 @@
+use crate::messages::{MessageId, ReferenceError};
 use std::collections::HashMap;
 use std::sync::LazyLock;
 #[derive(Debug, Clone)]
@@ -805,14 +806,14 @@ static SCRIPTURE_DATA: LazyLock<HashMap<&'static str, BookInfo>> = LazyLock::new
         },
     );
     map.insert(
-        "joseph-smith--history",
+        "js-h",
         BookInfo {
             name: "Joseph Smith--History",
             chapters: vec![75],
         },
     );
     map.insert(
-        "joseph-smith--matthew",
+        "js-m",
         BookInfo {
             name: "Joseph Smith--Matthew",
             chapters: vec![55],
@@ -834,22 +835,43 @@ pub fn get_book_info(book_key: &str) -> Option<&BookInfo> {
 ///
 /// # Errors
 /// Returns an error if the chapter is 0 or exceeds the book's chapter count
-#[allow(clippy::cast_possible_truncation)]
 pub fn validate_chapter_range(book_key: &str, chapter: u32) -> Result<(), String> {
+    check_chapter_range(book_key, chapter).map_err(|error| error.to_string())
+}
+
+/// [`validate_chapter_range`] with an error that can be shown in other languages
+#[allow(clippy::cast_possible_truncation)]
+pub(crate) fn check_chapter_range(book_key: &str, chapter: u32) -> Result<(), ReferenceError> {
     get_book_info(book_key).map_or(Ok(()), |book_info| {
         let total_chapters = book_info.chapters.len() as u32;
         if chapter == 0 {
-            Err("Chapter number must be greater than 0".to_string())
+            Err(ReferenceError::new(MessageId::ChapterZero, &[]))
         } else if chapter > total_chapters {
-            Err(format!(
-                "Chapter {chapter} does not exist in {}. {} has {total_chapters} chapters (1-{total_chapters})",
-                book_info.name, book_info.name
+            Err(ReferenceError::new(
+                MessageId::ChapterNotFound,
+                &[
+                    ("chapter", &chapter.to_string()),
+                    ("book", book_info.name),
+                    ("total", &total_chapters.to_string()),
+                ],
             ))
         } else {
             Ok(())
         }
     })
 }
+/// The error for a verse beyond the end of a chapter
+fn verse_not_found(verse: u32, book_name: &str, chapter: u32, total_verses: u32) -> ReferenceError {
+    ReferenceError::new(
+        MessageId::VerseNotFound,
+        &[
+            ("verse", &verse.to_string()),
+            ("book", book_name),
+            ("chapter", &chapter.to_string()),
+            ("total", &total_verses.to_string()),
+        ],
+    )
+}
 /// Validates that verse numbers exist within the given chapter
 ///
 /// # Errors
@@ -860,33 +882,51 @@ pub fn validate_verse_range(
     verse_start: u32,
     verse_end: Option<u32>,
 ) -> Result<(), String> {
+    check_verse_range(book_key, chapter, verse_start, verse_end).map_err(|error| error.to_string())
+}
+
+/// [`validate_verse_range`] with an error that can be shown in other languages
+pub(crate) fn check_verse_range(
+    book_key: &str,
+    chapter: u32,
+    verse_start: u32,
+    verse_end: Option<u32>,
+) -> Result<(), ReferenceError> {
     match get_book_info(book_key) {
         Some(book_info) => {
             // First validate the chapter exists
-            validate_chapter_range(book_key, chapter)?;
+            check_chapter_range(book_key, chapter)?;
             let chapter_index = (chapter - 1) as usize;
             let total_verses = book_info.chapters[chapter_index];
             // Validate start verse
             if verse_start == 0 {
-                return Err("Verse number must be greater than 0".to_string());
+                return Err(ReferenceError::new(MessageId::VerseZero, &[]));
             }
             if verse_start > total_verses {
-                return Err(format!(
-                    "Verse {} does not exist in {} {}. Chapter {} has {} verses (1-{})",
-                    verse_start, book_info.name, chapter, chapter, total_verses, total_verses
+                return Err(verse_not_found(
+                    verse_start,
+                    book_info.name,
+                    chapter,
+                    total_verses,
                 ));
             }
             // Validate end verse if provided
             if let Some(end_verse) = verse_end {
                 if end_verse < verse_start {
-                    return Err(format!(
-                        "End verse ({end_verse}) cannot be less than start verse ({verse_start})"
+                    return Err(ReferenceError::new(
+                        MessageId::EndVerseBeforeStart,
+                        &[
+                            ("end", &end_verse.to_string()),
+                            ("start", &verse_start.to_string()),
+                        ],
                     ));
                 }
                 if end_verse > total_verses {
-                    return Err(format!(
-                        "Verse {} does not exist in {} {}. Chapter {} has {} verses (1-{})",
-                        end_verse, book_info.name, chapter, chapter, total_verses, total_verses
+                    return Err(verse_not_found(
+                        end_verse,
+                        book_info.name,
+                        chapter,
+                        total_verses,
                     ));
                 }
             }
@@ -935,6 +975,14 @@ mod tests {
         assert!(validate_verse_range("gen", 1, 25, Some(32)).is_err()); // End verse too high
     }
     #[test]
+    fn test_joseph_smith_books_use_their_slugs() {
+        assert_eq!(get_book_info("js-h").unwrap().chapters, vec![75]);
+        assert_eq!(get_book_info("js-m").unwrap().chapters, vec![55]);
+        assert!(validate_verse_range("js-h", 1, 76, None).is_err());
+        assert!(validate_verse_range("js-m", 1, 56, None).is_err());
+        assert!(validate_chapter_range("js-h", 2).is_err());
+    }
+    #[test]
     fn test_unknown_book() {
         // Unknown books should not cause errors (graceful fallback)
         assert!(validate_chapter_range("unknown", 100).is_ok());
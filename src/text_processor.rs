@@ -1,10 +1,24 @@
 //! Text processing for finding and converting scripture references to markdown links
 
-use crate::abbreviations::{book_slug_to_display_name, create_abbreviation_map};
-use crate::parser::parse_scripture_reference;
-use crate::types::OutputFormat;
-use crate::url_generator::generate_url;
+use crate::abbreviations::{AbbreviationMap, create_abbreviation_map};
+use crate::aliases::BookAliases;
+use crate::html::{HtmlLink, unescape_html};
+use crate::language::Language;
+use crate::localized_names::{localized_book_names, name_pattern};
+use crate::parser::{
+    parse_scripture_reference_with_works, parse_scripture_url, reference_language,
+    resolve_note_book,
+};
+use crate::scripture_data;
+use crate::types::{OutputFormat, ScriptureReference, StandardWork};
+use crate::url_generator::{generate_url_with_lang, generate_url_with_works};
+use crate::url_provider::{UrlProvider, UrlProviders};
+use crate::wikilink::{StudyHelpsTemplate, WikilinkTemplate};
+use crate::works::CustomWorks;
 use regex::Regex;
+use std::io::{self, BufRead, Write};
+use std::ops::Range;
+use std::sync::LazyLock;
 
 /// Process text and convert scripture references to markdown links
 ///
@@ -32,7 +46,12 @@ pub fn process_text_with_format(
     format: OutputFormat,
     include_study_helps: bool,
 ) -> String {
-    process_text_with_format_impl(text, format, include_study_helps)
+    TextProcessor::new(ProcessorOptions {
+        format,
+        include_study_helps,
+        ..ProcessorOptions::default()
+    })
+    .process(text)
 }
 
 /// Process text with options for including Study Helps
@@ -59,181 +78,921 @@ pub fn process_text_with_options(text: &str, include_study_helps: bool) -> Strin
     process_text_with_format(text, OutputFormat::Markdown, include_study_helps)
 }
 
-#[allow(clippy::too_many_lines)]
-#[must_use]
-fn process_text_with_format_impl(
-    text: &str,
-    format: OutputFormat,
-    include_study_helps: bool,
-) -> String {
-    const fn range_overlaps(a: &std::ops::Range<usize>, b: &std::ops::Range<usize>) -> bool {
-        a.start < b.end && a.end > b.start
-    }
-
-    // Create a more comprehensive regex to find scripture references in text
-    // This should match patterns like:
-    // - "See Genesis 1:1 for more details"
-    // - "According to 2 Nephi 10:14-15"
-    // - "The scripture in D&C 128:22 says"
-    // - "As stated in Matt. 5:3-4"
+/// Existing markdown links `[text](url)`, wikilinks `[[note|text]]` and HTML
+/// anchors `<a href="url">text</a>`; text inside them is never converted
+static LINK_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\[\[[^\]]*\]\]|\[[^\]]*\]\([^)]*\)|<a\b[^>]*>[^<]*</a>").unwrap()
+});
+
+/// Markdown links with their text and URL, for converting existing links
+static MARKDOWN_LINK_PARTS_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\[([^\]]*)\]\(([^)\s]*)\)").unwrap());
+
+/// HTML anchors with their URL and text, for converting existing links
+static HTML_LINK_PARTS_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"<a\s[^>]*?\bhref="([^"]*)"[^>]*>([^<]*)</a>"#).unwrap());
+
+/// Wikilinks with their target, alias and a `:Verse` suffix, for converting existing links
+static WIKILINK_PARTS_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\[\[([^\]|]*)(?:\|([^\]]*))?\]\](?::(\d+)(?:-(\d+))?)?").unwrap()
+});
+
+/// A chapter note name such as `Scriptures/Old Testament/Genesis 1#^3`
+static CHAPTER_NOTE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(?:.*/)?(.+?) (\d+)(?:#\^?(\d+))?$").unwrap());
+
+/// The page language of a URL
+static URL_LANG_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"[?&]lang=([a-z]{3})\b").unwrap());
+
+/// Options controlling how scripture references in text are linked
+#[derive(Debug, Clone, Default)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct ProcessorOptions {
+    pub format: OutputFormat,
+    /// Whether to also process Study Helps abbreviations (may cause false positives)
+    pub include_study_helps: bool,
+    /// User-defined book names recognized in addition to the built-in abbreviations
+    pub aliases: BookAliases,
+    /// Sites the links point to, per standard work
+    pub urls: UrlProviders,
+    /// Works declared in configuration, recognized like the standard works
+    pub works: CustomWorks,
+    /// Language of the linked pages, except for references written with book
+    /// names of another enabled language, which link to pages in that language
+    pub lang: Language,
+    /// Link every reference in `lang`, whatever language it is written in
+    pub force_lang: bool,
+    /// Language of book names in wikilinks and canonical link text
+    pub display_lang: Language,
+    /// Replace the book name as written with its full name in `display_lang`
+    /// (e.g., "Gén. 1:1" becomes "Génesis 1:1")
+    pub canonicalize: bool,
+    /// How wikilinks are written
+    pub wikilink: WikilinkTemplate,
+    /// Note path prefix for wikilinks, such as `Scriptures/{work_name}/`
+    pub wikilink_path: String,
+    /// How Study Helps topics are written as wikilinks
+    pub study_helps_wikilink: StudyHelpsTemplate,
+    /// Class and attributes of HTML anchors
+    pub html: HtmlLink,
+    /// Also rewrite existing scripture links in `format`: Markdown links to verses
+    /// and Study Helps on ChurchofJesusChrist.org, and wikilinks to chapter notes
+    pub convert: bool,
+    /// Replace existing scripture links with their text instead of linking
+    /// references, for plain-text output
+    pub unlink: bool,
+}
 
-    let abbreviations = create_abbreviation_map();
-    let mut scripture_patterns = Vec::new();
-    let mut study_helps_patterns = Vec::new();
+impl ProcessorOptions {
+    /// Language of the page linked for a reference written in `detected`
+    #[must_use]
+    pub const fn url_language(&self, detected: Option<Language>) -> Language {
+        match detected {
+            Some(language) if !self.force_lang => language,
+            _ => self.lang,
+        }
+    }
 
-    // Separate scripture abbreviations from Study Helps
-    for (book_abbrev, (_, standard_work)) in &abbreviations {
-        let escaped = regex::escape(book_abbrev);
-        if standard_work.is_study_help() {
-            study_helps_patterns.push(escaped);
+    /// The URL of `scripture` in `lang`: its work's own template for one of
+    /// `works`, otherwise the page on the site configured in `urls`
+    ///
+    /// # Panics
+    /// Panics for a custom work that is not one of `works`.
+    #[must_use]
+    pub fn url(&self, scripture: &ScriptureReference, lang: Language) -> String {
+        if scripture.standard_work == StandardWork::Custom {
+            generate_url_with_works(scripture, &self.works, lang)
         } else {
-            scripture_patterns.push(escaped);
+            self.urls.url(scripture, lang)
         }
     }
 
-    // Sort by length (descending) to match longer book names first
-    // This prevents "1 Ne" from matching before "1 Nephi"
-    scripture_patterns.sort_by_key(|b| std::cmp::Reverse(b.len()));
-    study_helps_patterns.sort_by_key(|b| std::cmp::Reverse(b.len()));
-
-    let mut result = text.to_string();
-
-    // Ranges of existing markdown links [text](url) — do not convert text inside them
-    let markdown_link_ranges: Vec<std::ops::Range<usize>> = {
-        let link_re = Regex::new(r"\[[^\]]*\]\([^)]*\)").unwrap();
-        link_re.find_iter(&result).map(|m| m.range()).collect()
-    };
+    /// Full name of `book` in `display_lang`, including the names of `works`
+    #[must_use]
+    pub fn book_name(&self, book: &str) -> String {
+        self.works.book_name_in(book, self.display_lang)
+    }
+}
 
-    // Process regular scripture references (chapter:verse pattern)
-    if !scripture_patterns.is_empty() {
-        let book_pattern = scripture_patterns.join("|");
-        let pattern = format!(r"\b({book_pattern})\s*\.?\s*(\d+):(\d+)(?:-(\d+))?\b");
-        let re = Regex::new(&pattern).unwrap();
+/// Output of processing a document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessedText {
+    pub text: String,
+    pub references_linked: usize,
+}
 
-        let matches: Vec<_> = re
-            .find_iter(&result)
-            .map(|m| (m.range(), m.as_str().to_string()))
-            .collect();
+/// Precompiled matcher that finds and links scripture references in text
+///
+/// Building a processor compiles its patterns once, so a single instance should
+/// be reused (and may be shared across threads) when processing many documents.
+///
+/// # Examples
+///
+/// ```
+/// use scripture_links_lib::text_processor::{ProcessorOptions, TextProcessor};
+///
+/// let processor = TextProcessor::new(ProcessorOptions::default());
+/// assert!(processor.process("See Alma 32:21.").contains("[Alma 32:21]("));
+/// assert!(processor.process("And Ether 12:6.").contains("[Ether 12:6]("));
+/// ```
+#[derive(Debug)]
+pub struct TextProcessor {
+    options: ProcessorOptions,
+    abbreviations: AbbreviationMap,
+    scripture_re: Regex,
+    study_helps_re: Regex,
+}
 
-        // Process matches in reverse order to preserve indices
-        for (range, matched_text) in matches.into_iter().rev() {
-            // Skip if already inside [[wikilink]] (avoid double-converting)
-            if range.start >= 2
-                && result.get(range.start.saturating_sub(2)..range.start) == Some("[[")
-            {
-                continue;
+impl TextProcessor {
+    /// Compile the reference patterns for the given options
+    ///
+    /// # Panics
+    /// Panics if the generated regex patterns are invalid (should never happen with escaped abbreviations)
+    #[must_use]
+    pub fn new(options: ProcessorOptions) -> Self {
+        // Match patterns like:
+        // - "See Genesis 1:1 for more details"
+        // - "According to 2 Nephi 10:14-15"
+        // - "The scripture in D&C 128:22 says"
+        // - "As stated in Matt. 5:3-4"
+        let abbreviations = create_abbreviation_map();
+        let mut scripture_patterns = Vec::new();
+        let mut study_helps_patterns = Vec::new();
+
+        // Separate scripture abbreviations from Study Helps
+        for (book_abbrev, (_, standard_work)) in &abbreviations {
+            let escaped = regex::escape(book_abbrev);
+            if standard_work.is_study_help() {
+                study_helps_patterns.push(escaped);
+            } else {
+                scripture_patterns.push(escaped);
             }
-            // Skip if inside an existing markdown link [text](url)
-            if markdown_link_ranges
-                .iter()
-                .any(|link_range| range_overlaps(&range, link_range))
-            {
-                continue;
-            }
-            // Try to parse this as a scripture reference
-            if let Ok(scripture) = parse_scripture_reference(&matched_text) {
-                let replacement = match format {
-                    OutputFormat::Wikilink => {
-                        let display_name = book_slug_to_display_name(&scripture.book)
-                            .unwrap_or(scripture.book.as_str());
-                        let verse_suffix = scripture.verse_end.map_or_else(
-                            || scripture.verse_start.to_string(),
-                            |end| format!("{}-{end}", scripture.verse_start),
-                        );
-                        format!("[[{display_name} {}]]:{verse_suffix}", scripture.chapter)
-                    }
-                    OutputFormat::Markdown => {
-                        let url = generate_url(&scripture);
-                        // Normalize DC to D&C in link text when user wrote DC (no ampersand)
-                        let trimmed = matched_text.trim();
-                        let u = trimmed.to_uppercase();
-                        let link_text = if scripture.book == "dc"
-                            && u.len() >= 2
-                            && u.get(0..2) == Some("DC")
-                            && (u.len() == 2 || u.chars().nth(2) != Some('&'))
-                        {
-                            let verse_part = scripture.verse_end.map_or_else(
-                                || format!("{}:{}", scripture.chapter, scripture.verse_start),
-                                |end| {
-                                    format!(
-                                        "{}:{}-{}",
-                                        scripture.chapter, scripture.verse_start, end
-                                    )
-                                },
-                            );
-                            format!("D&C {verse_part}")
-                        } else {
-                            matched_text.clone()
-                        };
-                        format!("[{link_text}]({url})")
-                    }
-                };
-
-                // Replace the matched text with the link
-                result.replace_range(range, &replacement);
+        }
+        // The pattern allows a period after the book name, so aliases are matched without theirs
+        for alias in options.aliases.iter() {
+            scripture_patterns.push(regex::escape(alias.alias.trim_end_matches('.')));
+        }
+        // Localized names match regardless of case and accents
+        for &language in options.aliases.languages() {
+            for (_, names) in localized_book_names(language).unwrap_or_default() {
+                scripture_patterns.extend(
+                    names
+                        .iter()
+                        .map(|name| name_pattern(name.trim_end_matches('.'))),
+                );
             }
         }
-    }
+        for work in options.works.iter() {
+            scripture_patterns.extend(
+                work.names()
+                    .map(|name| regex::escape(name.trim_end_matches('.'))),
+            );
+        }
+
+        // Sort by length (descending) to match longer book names first
+        // This prevents "1 Ne" from matching before "1 Nephi"
+        scripture_patterns.sort_by_key(|b| std::cmp::Reverse(b.len()));
+        study_helps_patterns.sort_by_key(|b| std::cmp::Reverse(b.len()));
+
+        let book_pattern = scripture_patterns.join("|");
+        let scripture_re = Regex::new(&format!(
+            r"\b({book_pattern})\s*\.?\s*(\d+):(\d+)(?:-(\d+))?\b"
+        ))
+        .unwrap();
 
-    // Process Study Helps if enabled (more restrictive patterns to avoid false positives)
-    if include_study_helps && !study_helps_patterns.is_empty() {
         // Use more restrictive patterns for Study Helps to reduce false positives
         // Look for patterns like "TG Faith", "BD Abraham", "IT Accountability"
-        let study_pattern = study_helps_patterns.join("|");
         // Match abbreviation followed by a capitalized word (topic name)
         // Use non-greedy matching and stop at common word boundaries
-        let pattern = format!(
+        let study_pattern = study_helps_patterns.join("|");
+        let study_helps_re = Regex::new(&format!(
             r"\b({study_pattern})\s+([A-Z][A-Za-z0-9\s,.-]*?)(?:\s+(?:and|or|for|in|on|at|to|with|by|the|a|an)\b|\s*[.!?;]|\s*$)"
-        );
-        let re = Regex::new(&pattern).unwrap();
+        ))
+        .unwrap();
+
+        Self {
+            options,
+            abbreviations,
+            scripture_re,
+            study_helps_re,
+        }
+    }
+
+    /// The options this processor was built with
+    #[must_use]
+    pub const fn options(&self) -> &ProcessorOptions {
+        &self.options
+    }
+
+    /// Convert the scripture references in `text` to links
+    #[must_use]
+    pub fn process(&self, text: &str) -> String {
+        self.process_with_stats(text).text
+    }
 
-        let matches: Vec<_> = re
-            .captures_iter(&result)
-            .map(|caps| {
-                let full_match = caps.get(0).unwrap();
+    /// Convert the scripture references in `text` to links, counting the links created
+    #[must_use]
+    pub fn process_with_stats(&self, text: &str) -> ProcessedText {
+        let replacements = self.find_replacements(text);
+        ProcessedText {
+            text: apply_replacements(text, &replacements),
+            references_linked: replacements.len(),
+        }
+    }
+
+    /// Process text from `reader` into `writer` in bounded memory
+    ///
+    /// Input is read in chunks of 64 kilobytes. The end of each chunk is held
+    /// back and processed with the next one, so references and links spanning
+    /// a chunk boundary are handled exactly as [`process`](Self::process) would,
+    /// as long as a single reference or link is shorter than the 4 kilobytes held back.
+    /// Code blocks are kept whole when links are converted or removed, up to
+    /// 1 megabyte; longer ones are split like other text.
+    ///
+    /// Returns the number of links created.
+    ///
+    /// # Errors
+    /// Returns an error if reading, writing, or UTF-8 decoding fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scripture_links_lib::text_processor::{ProcessorOptions, TextProcessor};
+    ///
+    /// let processor = TextProcessor::new(ProcessorOptions::default());
+    /// let mut output = Vec::new();
+    /// let linked = processor
+    ///     .process_stream("See Moroni 10:4.\n".as_bytes(), &mut output)
+    ///     .unwrap();
+    /// assert_eq!(linked, 1);
+    /// assert!(String::from_utf8(output).unwrap().contains("[Moroni 10:4]("));
+    /// ```
+    pub fn process_stream<R: BufRead, W: Write>(
+        &self,
+        mut reader: R,
+        mut writer: W,
+    ) -> io::Result<usize> {
+        let mut buffer = String::new();
+        // Bytes of a character split by the chunk boundary
+        let mut partial = Vec::new();
+        let mut references_linked = 0;
+
+        loop {
+            let at_end = read_chunk(&mut reader, &mut partial)?;
+            decode_utf8(&mut partial, &mut buffer, at_end)?;
+
+            let replacements = self.find_replacements(&buffer);
+            let mut cut = if at_end {
+                buffer.len()
+            } else {
+                self.stream_cut(&buffer, &replacements)
+            };
+            // A code block longer than the chunk is read on to its end, up to a limit
+            if cut == 0 && buffer.len() >= STREAM_MAX_HELD_BACK {
+                cut = stream_cut(&buffer, &replacements, Vec::new());
+            }
+
+            let committed: Vec<Replacement> = replacements
+                .into_iter()
+                .filter(|replacement| replacement.range.end <= cut)
+                .collect();
+            references_linked += committed.len();
+            writer.write_all(apply_replacements(&buffer[..cut], &committed).as_bytes())?;
+
+            if at_end {
+                break;
+            }
+            buffer.drain(..cut);
+        }
+
+        writer.flush()?;
+        Ok(references_linked)
+    }
+
+    /// Find the scripture references in `text` that processing would link
+    ///
+    /// References inside existing links are skipped. Matches are returned in
+    /// order of appearance and never overlap.
+    ///
+    /// # Panics
+    /// Panics if a regex capture group is missing (should never happen with the compiled patterns)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scripture_links_lib::text_processor::{ProcessorOptions, TextProcessor};
+    ///
+    /// let processor = TextProcessor::new(ProcessorOptions::default());
+    /// let found = processor.find_references("See Alma 32:21 and [Moro 10:4](url).");
+    /// assert_eq!(found.len(), 1);
+    /// assert_eq!(found[0].matched_text, "Alma 32:21");
+    /// assert_eq!(found[0].range, 4..14);
+    /// ```
+    #[must_use]
+    pub fn find_references(&self, text: &str) -> Vec<ReferenceMatch> {
+        let link_ranges = link_ranges(text);
+        let inside_link = |range: &Range<usize>| {
+            link_ranges
+                .iter()
+                .any(|link_range| ranges_overlap(range, link_range))
+        };
+
+        // Regular scripture references (chapter:verse pattern)
+        let mut found: Vec<ReferenceMatch> = self
+            .scripture_re
+            .find_iter(text)
+            .filter(|m| {
+                // Skip if already inside [[wikilink]] (avoid double-converting)
+                text.get(m.start().saturating_sub(2)..m.start()) != Some("[[")
+                    // Skip if inside an existing link [text](url) or [[note|text]]
+                    && !inside_link(&m.range())
+            })
+            .filter_map(|m| {
+                let reference = parse_scripture_reference_with_works(
+                    m.as_str(),
+                    &self.options.aliases,
+                    &self.options.works,
+                )
+                .ok()?;
+                Some(ReferenceMatch {
+                    range: m.range(),
+                    matched_text: m.as_str().to_string(),
+                    reference,
+                    language: reference_language(m.as_str(), &self.options.aliases),
+                })
+            })
+            .collect();
+
+        // Study Helps if enabled (more restrictive patterns to avoid false positives)
+        if self.options.include_study_helps {
+            for caps in self.study_helps_re.captures_iter(text) {
+                let range = caps.get(0).unwrap().range();
                 let abbreviation = caps.get(1).unwrap().as_str();
                 let topic = caps.get(2).unwrap().as_str().trim();
-                (
-                    full_match.range(),
-                    abbreviation.to_string(),
-                    topic.to_string(),
+
+                if inside_link(&range)
+                    || found
+                        .iter()
+                        .any(|existing| ranges_overlap(&range, &existing.range))
+                {
+                    continue;
+                }
+
+                // Look up the abbreviation
+                if let Some((book_url, standard_work)) = self.abbreviations.get(abbreviation)
+                    && standard_work.is_study_help()
+                {
+                    // Only the abbreviation and topic are matched, not the boundary words
+                    // or punctuation that ended the match
+                    let topic_end = caps.get(2).unwrap().start() + topic.len();
+                    found.push(ReferenceMatch {
+                        range: range.start..topic_end,
+                        matched_text: text[range.start..topic_end].to_string(),
+                        reference: ScriptureReference {
+                            book: (*book_url).to_string(),
+                            chapter: 1,     // Not used for Study Helps
+                            verse_start: 1, // Not used for Study Helps
+                            verse_end: None,
+                            standard_work: standard_work.clone(),
+                            topic: Some(topic.to_string()),
+                        },
+                        language: None,
+                    });
+                }
+            }
+            found.sort_by_key(|found| found.range.start);
+        }
+
+        found
+    }
+
+    /// Existing scripture links in `text`, with the text shown for each
+    ///
+    /// Markdown links and HTML anchors are recognized by their URL (see [`parse_scripture_url`]),
+    /// with the language of the page as their language. Wikilinks are recognized
+    /// by a note named after a book and chapter, with the verses in a `:Verse`
+    /// suffix, a `#Verse` or `#^Verse` anchor or their alias. Study Helps links
+    /// are recognized by text such as "TG Faith". Links to the sites in
+    /// [`ProcessorOptions::urls`] are recognized when their text is a reference
+    /// linking to that URL. Other links, and links in fenced code blocks and inline
+    /// code spans, are not returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use scripture_links_lib::text_processor::{ProcessorOptions, TextProcessor};
+    ///
+    /// let processor = TextProcessor::new(ProcessorOptions::default());
+    /// let found = processor.find_links("See [[Genesis 1]]:1-3 and [[Shopping]].");
+    /// assert_eq!(found.len(), 1);
+    /// assert_eq!(found[0].matched_text, "Genesis 1:1-3");
+    /// assert_eq!(found[0].reference.verse_end, Some(3));
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if the internal regex patterns are invalid (should never happen).
+    #[must_use]
+    pub fn find_links(&self, text: &str) -> Vec<ReferenceMatch> {
+        let mut found: Vec<ReferenceMatch> = MARKDOWN_LINK_PARTS_RE
+            .captures_iter(text)
+            .filter_map(|captures| {
+                let link_text = captures.get(1).unwrap().as_str();
+                self.url_link(
+                    captures.get(0).unwrap().range(),
+                    link_text.to_string(),
+                    captures.get(2).unwrap().as_str(),
                 )
             })
             .collect();
+        found.extend(
+            HTML_LINK_PARTS_RE
+                .captures_iter(text)
+                .filter_map(|captures| {
+                    self.url_link(
+                        captures.get(0).unwrap().range(),
+                        unescape_html(captures.get(2).unwrap().as_str()),
+                        &unescape_html(captures.get(1).unwrap().as_str()),
+                    )
+                }),
+        );
+        found.extend(
+            WIKILINK_PARTS_RE
+                .captures_iter(text)
+                .filter_map(|captures| self.wikilink_reference(&captures)),
+        );
+        let code_ranges = code_ranges(text);
+        found.retain(|found| {
+            !code_ranges
+                .iter()
+                .any(|code_range| ranges_overlap(&found.range, code_range))
+        });
+        found.sort_by_key(|found| found.range.start);
+        found
+    }
+
+    /// The reference of a Markdown link or HTML anchor to `url`, recognized by its URL
+    fn url_link(
+        &self,
+        range: Range<usize>,
+        link_text: String,
+        url: &str,
+    ) -> Option<ReferenceMatch> {
+        let url_language = URL_LANG_RE
+            .captures(url)
+            .and_then(|captures| Language::new(&captures[1]).ok());
+        let (reference, language) = parse_scripture_url(url)
+            .or_else(|| {
+                let language = url_language?;
+                let reference = self.study_help(&link_text)?;
+                (generate_url_with_lang(&reference, language) == url)
+                    .then_some((reference, language))
+            })
+            .or_else(|| self.configured_site_link(&link_text, url, url_language))?;
+        Some(ReferenceMatch {
+            range,
+            matched_text: link_text,
+            reference,
+            language: Some(language),
+        })
+    }
+
+    /// The reference of a link whose text is a reference linked to `url`
+    /// by the configured sites
+    fn configured_site_link(
+        &self,
+        link_text: &str,
+        url: &str,
+        url_language: Option<Language>,
+    ) -> Option<(ScriptureReference, Language)> {
+        let reference = parse_scripture_reference_with_works(
+            link_text,
+            &self.options.aliases,
+            &self.options.works,
+        )
+        .ok()?;
+        let detected = self
+            .options
+            .url_language(reference_language(link_text, &self.options.aliases));
+        url_language
+            .into_iter()
+            .chain([detected])
+            .find(|&language| self.options.url(&reference, language) == url)
+            .map(|language| (reference, language))
+    }
+
+    /// The reference of a wikilink to a chapter note or Study Helps topic
+    fn wikilink_reference(&self, captures: &regex::Captures) -> Option<ReferenceMatch> {
+        let range = captures.get(0).unwrap().range();
+        let target = captures.get(1).unwrap().as_str().trim();
+        let alias = captures.get(2).map(|alias| alias.as_str().trim());
+        let topic_text = alias.unwrap_or_else(|| target.rsplit('/').next().unwrap_or(target));
+        if let Some(reference) = self.study_help(topic_text) {
+            return Some(ReferenceMatch {
+                range,
+                matched_text: topic_text.to_string(),
+                reference,
+                language: None,
+            });
+        }
+
+        let note = CHAPTER_NOTE_RE.captures(target)?;
+        let ((book, standard_work), note_language) = resolve_note_book(
+            &note[1],
+            &self.options.aliases,
+            &self.options.works,
+            self.options.display_lang,
+        )?;
+        if standard_work.is_study_help() {
+            return None;
+        }
+        let chapter: u32 = note[2].parse().ok()?;
+        let aliased = alias
+            .and_then(|alias| {
+                parse_scripture_reference_with_works(
+                    alias,
+                    &self.options.aliases,
+                    &self.options.works,
+                )
+                .ok()
+            })
+            .filter(|reference| reference.book == book && reference.chapter == chapter);
+        let (verse_start, verse_end) = match (captures.get(3), note.get(3), &aliased) {
+            (Some(start), _, _) => (
+                start.as_str().parse().ok()?,
+                captures.get(4).and_then(|end| end.as_str().parse().ok()),
+            ),
+            (None, _, Some(reference)) => (reference.verse_start, reference.verse_end),
+            (None, Some(anchor), None) => (anchor.as_str().parse().ok()?, None),
+            (None, None, None) => return None,
+        };
+        if standard_work != StandardWork::Custom {
+            scripture_data::validate_verse_range(&book, chapter, verse_start, verse_end).ok()?;
+        }
+
+        let matched_text = alias.map_or_else(
+            || {
+                let verses = verse_end.map_or_else(
+                    || verse_start.to_string(),
+                    |end| format!("{verse_start}-{end}"),
+                );
+                format!("{} {chapter}:{verses}", &note[1])
+            },
+            str::to_string,
+        );
+        Some(ReferenceMatch {
+            range,
+            language: reference_language(&matched_text, &self.options.aliases).or(note_language),
+            matched_text,
+            reference: ScriptureReference {
+                book,
+                chapter,
+                verse_start,
+                verse_end,
+                standard_work,
+                topic: None,
+            },
+        })
+    }
+
+    /// A Study Helps reference written as an abbreviation and topic, such as "TG Faith"
+    fn study_help(&self, text: &str) -> Option<ScriptureReference> {
+        let (abbreviation, topic) = text.trim().split_once(' ')?;
+        let (book, standard_work) = self.abbreviations.get(abbreviation)?;
+        let topic = topic.trim();
+        (standard_work.is_study_help() && !topic.is_empty()).then(|| ScriptureReference {
+            book: (*book).to_string(),
+            chapter: 1,
+            verse_start: 1,
+            verse_end: None,
+            standard_work: standard_work.clone(),
+            topic: Some(topic.to_string()),
+        })
+    }
+
+    /// Find every reference to link in `text` with the link that replaces it,
+    /// or every link to remove when unlinking
+    fn find_replacements(&self, text: &str) -> Vec<Replacement> {
+        if self.options.unlink {
+            return self
+                .find_links(text)
+                .into_iter()
+                .map(|found| Replacement {
+                    range: found.range,
+                    text: found.matched_text,
+                })
+                .collect();
+        }
+        let render = |found: ReferenceMatch| Replacement {
+            text: render_reference(
+                &found.reference,
+                &found.matched_text,
+                self.options.url_language(found.language),
+                &self.options,
+            ),
+            range: found.range,
+        };
+        let mut replacements: Vec<Replacement> =
+            self.find_references(text).into_iter().map(render).collect();
+        if self.options.convert {
+            // Links already in the requested form are left as they are
+            replacements.extend(
+                self.find_links(text)
+                    .into_iter()
+                    .map(render)
+                    .filter(|replacement| replacement.text != text[replacement.range.clone()]),
+            );
+            replacements.sort_by_key(|replacement| replacement.range.start);
+        }
+        replacements
+    }
+
+    /// Where to split a streaming chunk, keeping code blocks whole when existing
+    /// links are rewritten so links in them are recognized as [`process`](Self::process) does
+    fn stream_cut(&self, buffer: &str, replacements: &[Replacement]) -> usize {
+        let protected = if self.options.convert || self.options.unlink {
+            code_ranges(buffer)
+        } else {
+            Vec::new()
+        };
+        stream_cut(buffer, replacements, protected)
+    }
+}
+
+/// A scripture reference found in text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReferenceMatch {
+    /// Byte range of the reference in the text
+    pub range: Range<usize>,
+    /// The reference as written in the text
+    pub matched_text: String,
+    pub reference: ScriptureReference,
+    /// Language of the book name, when it is a name from one of the enabled
+    /// book name languages rather than an English one
+    pub language: Option<Language>,
+}
+
+/// Bytes read per chunk by [`TextProcessor::process_stream`]
+const STREAM_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Bytes at the end of each chunk held back for the next one
+const STREAM_CARRY_OVER: usize = 4 * 1024;
+
+/// Most bytes held back to keep a code block whole while streaming
+const STREAM_MAX_HELD_BACK: usize = 16 * STREAM_CHUNK_SIZE;
+
+/// A span of the input and the link that replaces it
+#[derive(Debug)]
+struct Replacement {
+    range: Range<usize>,
+    text: String,
+}
 
-        // Process matches in reverse order to preserve indices
-        for (range, abbreviation, topic) in matches.into_iter().rev() {
-            // Look up the abbreviation
-            if let Some((book_url, standard_work)) = abbreviations.get(abbreviation.as_str())
-                && standard_work.is_study_help()
-            {
-                // Create a ScriptureReference for the Study Help
-                let scripture = crate::types::ScriptureReference {
-                    book: (*book_url).to_string(),
-                    chapter: 1,     // Not used for Study Helps
-                    verse_start: 1, // Not used for Study Helps
-                    verse_end: None,
-                    standard_work: standard_work.clone(),
-                    topic: Some(topic.clone()),
-                };
-
-                let url = generate_url(&scripture);
-                // Only include the abbreviation and topic in the link text, not the boundary words
-                let link_text = format!("{abbreviation} {topic}");
-                let markdown_link = format!("[{link_text}]({url})");
-
-                // Replace the matched text with the markdown link
-                result.replace_range(range, &markdown_link);
+const fn ranges_overlap(a: &Range<usize>, b: &Range<usize>) -> bool {
+    a.start < b.end && a.end > b.start
+}
+
+fn link_ranges(text: &str) -> Vec<Range<usize>> {
+    LINK_RE.find_iter(text).map(|m| m.range()).collect()
+}
+
+/// Start, character and length of a code fence not closed yet
+type OpenFence = (usize, char, usize);
+
+/// Fenced code blocks and inline code spans in Markdown `text`
+///
+/// A fence is a line starting with three or more backticks or tildes, indented
+/// by at most three spaces, and runs to a closing fence of the same character
+/// at least as long, or to the end of the text.
+fn code_ranges(text: &str) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut fence: Option<OpenFence> = None;
+    let mut prose_start = 0;
+    let mut position = 0;
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim_start_matches(' ');
+        let indent = line.len() - trimmed.len();
+        let marker = trimmed.chars().next().filter(|&c| c == '`' || c == '~');
+        let run = marker.map_or(0, |c| trimmed.len() - trimmed.trim_start_matches(c).len());
+        let line_end = position + line.len();
+        match fence {
+            Some((start, c, length)) => {
+                if marker == Some(c) && run >= length && trimmed[run..].trim().is_empty() {
+                    ranges.push(start..line_end);
+                    fence = None;
+                    prose_start = line_end;
+                }
+            }
+            None => {
+                // Backtick fences cannot have backticks in their info string
+                if indent < 4 && run >= 3 && !(marker == Some('`') && trimmed[run..].contains('`'))
+                {
+                    push_code_spans(text, prose_start..position, &mut ranges);
+                    fence = marker.map(|c| (position, c, run));
+                }
+            }
+        }
+        position = line_end;
+    }
+    match fence {
+        Some((start, _, _)) => ranges.push(start..text.len()),
+        None => push_code_spans(text, prose_start..text.len(), &mut ranges),
+    }
+    ranges.sort_by_key(|range| range.start);
+    ranges
+}
+
+/// Inline code spans in `text[range]`: a run of backticks up to the next run of the same length
+fn push_code_spans(text: &str, range: Range<usize>, ranges: &mut Vec<Range<usize>>) {
+    let backtick_run =
+        |from: usize| text[from..].len() - text[from..].trim_start_matches('`').len();
+    let mut position = range.start;
+    while let Some(open) = text[position..range.end].find('`').map(|i| position + i) {
+        let length = backtick_run(open);
+        let mut search = open + length;
+        let close = loop {
+            match text[search..range.end].find('`').map(|i| search + i) {
+                Some(candidate) if backtick_run(candidate) == length => {
+                    break Some(candidate + length);
+                }
+                Some(candidate) => search = candidate + backtick_run(candidate),
+                None => break None,
+            }
+        };
+        match close {
+            Some(end) => {
+                ranges.push(open..end);
+                position = end;
+            }
+            None => position = open + length,
+        }
+    }
+}
+
+/// Build the output in a single pass over the input
+fn apply_replacements(text: &str, replacements: &[Replacement]) -> String {
+    let extra: usize = replacements.iter().map(|r| r.text.len()).sum();
+    let mut output = String::with_capacity(text.len() + extra);
+    let mut position = 0;
+    for replacement in replacements {
+        output.push_str(&text[position..replacement.range.start]);
+        output.push_str(&replacement.text);
+        position = replacement.range.end;
+    }
+    output.push_str(&text[position..]);
+    output
+}
+
+/// Append up to [`STREAM_CHUNK_SIZE`] bytes from `reader` to `bytes`,
+/// returning whether the input has ended
+fn read_chunk<R: BufRead>(reader: &mut R, bytes: &mut Vec<u8>) -> io::Result<bool> {
+    let mut read = 0;
+    while read < STREAM_CHUNK_SIZE {
+        let available = match reader.fill_buf() {
+            Ok(available) => available,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(error),
+        };
+        if available.is_empty() {
+            return Ok(true);
+        }
+        let taken = available.len().min(STREAM_CHUNK_SIZE - read);
+        bytes.extend_from_slice(&available[..taken]);
+        reader.consume(taken);
+        read += taken;
+    }
+    Ok(false)
+}
+
+/// Move the complete UTF-8 text in `bytes` to `text`, leaving the start of a
+/// character split by the chunk boundary in `bytes` unless the input has ended
+fn decode_utf8(bytes: &mut Vec<u8>, text: &mut String, at_end: bool) -> io::Result<()> {
+    let invalid = |error| io::Error::new(io::ErrorKind::InvalidData, error);
+    let valid = match std::str::from_utf8(bytes) {
+        Ok(decoded) => decoded.len(),
+        Err(error) if error.error_len().is_none() && !at_end => error.valid_up_to(),
+        Err(error) => return Err(invalid(error)),
+    };
+    text.push_str(std::str::from_utf8(&bytes[..valid]).map_err(invalid)?);
+    bytes.drain(..valid);
+    Ok(())
+}
+
+/// Where to split a streaming chunk: at least [`STREAM_CARRY_OVER`] bytes from
+/// the end, preferably at a line start, and never inside a reference, link or
+/// any of the `protected` spans
+fn stream_cut(buffer: &str, replacements: &[Replacement], protected: Vec<Range<usize>>) -> usize {
+    let limit = buffer.len().saturating_sub(STREAM_CARRY_OVER);
+    let limit = (0..=limit)
+        .rev()
+        .find(|&index| buffer.is_char_boundary(index))
+        .unwrap_or(0);
+    let head = &buffer[..limit];
+    let mut cut = head
+        .rfind('\n')
+        .or_else(|| head.rfind(char::is_whitespace))
+        .map_or(head.len(), |index| {
+            index + head[index..].chars().next().map_or(0, char::len_utf8)
+        });
+
+    let mut spans: Vec<Range<usize>> = protected;
+    spans.extend(link_ranges(buffer));
+    spans.extend(replacements.iter().map(|r| r.range.clone()));
+    // Moving the cut back to the start of a span can land inside another one
+    while let Some(span) = spans.iter().find(|span| span.start < cut && cut < span.end) {
+        cut = span.start;
+    }
+    cut
+}
+
+/// Render a parsed reference as a link in the requested format
+fn render_reference(
+    scripture: &ScriptureReference,
+    matched_text: &str,
+    lang: Language,
+    options: &ProcessorOptions,
+) -> String {
+    let book_name = options.book_name(&scripture.book);
+    if let Some(topic) = &scripture.topic {
+        match options.format {
+            OutputFormat::Wikilink => {
+                return options
+                    .study_helps_wikilink
+                    .render(scripture, matched_text.trim());
+            }
+            OutputFormat::Html => {
+                let url = options.url(scripture, lang);
+                return options
+                    .html
+                    .render(scripture, matched_text.trim(), &url, &book_name);
             }
+            OutputFormat::Markdown => {}
+        }
+        let abbreviation = matched_text
+            .strip_suffix(topic.as_str())
+            .unwrap_or(matched_text);
+        return format!(
+            "[{} {topic}]({})",
+            abbreviation.trim_end(),
+            options.url(scripture, lang)
+        );
+    }
+    let link_text = link_text(scripture, matched_text, options);
+    match options.format {
+        OutputFormat::Wikilink => {
+            options
+                .wikilink
+                .render(scripture, &link_text, &options.wikilink_path, &book_name)
+        }
+        OutputFormat::Markdown => {
+            let url = options.url(scripture, lang);
+            format!("[{link_text}]({url})")
+        }
+        OutputFormat::Html => {
+            let url = options.url(scripture, lang);
+            options.html.render(scripture, &link_text, &url, &book_name)
         }
     }
+}
 
-    result
+/// Text shown for a reference: as written, or its full name when canonicalizing
+fn link_text(
+    scripture: &ScriptureReference,
+    matched_text: &str,
+    options: &ProcessorOptions,
+) -> String {
+    // Normalize DC to D&C in link text when user wrote DC (no ampersand)
+    let trimmed = matched_text.trim();
+    let u = trimmed.to_uppercase();
+    if options.canonicalize {
+        let verses = scripture.verse_end.map_or_else(
+            || scripture.verse_start.to_string(),
+            |end| format!("{}-{end}", scripture.verse_start),
+        );
+        format!(
+            "{} {}:{verses}",
+            options.book_name(&scripture.book),
+            scripture.chapter
+        )
+    } else if scripture.book == "dc"
+        && u.len() >= 2
+        && u.get(0..2) == Some("DC")
+        && (u.len() == 2 || u.chars().nth(2) != Some('&'))
+    {
+        let verse_part = scripture.verse_end.map_or_else(
+            || format!("{}:{}", scripture.chapter, scripture.verse_start),
+            |end| format!("{}:{}-{}", scripture.chapter, scripture.verse_start, end),
+        );
+        format!("D&C {verse_part}")
+    } else {
+        matched_text.to_string()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::io::Read;
 
     #[test]
     fn test_process_text_single_reference() {
@@ -568,4 +1327,577 @@ mod tests {
         assert!(result.contains("[Heb. 11:1]("));
         assert!(result.contains("[Hebrews 11:1]("));
     }
+
+    #[test]
+    fn test_localized_book_names_are_linked() {
+        let mut aliases = BookAliases::default();
+        aliases.enable_language(Language::SPANISH).unwrap();
+        aliases.enable_language(Language::PORTUGUESE).unwrap();
+        let processor = TextProcessor::new(ProcessorOptions {
+            aliases,
+            ..ProcessorOptions::default()
+        });
+        let result = processor
+            .process("Ver Juan 3:16, 1 Nefi 3:7, DyC 4:2, Génesis 1:1, genesis 2:3 y João 14:6.");
+        assert!(result.contains(
+            "[Juan 3:16](https://www.churchofjesuschrist.org/study/scriptures/nt/john/3?lang=spa&id=p16#p16)"
+        ));
+        assert!(result.contains("[1 Nefi 3:7]("));
+        assert!(result.contains(
+            "[DyC 4:2](https://www.churchofjesuschrist.org/study/scriptures/dc-testament/dc/4"
+        ));
+        assert!(result.contains("[Génesis 1:1]("));
+        assert!(result.contains("[genesis 2:3]("));
+        assert!(result.contains("[João 14:6]("));
+
+        let plain = TextProcessor::new(ProcessorOptions::default());
+        assert!(!plain.process("Ver Juan 3:16").contains("]("));
+    }
+
+    #[test]
+    fn test_links_use_the_language_of_each_reference() {
+        let mut aliases = BookAliases::default();
+        aliases.enable_language(Language::SPANISH).unwrap();
+        let mut options = ProcessorOptions {
+            aliases,
+            ..ProcessorOptions::default()
+        };
+        let text = "John 3:16 (Juan 3:16)";
+
+        let found = TextProcessor::new(options.clone()).find_references(text);
+        assert_eq!(found[0].language, None);
+        assert_eq!(found[1].language, Some(Language::SPANISH));
+        let result = TextProcessor::new(options.clone()).process(text);
+        assert!(result.contains(
+            "[John 3:16](https://www.churchofjesuschrist.org/study/scriptures/nt/john/3?lang=eng&"
+        ));
+        assert!(result.contains(
+            "[Juan 3:16](https://www.churchofjesuschrist.org/study/scriptures/nt/john/3?lang=spa&"
+        ));
+
+        // References in English use the configured language
+        options.lang = Language::PORTUGUESE;
+        let result = TextProcessor::new(options.clone()).process(text);
+        assert!(result.contains(
+            "[John 3:16](https://www.churchofjesuschrist.org/study/scriptures/nt/john/3?lang=por&"
+        ));
+        assert!(result.contains(
+            "[Juan 3:16](https://www.churchofjesuschrist.org/study/scriptures/nt/john/3?lang=spa&"
+        ));
+
+        options.force_lang = true;
+        let result = TextProcessor::new(options).process(text);
+        assert_eq!(result.matches("lang=por&").count(), 2);
+    }
+
+    #[test]
+    fn test_english_abbreviations_keep_english_links() {
+        let mut aliases = BookAliases::new([("Mos.", "mosiah")]).unwrap();
+        aliases.enable_language(Language::SPANISH).unwrap();
+        let processor = TextProcessor::new(ProcessorOptions {
+            aliases,
+            ..ProcessorOptions::default()
+        });
+
+        let found = processor.find_references("Mos. 3:19 and Mosíah 3:19");
+        assert_eq!(found[0].language, None);
+        assert_eq!(found[1].language, Some(Language::SPANISH));
+        assert!(
+            processor
+                .process("Mos. 3:19")
+                .contains("/bofm/mosiah/3?lang=eng&")
+        );
+
+        // Without the alias it is not taken for a Spanish name either
+        let mut aliases = BookAliases::default();
+        aliases.enable_language(Language::SPANISH).unwrap();
+        let processor = TextProcessor::new(ProcessorOptions {
+            aliases,
+            ..ProcessorOptions::default()
+        });
+        assert_eq!(processor.process("Mos. 3:19"), "Mos. 3:19");
+    }
+
+    #[test]
+    fn test_localized_display_names() {
+        let processor = TextProcessor::new(ProcessorOptions {
+            format: OutputFormat::Wikilink,
+            display_lang: Language::SPANISH,
+            ..ProcessorOptions::default()
+        });
+        assert_eq!(
+            processor.process("See Gen. 1:1 and 1 Ne. 3:7-8."),
+            "See [[Génesis 1]]:1 and [[1 Nefi 3]]:7-8."
+        );
+
+        let mut aliases = BookAliases::default();
+        aliases.enable_language(Language::SPANISH).unwrap();
+        let processor = TextProcessor::new(ProcessorOptions {
+            aliases,
+            canonicalize: true,
+            display_lang: Language::SPANISH,
+            ..ProcessorOptions::default()
+        });
+        let result = processor.process("Gén. 1:1, John 3:16 y DyC 4:2-3");
+        assert!(result.contains(
+            "[Génesis 1:1](https://www.churchofjesuschrist.org/study/scriptures/ot/gen/1?lang=spa&"
+        ));
+        assert!(result.contains(
+            "[Juan 3:16](https://www.churchofjesuschrist.org/study/scriptures/nt/john/3?lang=eng&"
+        ));
+        assert!(result.contains("[Doctrina y Convenios 4:2-3]("));
+
+        let processor = TextProcessor::new(ProcessorOptions {
+            canonicalize: true,
+            ..ProcessorOptions::default()
+        });
+        assert!(
+            processor
+                .process("Matt 5:3 and DC 4:2")
+                .starts_with("[Matthew 5:3](")
+        );
+        assert!(processor.process("DC 4:2").starts_with("[D&C 4:2]("));
+    }
+
+    #[test]
+    fn test_wikilink_templates() {
+        let processor = TextProcessor::new(ProcessorOptions {
+            format: OutputFormat::Wikilink,
+            wikilink: WikilinkTemplate::new("heading").unwrap(),
+            wikilink_path: "Scriptures/{work_name}/".to_string(),
+            ..ProcessorOptions::default()
+        });
+        let result = processor.process("See Gen 1:1 and DC 4:2.");
+        assert_eq!(
+            result,
+            "See [[Scriptures/Old Testament/Genesis 1#1|Gen 1:1]] and \
+             [[Scriptures/Doctrine and Covenants/D&C 4#2|D&C 4:2]]."
+        );
+        // The reference shown in an existing wikilink is not linked again
+        assert_eq!(processor.process(&result), result);
+
+        let processor = TextProcessor::new(ProcessorOptions {
+            format: OutputFormat::Wikilink,
+            wikilink: WikilinkTemplate::new("block").unwrap(),
+            ..ProcessorOptions::default()
+        });
+        assert_eq!(
+            processor.process("Moro. 10:4-5"),
+            "[[Moroni 10#^4|Moro. 10:4-5]]"
+        );
+    }
+
+    #[test]
+    fn test_study_helps_keep_boundary_words_and_punctuation() {
+        let input = "See TG Faith and BD Abraham for more. Also GS Moses!";
+        let result = process_text_with_options(input, true);
+
+        assert!(result.starts_with("See [TG Faith]("));
+        assert!(result.contains(") and [BD Abraham]("));
+        assert!(result.contains(") for more. Also [GS Moses]("));
+        assert!(result.ends_with(")!"));
+    }
+
+    #[test]
+    fn test_study_helps_wikilinks() {
+        let processor = TextProcessor::new(ProcessorOptions {
+            format: OutputFormat::Wikilink,
+            include_study_helps: true,
+            ..ProcessorOptions::default()
+        });
+        let result = processor.process("See TG Faith and Alma 32:21.");
+        assert_eq!(
+            result,
+            "See [[Topical Guide/Faith|TG Faith]] and [[Alma 32]]:21."
+        );
+        assert_eq!(processor.process(&result), result);
+
+        let processor = TextProcessor::new(ProcessorOptions {
+            format: OutputFormat::Wikilink,
+            include_study_helps: true,
+            study_helps_wikilink: StudyHelpsTemplate::new(
+                "[[Study/{abbreviation}/{topic_slug}|{topic}]]",
+            )
+            .unwrap(),
+            ..ProcessorOptions::default()
+        });
+        assert_eq!(
+            processor.process("Read BD Aaronic Priesthood."),
+            "Read [[Study/BD/aaronic-priesthood|Aaronic Priesthood]]."
+        );
+    }
+
+    #[test]
+    fn test_convert_markdown_links_to_wikilinks() {
+        let processor = TextProcessor::new(ProcessorOptions {
+            format: OutputFormat::Wikilink,
+            include_study_helps: true,
+            convert: true,
+            ..ProcessorOptions::default()
+        });
+        let markdown = "See [Alma 32:21](https://www.churchofjesuschrist.org/study/scriptures/bofm/alma/32?lang=eng&id=p21#p21), \
+            [Gen. 1:1-3](https://www.churchofjesuschrist.org/study/scriptures/ot/gen/1?lang=eng&id=p1-p3#p1), \
+            [TG Faith](https://www.churchofjesuschrist.org/study/scriptures/tg/faith?lang=eng), \
+            [a blog](https://example.com/alma/32) and John 3:16.";
+        assert_eq!(
+            processor.process(markdown),
+            "See [[Alma 32]]:21, [[Genesis 1]]:1-3, [[Topical Guide/Faith|TG Faith]], \
+            [a blog](https://example.com/alma/32) and [[John 3]]:16."
+        );
+
+        // Without the option existing links are left alone
+        let processor = TextProcessor::new(ProcessorOptions {
+            format: OutputFormat::Wikilink,
+            ..ProcessorOptions::default()
+        });
+        assert_eq!(processor.process(markdown).matches("[[").count(), 1);
+    }
+
+    #[test]
+    fn test_convert_wikilinks_to_markdown_links() {
+        let processor = TextProcessor::new(ProcessorOptions {
+            include_study_helps: true,
+            convert: true,
+            ..ProcessorOptions::default()
+        });
+        assert_eq!(
+            processor.process(
+                "[[Alma 32]]:21-23, [[Scriptures/Moroni 10#^4|Moro. 10:4]], [[Topical Guide/Faith|TG Faith]], \
+                [[Alma 32]], [[Alma 99]]:1 and [[Shopping list]]"
+            ),
+            "[Alma 32:21-23](https://www.churchofjesuschrist.org/study/scriptures/bofm/alma/32?lang=eng&id=p21-23#p21), \
+            [Moro. 10:4](https://www.churchofjesuschrist.org/study/scriptures/bofm/moro/10?lang=eng&id=p4#p4), \
+            [TG Faith](https://www.churchofjesuschrist.org/study/scriptures/tg/faith?lang=eng), \
+            [[Alma 32]], [[Alma 99]]:1 and [[Shopping list]]"
+        );
+
+        // Links already in the requested form are unchanged
+        let linked = processor.process("[[Alma 32]]:21");
+        assert_eq!(processor.process(&linked), linked);
+    }
+
+    #[test]
+    fn test_unlink_replaces_links_with_their_text() {
+        let processor = TextProcessor::new(ProcessorOptions {
+            unlink: true,
+            ..ProcessorOptions::default()
+        });
+        let linked = TextProcessor::new(ProcessorOptions {
+            include_study_helps: true,
+            ..ProcessorOptions::default()
+        })
+        .process("See Gen. 1:1-3, TG Faith and D&C 4:2.");
+        assert_eq!(
+            processor.process(&linked),
+            "See Gen. 1:1-3, TG Faith and D&C 4:2."
+        );
+        assert_eq!(
+            processor.process(
+                "[[Alma 32]]:21, [[Moroni 10#^4|Moro. 10:4]], [[Topical Guide/Faith|TG Faith]], \
+                [a blog](https://example.com/alma/32), [[Shopping]] and John 3:16"
+            ),
+            "Alma 32:21, Moro. 10:4, TG Faith, [a blog](https://example.com/alma/32), \
+            [[Shopping]] and John 3:16"
+        );
+    }
+
+    #[test]
+    fn test_unlink_keeps_links_in_code() {
+        let processor = TextProcessor::new(ProcessorOptions {
+            unlink: true,
+            ..ProcessorOptions::default()
+        });
+        let input = "[[Alma 32]]:21 and `[[Alma 32]]:22`\n\n```md\n[[Alma 32]]:23\n```\n\n\
+            ~~~~\n[[Alma 32]]:24\n```\n~~~~\n``code with ` inside [[Alma 32]]:25``\n";
+        assert_eq!(
+            processor.process(input),
+            input.replacen("[[Alma 32]]:21", "Alma 32:21", 1)
+        );
+        // An unclosed fence runs to the end of the text
+        assert_eq!(
+            processor.process("[[Alma 32]]:1\n```\n[[Alma 32]]:2\n"),
+            "Alma 32:1\n```\n[[Alma 32]]:2\n"
+        );
+    }
+
+    #[test]
+    fn test_stream_unlink_keeps_long_code_blocks_whole() {
+        let processor = TextProcessor::new(ProcessorOptions {
+            unlink: true,
+            ..ProcessorOptions::default()
+        });
+        let mut input = String::from("[[Alma 32]]:21\n```\n");
+        while input.len() < 2 * STREAM_CHUNK_SIZE {
+            input.push_str("[[Alma 32]]:22 in code\n");
+        }
+        input.push_str("```\n[[Alma 32]]:23\n");
+
+        let expected = processor.process_with_stats(&input);
+        assert_eq!(expected.references_linked, 2);
+        let (output, removed) = stream(&processor, &input);
+        assert_eq!(output, expected.text);
+        assert_eq!(removed, 2);
+    }
+
+    #[test]
+    fn test_html_links() {
+        let processor = TextProcessor::new(ProcessorOptions {
+            format: OutputFormat::Html,
+            include_study_helps: true,
+            ..ProcessorOptions::default()
+        });
+        let result = processor.process("See D&C 4:2 and TG Faith.");
+        assert_eq!(
+            result,
+            "See <a href=\"https://www.churchofjesuschrist.org/study/scriptures/dc-testament/dc/4?lang=eng&amp;id=p2#p2\" \
+             class=\"scripture-ref\" data-book=\"dc\" data-chapter=\"4\" data-verses=\"2\" title=\"D&amp;C 4:2\">D&amp;C 4:2</a> \
+             and <a href=\"https://www.churchofjesuschrist.org/study/scriptures/tg/faith?lang=eng\" class=\"scripture-ref\" \
+             data-book=\"tg\" title=\"Topical Guide: Faith\">TG Faith</a>."
+        );
+        assert_eq!(processor.process(&result), result);
+
+        // Anchors are recognized by their URL for unlinking and converting
+        let unlinker = TextProcessor::new(ProcessorOptions {
+            unlink: true,
+            ..ProcessorOptions::default()
+        });
+        assert_eq!(unlinker.process(&result), "See D&C 4:2 and TG Faith.");
+        let converter = TextProcessor::new(ProcessorOptions {
+            format: OutputFormat::Wikilink,
+            convert: true,
+            ..ProcessorOptions::default()
+        });
+        assert_eq!(
+            converter.process(&result),
+            "See [[D&C 4]]:2 and [[Topical Guide/Faith|TG Faith]]."
+        );
+    }
+
+    #[test]
+    fn test_convert_keeps_link_language() {
+        let processor = TextProcessor::new(ProcessorOptions {
+            format: OutputFormat::Wikilink,
+            convert: true,
+            ..ProcessorOptions::default()
+        });
+        let wikilink = processor.process(
+            "[Alma 32:21](https://www.churchofjesuschrist.org/study/scriptures/bofm/alma/32?lang=spa&id=p21#p21)",
+        );
+        assert_eq!(wikilink, "[[Alma 32]]:21");
+
+        let processor = TextProcessor::new(ProcessorOptions {
+            display_lang: Language::SPANISH,
+            convert: true,
+            ..ProcessorOptions::default()
+        });
+        assert_eq!(
+            processor.process("[[Génesis 1]]:3"),
+            "[Génesis 1:3](https://www.churchofjesuschrist.org/study/scriptures/ot/gen/1?lang=spa&id=p3#p3)"
+        );
+    }
+
+    #[test]
+    fn test_user_aliases_are_linked() {
+        let processor = TextProcessor::new(ProcessorOptions {
+            aliases: BookAliases::new([
+                ("D and C", "dc"),
+                ("JSH", "js-h"),
+                ("Mos.", "mosiah"),
+                ("Ne", "1-ne"),
+            ])
+            .unwrap(),
+            ..ProcessorOptions::default()
+        });
+        let result = processor.process("D and C 4:2, JSH 1:17, Mos. 3:19, Ne 3:7 and 2 Ne 2:25");
+        assert!(result.contains(
+            "[D and C 4:2](https://www.churchofjesuschrist.org/study/scriptures/dc-testament/dc/4"
+        ));
+        assert!(result.contains(
+            "[JSH 1:17](https://www.churchofjesuschrist.org/study/scriptures/pgp/js-h/1"
+        ));
+        assert!(result.contains(
+            "[Mos. 3:19](https://www.churchofjesuschrist.org/study/scriptures/bofm/mosiah/3"
+        ));
+        assert!(
+            result.contains(
+                "[Ne 3:7](https://www.churchofjesuschrist.org/study/scriptures/bofm/1-ne/3"
+            )
+        );
+        // A built-in name that contains an alias still wins
+        assert!(result.contains(
+            "[2 Ne 2:25](https://www.churchofjesuschrist.org/study/scriptures/bofm/2-ne/2"
+        ));
+
+        // Without the aliases none of these are references
+        let plain = TextProcessor::new(ProcessorOptions::default());
+        assert_eq!(
+            plain.process("D and C 4:2 and Mos. 3:19"),
+            "D and C 4:2 and Mos. 3:19"
+        );
+    }
+
+    #[test]
+    fn test_custom_works_are_linked() {
+        let options = ProcessorOptions {
+            works: CustomWorks::new([crate::works::CustomWork {
+                id: "test-scanner".to_string(),
+                name: "Scanner Manual".to_string(),
+                abbreviations: vec!["ScM.".to_string()],
+                url: "https://example.org/scanner/{chapter}#p{verse}".to_string(),
+                chapters: Some(4),
+                verses: None,
+            }])
+            .unwrap(),
+            ..ProcessorOptions::default()
+        };
+        let processor = TextProcessor::new(options.clone());
+
+        let result = processor.process("See ScM. 2:3 and Scanner Manual 4:1.");
+        assert!(result.contains("[ScM. 2:3](https://example.org/scanner/2#p3)"));
+        assert!(result.contains("[Scanner Manual 4:1](https://example.org/scanner/4#p1)"));
+        // Chapters beyond the configured count are not references
+        assert_eq!(processor.process("ScM 5:1"), "ScM 5:1");
+
+        let wikilinks = TextProcessor::new(ProcessorOptions {
+            format: OutputFormat::Wikilink,
+            ..options
+        });
+        assert_eq!(wikilinks.process("ScM 2:3"), "[[Scanner Manual 2]]:3");
+
+        // Other processors do not know the work
+        assert_eq!(
+            process_text_for_scripture_references("See ScM. 2:3."),
+            "See ScM. 2:3."
+        );
+    }
+
+    #[test]
+    fn test_processor_counts_linked_references() {
+        let processor = TextProcessor::new(ProcessorOptions {
+            include_study_helps: true,
+            ..ProcessorOptions::default()
+        });
+        let processed = processor.process_with_stats("Read Alma 32:21, Ether 12:6 and TG Faith.");
+        assert_eq!(processed.references_linked, 3);
+        assert!(processed.text.contains("[Alma 32:21]("));
+
+        // Existing links are not counted again
+        let again = processor.process_with_stats(&processed.text);
+        assert_eq!(again.references_linked, 0);
+        assert_eq!(again.text, processed.text);
+    }
+
+    fn stream(processor: &TextProcessor, input: &str) -> (String, usize) {
+        let mut output = Vec::new();
+        let linked = processor
+            .process_stream(input.as_bytes(), &mut output)
+            .unwrap();
+        (String::from_utf8(output).unwrap(), linked)
+    }
+
+    #[test]
+    fn test_stream_matches_in_memory_processing_across_chunks() {
+        let processor = TextProcessor::new(ProcessorOptions {
+            include_study_helps: true,
+            ..ProcessorOptions::default()
+        });
+        // Varying padding moves references across every chunk boundary offset,
+        // including ones split over lines and existing links
+        let mut input = String::new();
+        for n in 0..4000 {
+            input.push_str(&"x".repeat(n % 37));
+            input.push_str(match n % 5 {
+                0 => " See Alma 32:21 and Ether 12:6-7.\n",
+                1 => " Read Genesis\n1:1 today.\n",
+                2 => " Keep [Moroni 10:4](https://example.com) and [[Alma 13]]:6.\n",
+                3 => " See TG Faith. And D\u{e9}j\u{e0} vu D&C 4:2\n",
+                _ => " plain line\n",
+            });
+        }
+        assert!(input.len() > 3 * STREAM_CHUNK_SIZE);
+
+        let expected = processor.process_with_stats(&input);
+        let (output, linked) = stream(&processor, &input);
+        assert_eq!(output, expected.text);
+        assert_eq!(linked, expected.references_linked);
+    }
+
+    #[test]
+    fn test_stream_single_long_line() {
+        let processor = TextProcessor::new(ProcessorOptions::default());
+        let input = "Compare 1 Cor. 13:1 with Hebrews 11:1 here ".repeat(5000);
+
+        let (output, linked) = stream(&processor, &input);
+        assert_eq!(output, processor.process(&input));
+        assert_eq!(linked, 10_000);
+    }
+
+    /// Counts the bytes read so far and fails once more than `limit` are asked for
+    struct CountingReader<'a> {
+        input: &'a [u8],
+        read: usize,
+        limit: usize,
+    }
+
+    impl Read for CountingReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.read >= self.limit {
+                return Err(io::Error::other("read past the limit"));
+            }
+            let count = self.input.len().min(buf.len());
+            buf[..count].copy_from_slice(&self.input[..count]);
+            self.input = &self.input[count..];
+            self.read += count;
+            Ok(count)
+        }
+    }
+
+    #[test]
+    fn test_stream_reads_one_chunk_at_a_time() {
+        let processor = TextProcessor::new(ProcessorOptions::default());
+        // One long line: reading whole lines would pull in all of it at once
+        let input = "Compare 1 Cor. 13:1 with Hebrews 11:1 here ".repeat(20_000);
+        let mut reader = CountingReader {
+            input: input.as_bytes(),
+            read: 0,
+            limit: 2 * STREAM_CHUNK_SIZE,
+        };
+        let mut output = Vec::new();
+        // The reader fails after two chunks, by which time output must have been written
+        let result = processor.process_stream(io::BufReader::new(&mut reader), &mut output);
+        assert!(result.is_err());
+        assert!(!output.is_empty());
+        assert!(reader.read <= 2 * STREAM_CHUNK_SIZE);
+    }
+
+    #[test]
+    fn test_stream_character_split_across_chunks() {
+        let processor = TextProcessor::new(ProcessorOptions::default());
+        let mut input = "x".repeat(STREAM_CHUNK_SIZE - 1);
+        input.push_str("\u{e9} See Alma 32:21.\n");
+
+        let (output, linked) = stream(&processor, &input);
+        assert_eq!(output, processor.process(&input));
+        assert_eq!(linked, 1);
+    }
+
+    #[test]
+    fn test_stream_invalid_utf8() {
+        let processor = TextProcessor::new(ProcessorOptions::default());
+        let error = processor
+            .process_stream(&b"See Alma 32:21 \xff\n"[..], io::sink())
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        // A character cut off by the end of the input is invalid too
+        let error = processor
+            .process_stream(&b"See Alma 32:21 \xc3"[..], io::sink())
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_stream_empty_input() {
+        let processor = TextProcessor::new(ProcessorOptions::default());
+        assert_eq!(stream(&processor, ""), (String::new(), 0));
+    }
 }
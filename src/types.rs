@@ -3,13 +3,17 @@
 use serde::{Deserialize, Serialize};
 
 /// Output format for scripture links
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
 pub enum OutputFormat {
     /// Standard markdown `[text](url)` links
     #[default]
     Markdown,
     /// Obsidian-style `[[Book Chapter]]:Verse` wikilinks
     Wikilink,
+    /// HTML `<a href="url">text</a>` anchors with data attributes describing the reference
+    Html,
 }
 
 /// Represents a parsed scripture reference
@@ -26,6 +30,7 @@ pub struct ScriptureReference {
 
 /// Standard works of LDS scripture and study helps
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
 pub enum StandardWork {
     OldTestament,
     NewTestament,
@@ -33,6 +38,8 @@ pub enum StandardWork {
     DoctrineAndCovenants,
     PearlOfGreatPrice,
     StudyHelps,
+    /// A work declared in configuration; the reference's book is the work's id
+    Custom,
 }
 
 impl StandardWork {
@@ -46,6 +53,21 @@ impl StandardWork {
             Self::DoctrineAndCovenants => "dc-testament",
             Self::PearlOfGreatPrice => "pgp",
             Self::StudyHelps => "study-helps", // This won't be used directly for URL generation
+            Self::Custom => "custom",          // Custom works use their own URL templates
+        }
+    }
+
+    /// Human-readable name of the standard work
+    #[must_use]
+    pub const fn display_name(&self) -> &'static str {
+        match self {
+            Self::OldTestament => "Old Testament",
+            Self::NewTestament => "New Testament",
+            Self::BookOfMormon => "Book of Mormon",
+            Self::DoctrineAndCovenants => "Doctrine and Covenants",
+            Self::PearlOfGreatPrice => "Pearl of Great Price",
+            Self::StudyHelps => "Study Helps",
+            Self::Custom => "Other Works",
         }
     }
 
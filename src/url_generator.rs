@@ -1,11 +1,15 @@
 //! URL generation for scripture references
 
-use crate::types::ScriptureReference;
+use crate::language::Language;
+use crate::types::{ScriptureReference, StandardWork};
+use crate::works::CustomWorks;
+
+const BASE_URL: &str = "https://www.churchofjesuschrist.org/study/scriptures";
 
 /// Convert a topic name to a URL slug
 /// Examples: "Aaron, Brother of Moses" -> "aaron-brother-of-moses"
 ///           "Faith" -> "faith"
-fn topic_to_slug(topic: &str) -> String {
+pub(crate) fn topic_to_slug(topic: &str) -> String {
     topic
         .to_lowercase()
         .chars()
@@ -45,9 +49,42 @@ fn topic_to_slug(topic: &str) -> String {
 /// let url = generate_url(&scripture);
 /// assert!(url.contains("https://www.churchofjesuschrist.org/study/scriptures"));
 /// ```
+///
+/// # Panics
+/// Panics for a reference to a [custom work](crate::works), which has no page
+/// on the site; use [`generate_url_with_works`] for those.
 #[must_use]
 pub fn generate_url(scripture: &ScriptureReference) -> String {
-    let base_url = "https://www.churchofjesuschrist.org/study/scriptures";
+    generate_url_with_lang(scripture, Language::ENGLISH)
+}
+
+/// Generate a URL for a scripture reference in the given language of the site
+///
+/// # Examples
+///
+/// ```
+/// use scripture_links_lib::{Language, generate_url_with_lang, parse_scripture_reference};
+///
+/// let scripture = parse_scripture_reference("John 3:16").unwrap();
+/// let url = generate_url_with_lang(&scripture, Language::SPANISH);
+/// assert_eq!(
+///     url,
+///     "https://www.churchofjesuschrist.org/study/scriptures/nt/john/3?lang=spa&id=p16#p16"
+/// );
+/// ```
+///
+/// # Panics
+/// Panics for a reference to a [custom work](crate::works), which has no page
+/// on the site; use [`generate_url_with_works`] for those.
+#[must_use]
+pub fn generate_url_with_lang(scripture: &ScriptureReference, lang: Language) -> String {
+    let base_url = BASE_URL;
+
+    assert!(
+        scripture.standard_work != StandardWork::Custom,
+        "custom work '{}' has no page on ChurchofJesusChrist.org; use generate_url_with_works",
+        scripture.book
+    );
 
     // Study Helps use different URL patterns
     if scripture.standard_work.is_study_help() {
@@ -57,24 +94,28 @@ pub fn generate_url(scripture: &ScriptureReference) -> String {
             match scripture.book.as_str() {
                 "it" => {
                     // Index to the Triple Combination uses "triple-index" in URL
-                    return format!("{base_url}/triple-index/{slug}?lang=eng");
+                    return format!("{base_url}/triple-index/{slug}?lang={lang}");
                 }
                 _ => {
                     // Other Study Helps use the abbreviation directly
-                    return format!("{base_url}/{}/{}?lang=eng", scripture.book, slug);
+                    return format!("{base_url}/{}/{slug}?lang={lang}", scripture.book);
                 }
             }
         }
         // If no topic specified, link to the main study help page
         match scripture.book.as_str() {
-            "it" => return format!("{base_url}/triple-index?lang=eng"),
-            _ => return format!("{base_url}/{}?lang=eng", scripture.book),
+            "it" => return format!("{base_url}/triple-index?lang={lang}"),
+            _ => return format!("{base_url}/{}?lang={lang}", scripture.book),
         }
     }
 
     // Regular scripture references
-    let standard_work_path = scripture.standard_work.to_url_path();
-    let book_path = &scripture.book;
+    let chapter_url = generate_chapter_url_with_lang(
+        &scripture.standard_work,
+        &scripture.book,
+        scripture.chapter,
+        lang,
+    );
 
     let id_param = scripture.verse_end.map_or_else(
         || format!("p{}", scripture.verse_start),
@@ -83,9 +124,81 @@ pub fn generate_url(scripture: &ScriptureReference) -> String {
 
     let fragment = format!("p{}", scripture.verse_start);
 
+    format!("{chapter_url}&id={id_param}#{fragment}")
+}
+
+/// Generate a URL for a reference to a built-in book or to one of `works`,
+/// which link through their own URL templates
+///
+/// # Examples
+///
+/// ```
+/// use scripture_links_lib::aliases::BookAliases;
+/// use scripture_links_lib::works::{CustomWork, CustomWorks};
+/// use scripture_links_lib::{Language, generate_url_with_works, parse_scripture_reference_with_works};
+///
+/// let works = CustomWorks::new([CustomWork {
+///     id: "lof".to_string(),
+///     name: "Lectures on Faith".to_string(),
+///     abbreviations: vec!["LoF".to_string()],
+///     url: "https://example.org/lectures-on-faith/{chapter}#p{verse}".to_string(),
+///     chapters: Some(7),
+///     verses: None,
+/// }])
+/// .unwrap();
+/// let aliases = BookAliases::default();
+/// let lectures = parse_scripture_reference_with_works("LoF 3:4", &aliases, &works).unwrap();
+/// assert_eq!(
+///     generate_url_with_works(&lectures, &works, Language::ENGLISH),
+///     "https://example.org/lectures-on-faith/3#p4"
+/// );
+/// let alma = parse_scripture_reference_with_works("Alma 32:21", &aliases, &works).unwrap();
+/// assert!(generate_url_with_works(&alma, &works, Language::ENGLISH).contains("/bofm/alma/32"));
+/// ```
+///
+/// # Panics
+/// Panics for a reference to a custom work that is not one of `works`.
+#[must_use]
+pub fn generate_url_with_works(
+    scripture: &ScriptureReference,
+    works: &CustomWorks,
+    lang: Language,
+) -> String {
+    match works.get(&scripture.book) {
+        Some(work) if scripture.standard_work == StandardWork::Custom => {
+            work.url_for(scripture, lang)
+        }
+        _ => generate_url_with_lang(scripture, lang),
+    }
+}
+
+/// Generate the URL of a whole chapter
+///
+/// # Examples
+///
+/// ```
+/// use scripture_links_lib::StandardWork;
+/// use scripture_links_lib::url_generator::generate_chapter_url;
+///
+/// let url = generate_chapter_url(&StandardWork::BookOfMormon, "alma", 32);
+/// assert_eq!(url, "https://www.churchofjesuschrist.org/study/scriptures/bofm/alma/32?lang=eng");
+/// ```
+#[must_use]
+pub fn generate_chapter_url(standard_work: &StandardWork, book: &str, chapter: u32) -> String {
+    generate_chapter_url_with_lang(standard_work, book, chapter, Language::ENGLISH)
+}
+
+/// Generate the URL of a whole chapter in the given language of the site
+#[must_use]
+pub fn generate_chapter_url_with_lang(
+    standard_work: &StandardWork,
+    book: &str,
+    chapter: u32,
+    lang: Language,
+) -> String {
     format!(
-        "{}/{}/{}/{}?lang=eng&id={}#{}",
-        base_url, standard_work_path, book_path, scripture.chapter, id_param, fragment
+        "{BASE_URL}/{}/{book}/{chapter}?lang={lang}",
+        standard_work.to_url_path()
     )
 }
 
@@ -324,4 +437,54 @@ mod tests {
         assert!(url.contains("bd/aaron's-rod-&-staff"));
         assert!(url.contains("lang=eng"));
     }
+
+    #[test]
+    fn test_lang_parameter() {
+        let mut scripture = ScriptureReference {
+            book: "1-ne".to_string(),
+            chapter: 3,
+            verse_start: 7,
+            verse_end: None,
+            standard_work: StandardWork::BookOfMormon,
+            topic: None,
+        };
+        assert_eq!(
+            generate_url_with_lang(&scripture, Language::PORTUGUESE),
+            "https://www.churchofjesuschrist.org/study/scriptures/bofm/1-ne/3?lang=por&id=p7#p7"
+        );
+
+        scripture.book = "it".to_string();
+        scripture.standard_work = StandardWork::StudyHelps;
+        scripture.topic = Some("Faith".to_string());
+        assert_eq!(
+            generate_url_with_lang(&scripture, Language::SPANISH),
+            "https://www.churchofjesuschrist.org/study/scriptures/triple-index/faith?lang=spa"
+        );
+        scripture.topic = None;
+        assert_eq!(
+            generate_url_with_lang(&scripture, Language::SPANISH),
+            "https://www.churchofjesuschrist.org/study/scriptures/triple-index?lang=spa"
+        );
+
+        scripture.book = "tg".to_string();
+        scripture.topic = Some("Faith".to_string());
+        assert_eq!(
+            generate_url_with_lang(&scripture, Language::FRENCH),
+            "https://www.churchofjesuschrist.org/study/scriptures/tg/faith?lang=fra"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "custom work 'lof'")]
+    fn test_custom_works_have_no_url_on_the_site() {
+        let scripture = ScriptureReference {
+            book: "lof".to_string(),
+            chapter: 3,
+            verse_start: 4,
+            verse_end: None,
+            standard_work: StandardWork::Custom,
+            topic: None,
+        };
+        let _ = generate_url(&scripture);
+    }
 }
@@ -0,0 +1,491 @@
+//! Pluggable URL providers, so references can link to sites other than ChurchofJesusChrist.org
+
+use crate::language::Language;
+use crate::types::{ScriptureReference, StandardWork};
+use crate::url_generator::{generate_chapter_url_with_lang, generate_url_with_lang};
+use crate::works::book_name;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt::{Debug, Write};
+use std::sync::Arc;
+
+/// Placeholders that may appear in a URL template
+pub const TEMPLATE_PLACEHOLDERS: &[&str] = &[
+    "work",
+    "book",
+    "book_name",
+    "chapter",
+    "verse",
+    "end_verse",
+    "verses",
+    "lang",
+];
+
+/// Built-in templates selectable by name, besides "church"
+const PRESETS: &[(&str, &str)] = &[
+    (
+        "biblegateway",
+        "https://www.biblegateway.com/passage/?search={book_name}+{chapter}:{verses}&version=KJV",
+    ),
+    (
+        "blueletterbible",
+        "https://www.blueletterbible.org/search/preSearch.cfm?Criteria={book_name}+{chapter}:{verses}&t=KJV",
+    ),
+];
+
+/// Name of the provider that links to ChurchofJesusChrist.org
+pub const CHURCH_PROVIDER: &str = "church";
+
+/// A key and the standard works it selects
+type WorkKey = (&'static str, &'static [StandardWork]);
+
+/// Keys selecting which standard works a provider is used for
+const WORK_KEYS: &[WorkKey] = &[
+    ("ot", &[StandardWork::OldTestament]),
+    ("nt", &[StandardWork::NewTestament]),
+    (
+        "bible",
+        &[StandardWork::OldTestament, StandardWork::NewTestament],
+    ),
+    ("bofm", &[StandardWork::BookOfMormon]),
+    ("dc", &[StandardWork::DoctrineAndCovenants]),
+    ("pgp", &[StandardWork::PearlOfGreatPrice]),
+];
+
+/// Key selecting the provider used for works without their own provider
+const DEFAULT_KEY: &str = "default";
+
+/// Builds the URL a reference links to
+pub trait UrlProvider: Debug + Send + Sync {
+    /// The URL of `scripture`, in `lang` where the site supports it
+    ///
+    /// Providers are not asked for custom works, which link through their own
+    /// templates (see [`generate_url_with_works`](crate::generate_url_with_works)).
+    fn url(&self, scripture: &ScriptureReference, lang: Language) -> String;
+
+    /// The URL of a whole chapter of `book`, by default that of its first verse
+    fn chapter_url(
+        &self,
+        standard_work: &StandardWork,
+        book: &str,
+        chapter: u32,
+        lang: Language,
+    ) -> String {
+        let scripture = ScriptureReference {
+            book: book.to_string(),
+            chapter,
+            verse_start: 1,
+            verse_end: None,
+            standard_work: standard_work.clone(),
+            topic: None,
+        };
+        self.url(&scripture, lang)
+    }
+}
+
+/// A provider that can be shared between threads and settings
+pub type SharedUrlProvider = Arc<dyn UrlProvider>;
+
+/// Links to ChurchofJesusChrist.org, as [`generate_url_with_lang`] does
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ChurchUrlProvider;
+
+impl UrlProvider for ChurchUrlProvider {
+    fn url(&self, scripture: &ScriptureReference, lang: Language) -> String {
+        generate_url_with_lang(scripture, lang)
+    }
+
+    fn chapter_url(
+        &self,
+        standard_work: &StandardWork,
+        book: &str,
+        chapter: u32,
+        lang: Language,
+    ) -> String {
+        generate_chapter_url_with_lang(standard_work, book, chapter, lang)
+    }
+}
+
+/// Fills in a URL template such as `https://example.org/{work}/{book}/{chapter}#{verse}`
+///
+/// Placeholders are `{work}` (e.g., "ot"), `{book}` (e.g., "1-sam"), `{book_name}`
+/// (e.g., "1 Samuel", percent-encoded), `{chapter}`, `{verse}`, `{end_verse}`
+/// (the last verse of a range, or the verse), `{verses}` (e.g., "3" or "3-5") and
+/// `{lang}` (e.g., "eng").
+///
+/// # Examples
+///
+/// ```
+/// use scripture_links_lib::url_provider::{TemplateUrlProvider, UrlProvider};
+/// use scripture_links_lib::{Language, parse_scripture_reference};
+///
+/// let provider = TemplateUrlProvider::new("https://example.org/{lang}/{work}/{book}/{chapter}#{verse}").unwrap();
+/// let reference = parse_scripture_reference("1 Sam. 3:10").unwrap();
+/// assert_eq!(
+///     provider.url(&reference, Language::ENGLISH),
+///     "https://example.org/eng/ot/1-sam/3#10"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateUrlProvider {
+    template: String,
+}
+
+impl TemplateUrlProvider {
+    /// # Errors
+    /// Returns an error if the template is empty or uses an unknown placeholder.
+    pub fn new(template: impl Into<String>) -> Result<Self, String> {
+        let template = template.into();
+        validate_template(&template)?;
+        Ok(Self { template })
+    }
+
+    #[must_use]
+    pub fn template(&self) -> &str {
+        &self.template
+    }
+}
+
+impl UrlProvider for TemplateUrlProvider {
+    fn url(&self, scripture: &ScriptureReference, lang: Language) -> String {
+        render_template(&self.template, scripture, &book_name(&scripture.book), lang)
+    }
+}
+
+/// Check that a URL template is not empty and every `{placeholder}` in it is known
+///
+/// # Errors
+/// Returns an error naming the first unknown or unclosed placeholder.
+pub fn validate_template(template: &str) -> Result<(), String> {
+    if template.trim().is_empty() {
+        return Err("the URL is empty".to_string());
+    }
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| format!("unclosed '{{' in '{template}'"))?;
+        let placeholder = &rest[start + 1..start + end];
+        if !TEMPLATE_PLACEHOLDERS.contains(&placeholder) {
+            return Err(format!(
+                "unknown placeholder '{{{placeholder}}}'; expected one of {}",
+                TEMPLATE_PLACEHOLDERS
+                    .iter()
+                    .map(|name| format!("{{{name}}}"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+        rest = &rest[start + end + 1..];
+    }
+    Ok(())
+}
+
+/// Fill in the placeholders of a validated URL template, with `book_name` for `{book_name}`
+#[must_use]
+#[allow(clippy::literal_string_with_formatting_args)]
+pub fn render_template(
+    template: &str,
+    scripture: &ScriptureReference,
+    book_name: &str,
+    lang: Language,
+) -> String {
+    let verses = scripture.verse_end.map_or_else(
+        || scripture.verse_start.to_string(),
+        |end| format!("{}-{end}", scripture.verse_start),
+    );
+    template
+        .replace("{work}", scripture.standard_work.to_url_path())
+        .replace("{book_name}", &encode_component(book_name))
+        .replace("{book}", &scripture.book)
+        .replace("{chapter}", &scripture.chapter.to_string())
+        .replace("{verse}", &scripture.verse_start.to_string())
+        .replace(
+            "{end_verse}",
+            &scripture
+                .verse_end
+                .unwrap_or(scripture.verse_start)
+                .to_string(),
+        )
+        .replace("{verses}", &verses)
+        .replace("{lang}", lang.code())
+}
+
+/// Percent-encode everything but unreserved URL characters
+fn encode_component(value: &str) -> String {
+    let mut encoded = String::new();
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+            encoded.push(char::from(byte));
+        } else {
+            let _ = write!(encoded, "%{byte:02X}");
+        }
+    }
+    encoded
+}
+
+/// A provider by preset name ("church", "biblegateway", "blueletterbible") or template
+///
+/// # Errors
+/// Returns an error if `spec` is neither a preset nor a valid template.
+pub fn provider_from_spec(spec: &str) -> Result<SharedUrlProvider, String> {
+    let spec = spec.trim();
+    if spec == CHURCH_PROVIDER {
+        return Ok(Arc::new(ChurchUrlProvider));
+    }
+    if let Some((_, template)) = PRESETS.iter().find(|(name, _)| *name == spec) {
+        return Ok(Arc::new(TemplateUrlProvider::new(*template)?));
+    }
+    if spec.contains('{') || spec.contains("://") {
+        return Ok(Arc::new(TemplateUrlProvider::new(spec)?));
+    }
+    let presets: Vec<&str> = std::iter::once(CHURCH_PROVIDER)
+        .chain(PRESETS.iter().map(|(name, _)| *name))
+        .collect();
+    Err(format!(
+        "Unknown URL provider '{spec}'; expected {} or a URL template",
+        presets.join(", ")
+    ))
+}
+
+/// URL providers selected per standard work
+///
+/// Works without their own provider use the default provider, which links to
+/// ChurchofJesusChrist.org unless replaced. Study Helps always keep their own
+/// URLs.
+///
+/// # Examples
+///
+/// ```
+/// use scripture_links_lib::url_provider::{UrlProvider, UrlProviders};
+/// use scripture_links_lib::{Language, parse_scripture_reference};
+///
+/// let mut providers = UrlProviders::default();
+/// providers.set("bible", "biblegateway").unwrap();
+///
+/// let matthew = parse_scripture_reference("Matt. 5:3-4").unwrap();
+/// assert!(providers.url(&matthew, Language::ENGLISH).starts_with("https://www.biblegateway.com/passage/?search=Matthew+5:3-4"));
+///
+/// let alma = parse_scripture_reference("Alma 32:21").unwrap();
+/// assert!(providers.url(&alma, Language::SPANISH).ends_with("/bofm/alma/32?lang=spa&id=p21#p21"));
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(try_from = "BTreeMap<String, String>")]
+pub struct UrlProviders {
+    default: Option<SharedUrlProvider>,
+    by_work: Vec<(StandardWork, SharedUrlProvider)>,
+}
+
+impl UrlProviders {
+    /// Use the provider described by `spec` for the works selected by `key`
+    ///
+    /// `key` is "default" or one of "ot", "nt", "bible", "bofm", "dc" and "pgp".
+    ///
+    /// # Errors
+    /// Returns an error if the key is unknown or `spec` is not a valid provider.
+    pub fn set(&mut self, key: &str, spec: &str) -> Result<(), String> {
+        let provider = provider_from_spec(spec)?;
+        let key = key.trim();
+        if key == DEFAULT_KEY {
+            self.default = Some(provider);
+            return Ok(());
+        }
+        let (_, works) = WORK_KEYS
+            .iter()
+            .find(|(name, _)| *name == key)
+            .ok_or_else(|| {
+                let keys: Vec<&str> = std::iter::once(DEFAULT_KEY)
+                    .chain(WORK_KEYS.iter().map(|(name, _)| *name))
+                    .collect();
+                format!(
+                    "Unknown standard work '{key}' for a URL provider; expected one of {}",
+                    keys.join(", ")
+                )
+            })?;
+        for work in *works {
+            self.set_provider(work.clone(), Arc::clone(&provider));
+        }
+        Ok(())
+    }
+
+    /// Parse a `[WORK=]PROVIDER` command-line value, e.g. "nt=biblegateway"
+    ///
+    /// # Errors
+    /// Returns an error if the work or provider is unknown.
+    pub fn parse_arg(arg: &str) -> Result<Self, String> {
+        let mut providers = Self::default();
+        // Templates may contain '=' themselves, so only split when a bare word precedes it
+        match arg.split_once('=') {
+            Some((key, spec))
+                if !key.is_empty()
+                    && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') =>
+            {
+                providers.set(key, spec)?;
+            }
+            _ => providers.set(DEFAULT_KEY, arg)?,
+        }
+        Ok(providers)
+    }
+
+    /// Take the providers set in `other`, replacing those selected for the same works
+    pub fn extend(&mut self, other: Self) {
+        if other.default.is_some() {
+            self.default = other.default;
+        }
+        for (work, provider) in other.by_work {
+            self.set_provider(work, provider);
+        }
+    }
+
+    fn set_provider(&mut self, work: StandardWork, provider: SharedUrlProvider) {
+        self.by_work.retain(|(existing, _)| *existing != work);
+        self.by_work.push((work, provider));
+    }
+
+    /// The provider selected for `work`, or `None` to link to ChurchofJesusChrist.org
+    fn provider(&self, work: &StandardWork) -> Option<&SharedUrlProvider> {
+        if work.is_study_help() {
+            return None;
+        }
+        self.by_work
+            .iter()
+            .find(|(selected, _)| selected == work)
+            .map(|(_, provider)| provider)
+            .or(self.default.as_ref())
+    }
+}
+
+impl UrlProvider for UrlProviders {
+    fn url(&self, scripture: &ScriptureReference, lang: Language) -> String {
+        self.provider(&scripture.standard_work).map_or_else(
+            || generate_url_with_lang(scripture, lang),
+            |provider| provider.url(scripture, lang),
+        )
+    }
+
+    fn chapter_url(
+        &self,
+        standard_work: &StandardWork,
+        book: &str,
+        chapter: u32,
+        lang: Language,
+    ) -> String {
+        self.provider(standard_work).map_or_else(
+            || generate_chapter_url_with_lang(standard_work, book, chapter, lang),
+            |provider| provider.chapter_url(standard_work, book, chapter, lang),
+        )
+    }
+}
+
+impl TryFrom<BTreeMap<String, String>> for UrlProviders {
+    type Error = String;
+
+    fn try_from(specs: BTreeMap<String, String>) -> Result<Self, String> {
+        let mut providers = Self::default();
+        for (key, spec) in &specs {
+            providers.set(key, spec)?;
+        }
+        Ok(providers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_scripture_reference;
+
+    fn url(providers: &UrlProviders, reference: &str) -> String {
+        providers.url(
+            &parse_scripture_reference(reference).unwrap(),
+            Language::ENGLISH,
+        )
+    }
+
+    #[test]
+    fn test_default_is_church_site() {
+        let providers = UrlProviders::default();
+        assert_eq!(
+            url(&providers, "Alma 32:21"),
+            "https://www.churchofjesuschrist.org/study/scriptures/bofm/alma/32?lang=eng&id=p21#p21"
+        );
+    }
+
+    #[test]
+    fn test_presets_encode_book_names() {
+        let mut providers = UrlProviders::default();
+        providers.set("ot", "biblegateway").unwrap();
+        providers.set("nt", "blueletterbible").unwrap();
+        assert_eq!(
+            url(&providers, "Song 2:1-3"),
+            "https://www.biblegateway.com/passage/?search=Song%20of%20Solomon+2:1-3&version=KJV"
+        );
+        assert_eq!(
+            url(&providers, "1 Cor. 13:4"),
+            "https://www.blueletterbible.org/search/preSearch.cfm?Criteria=1%20Corinthians+13:4&t=KJV"
+        );
+        assert!(url(&providers, "Moro. 10:4").starts_with("https://www.churchofjesuschrist.org/"));
+    }
+
+    #[test]
+    fn test_work_providers_take_precedence_over_default() {
+        let mut providers =
+            UrlProviders::parse_arg("https://scriptures.example/{work}/{book}/{chapter}#{verse}")
+                .unwrap();
+        providers.extend(UrlProviders::parse_arg("bofm=church").unwrap());
+        assert_eq!(
+            url(&providers, "D&C 4:2"),
+            "https://scriptures.example/dc-testament/dc/4#2"
+        );
+        assert!(url(&providers, "Alma 5:6").starts_with("https://www.churchofjesuschrist.org/"));
+    }
+
+    #[test]
+    fn test_chapter_urls() {
+        let mut providers = UrlProviders::parse_arg("biblegateway").unwrap();
+        providers.extend(UrlProviders::parse_arg("bofm=church").unwrap());
+        assert_eq!(
+            providers.chapter_url(&StandardWork::NewTestament, "john", 3, Language::ENGLISH),
+            "https://www.biblegateway.com/passage/?search=John+3:1&version=KJV"
+        );
+        assert_eq!(
+            providers.chapter_url(&StandardWork::BookOfMormon, "alma", 32, Language::SPANISH),
+            "https://www.churchofjesuschrist.org/study/scriptures/bofm/alma/32?lang=spa"
+        );
+    }
+
+    #[test]
+    fn test_study_helps_keep_their_urls() {
+        let providers = UrlProviders::parse_arg("biblegateway").unwrap();
+        let reference = ScriptureReference {
+            book: "tg".to_string(),
+            chapter: 1,
+            verse_start: 1,
+            verse_end: None,
+            standard_work: StandardWork::StudyHelps,
+            topic: Some("Faith".to_string()),
+        };
+        assert_eq!(
+            providers.url(&reference, Language::PORTUGUESE),
+            "https://www.churchofjesuschrist.org/study/scriptures/tg/faith?lang=por"
+        );
+    }
+
+    #[test]
+    fn test_invalid_specs() {
+        assert!(
+            UrlProviders::parse_arg("bibelgateway")
+                .unwrap_err()
+                .contains("Unknown URL provider 'bibelgateway'")
+        );
+        assert!(
+            UrlProviders::parse_arg("nt=https://example.org/{page}")
+                .unwrap_err()
+                .contains("unknown placeholder '{page}'")
+        );
+        assert!(
+            UrlProviders::parse_arg("apocrypha=church")
+                .unwrap_err()
+                .contains("Unknown standard work 'apocrypha'")
+        );
+    }
+}
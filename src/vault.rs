@@ -0,0 +1,189 @@
+//! Generation of an Obsidian vault skeleton with one note per chapter, so every
+//! generated wikilink has a note to open
+
+use crate::books::book_catalog;
+use crate::language::Language;
+use crate::url_generator::generate_chapter_url_with_lang;
+use crate::works::book_name_in;
+use std::fmt::Write;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Folders the chapter notes are placed in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum VaultLayout {
+    /// Every note at the top of the vault: `Genesis 1.md`
+    Flat,
+    /// A folder per standard work: `Old Testament/Genesis 1.md`
+    #[default]
+    Work,
+    /// A folder per standard work and book: `Old Testament/Genesis/Genesis 1.md`
+    Book,
+}
+
+/// How verses are marked in a chapter note
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum VerseAnchors {
+    /// A `## 1` heading per verse, the target of `[[Genesis 1#1]]`
+    #[default]
+    Heading,
+    /// A paragraph with block ID `^1` per verse, the target of `[[Genesis 1#^1]]`
+    Block,
+}
+
+/// Options for [`chapter_notes`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VaultOptions {
+    pub layout: VaultLayout,
+    pub anchors: VerseAnchors,
+    /// Language of the linked Gospel Library pages
+    pub lang: Language,
+    /// Language of the book names in note names, as for wikilinks
+    pub display_lang: Language,
+}
+
+/// A note to create, relative to the vault directory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VaultNote {
+    pub path: PathBuf,
+    pub content: String,
+}
+
+/// Notes written by [`write_vault`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VaultSummary {
+    /// Notes created or overwritten
+    pub written: usize,
+    /// Existing notes left untouched
+    pub skipped: usize,
+}
+
+/// A note for every chapter of every book with verse data, in canonical order
+///
+/// Notes are named like the plain wikilinks of their chapter (`Genesis 1.md`
+/// for `[[Genesis 1]]`), with a heading or block ID per verse and a link to the
+/// chapter in the Gospel Library.
+///
+/// # Examples
+///
+/// ```
+/// use scripture_links_lib::vault::{VaultOptions, chapter_notes};
+///
+/// let notes = chapter_notes(&VaultOptions::default());
+/// let alma = notes
+///     .iter()
+///     .find(|note| note.path.ends_with("Alma 32.md"))
+///     .unwrap();
+/// assert!(alma.path.starts_with("Book of Mormon"));
+/// assert!(alma.content.contains("\n## 43\n"));
+/// ```
+#[must_use]
+pub fn chapter_notes(options: &VaultOptions) -> Vec<VaultNote> {
+    let mut notes = Vec::new();
+    for book in book_catalog() {
+        let name = book_name_in(book.slug, options.display_lang);
+        let folder = match options.layout {
+            VaultLayout::Flat => PathBuf::new(),
+            VaultLayout::Work => PathBuf::from(book.standard_work.display_name()),
+            VaultLayout::Book => Path::new(book.standard_work.display_name()).join(&name),
+        };
+        for (chapter, &verses) in (1..).zip(&book.verse_counts) {
+            let title = format!("{name} {chapter}");
+            let url = generate_chapter_url_with_lang(
+                &book.standard_work,
+                book.slug,
+                chapter,
+                options.lang,
+            );
+            let mut content = format!("# {title}\n\n[Gospel Library]({url})\n");
+            for verse in 1..=verses {
+                let _ = match options.anchors {
+                    VerseAnchors::Heading => write!(content, "\n## {verse}\n"),
+                    VerseAnchors::Block => write!(content, "\n{verse} ^{verse}\n"),
+                };
+            }
+            notes.push(VaultNote {
+                path: folder.join(format!("{title}.md")),
+                content,
+            });
+        }
+    }
+    notes
+}
+
+/// Write the chapter notes under `dir`, creating folders as needed
+///
+/// Existing notes are kept unless `overwrite` is set, so notes already taken
+/// in them are not lost.
+///
+/// # Errors
+/// Returns an error if a folder or note cannot be written.
+pub fn write_vault(
+    dir: &Path,
+    options: &VaultOptions,
+    overwrite: bool,
+) -> io::Result<VaultSummary> {
+    let mut summary = VaultSummary::default();
+    for note in chapter_notes(options) {
+        let path = dir.join(&note.path);
+        if !overwrite && path.exists() {
+            summary.skipped += 1;
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, note.content)?;
+        summary.written += 1;
+    }
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chapter_notes() {
+        let notes = chapter_notes(&VaultOptions {
+            layout: VaultLayout::Book,
+            anchors: VerseAnchors::Block,
+            lang: Language::SPANISH,
+            display_lang: Language::SPANISH,
+        });
+        assert_eq!(notes.len(), 1582);
+        assert_eq!(
+            notes[0].path,
+            Path::new("Old Testament/Génesis/Génesis 1.md")
+        );
+        assert!(notes[0].content.starts_with(
+            "# Génesis 1\n\n[Gospel Library](https://www.churchofjesuschrist.org/study/scriptures/ot/gen/1?lang=spa)\n\n1 ^1\n"
+        ));
+        assert!(notes[0].content.ends_with("\n31 ^31\n"));
+
+        let flat = chapter_notes(&VaultOptions {
+            layout: VaultLayout::Flat,
+            ..VaultOptions::default()
+        });
+        assert!(flat.iter().any(|note| note.path == Path::new("D&C 138.md")));
+    }
+
+    #[test]
+    fn test_write_vault_keeps_existing_notes() {
+        let dir = tempfile::tempdir().unwrap();
+        let note = dir.path().join("Book of Mormon/Alma 32.md");
+        fs::create_dir_all(note.parent().unwrap()).unwrap();
+        fs::write(&note, "My notes").unwrap();
+
+        let summary = write_vault(dir.path(), &VaultOptions::default(), false).unwrap();
+        assert_eq!(summary.written, 1581);
+        assert_eq!(summary.skipped, 1);
+        assert_eq!(fs::read_to_string(&note).unwrap(), "My notes");
+        assert!(dir.path().join("Old Testament/Genesis 1.md").is_file());
+
+        let summary = write_vault(dir.path(), &VaultOptions::default(), true).unwrap();
+        assert_eq!(summary.written, 1582);
+        assert!(fs::read_to_string(&note).unwrap().starts_with("# Alma 32"));
+    }
+}
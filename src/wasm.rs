@@ -1,7 +1,17 @@
 //! WASM bindings for the scripture links library
 
-use crate::json_output::{SingleReferenceResponse, create_error_response};
-use crate::{generate_url, parse_scripture_reference, process_text_for_scripture_references};
+use crate::aliases::BookAliases;
+use crate::books::supported_formats;
+use crate::html::{HtmlAttributes, HtmlLink};
+use crate::json_output::{
+    SingleReferenceResponse, create_error_response, create_error_response_in,
+};
+use crate::text_processor::{ProcessorOptions, TextProcessor};
+use crate::{
+    Language, OutputFormat, generate_url, generate_url_with_lang, parse_scripture_reference,
+    parse_scripture_reference_with_aliases, process_text_for_scripture_references,
+};
+use std::collections::BTreeMap;
 use wasm_bindgen::prelude::*;
 
 // Enable `console.log` for debugging
@@ -46,15 +56,36 @@ impl ScriptureLinkResult {
 pub fn parse_reference(reference: &str) -> ScriptureLinkResult {
     console_log!("Parsing reference: {}", reference);
 
-    match parse_scripture_reference(reference) {
-        Ok(scripture) => {
-            let url = generate_url(&scripture);
-            ScriptureLinkResult {
-                success: true,
-                result: url,
-                error: None,
-            }
-        }
+    match parse_scripture_reference(reference).map(|scripture| generate_url(&scripture)) {
+        Ok(url) => ScriptureLinkResult {
+            success: true,
+            result: url,
+            error: None,
+        },
+        Err(error) => ScriptureLinkResult {
+            success: false,
+            result: String::new(),
+            error: Some(error),
+        },
+    }
+}
+
+/// Parse a single scripture reference and return the URL in a language of the site
+/// (a three-letter code such as "spa" or "por"), with errors in that language
+#[wasm_bindgen]
+pub fn parse_reference_with_lang(reference: &str, lang: &str) -> ScriptureLinkResult {
+    console_log!("Parsing reference ({}): {}", lang, reference);
+
+    match Language::new(lang).and_then(|lang| {
+        parse_scripture_reference_with_aliases(reference, &BookAliases::default())
+            .map(|scripture| generate_url_with_lang(&scripture, lang))
+            .map_err(|error| error.render(lang))
+    }) {
+        Ok(url) => ScriptureLinkResult {
+            success: true,
+            result: url,
+            error: None,
+        },
         Err(error) => ScriptureLinkResult {
             success: false,
             result: String::new(),
@@ -68,23 +99,38 @@ pub fn parse_reference(reference: &str) -> ScriptureLinkResult {
 pub fn parse_reference_json(reference: &str) -> JsValue {
     console_log!("Parsing reference (JSON): {}", reference);
 
-    let response = match parse_scripture_reference(reference) {
-        Ok(scripture) => {
-            let url = generate_url(&scripture);
-            SingleReferenceResponse {
-                success: true,
-                input: reference.to_string(),
-                parsed: Some(scripture),
-                url: Some(url),
-                error: None,
-            }
-        }
+    let response = reference_response(reference, Language::ENGLISH);
+    serde_wasm_bindgen::to_value(&response).unwrap_or_else(|_| JsValue::NULL)
+}
+
+/// Parse a single scripture reference and return structured JSON response, with the
+/// URL and error message in `lang`
+#[wasm_bindgen]
+pub fn parse_reference_json_with_lang(reference: &str, lang: &str) -> JsValue {
+    console_log!("Parsing reference (JSON, {}): {}", lang, reference);
+
+    let response = match Language::new(lang) {
+        Ok(lang) => reference_response(reference, lang),
         Err(error) => create_error_response(reference, &error),
     };
 
     serde_wasm_bindgen::to_value(&response).unwrap_or_else(|_| JsValue::NULL)
 }
 
+/// The response for a reference linked in `lang`, or for its error in that language
+fn reference_response(reference: &str, lang: Language) -> SingleReferenceResponse {
+    match parse_scripture_reference_with_aliases(reference, &BookAliases::default()) {
+        Ok(scripture) => SingleReferenceResponse {
+            success: true,
+            input: reference.to_string(),
+            url: Some(generate_url_with_lang(&scripture, lang)),
+            parsed: Some(scripture),
+            error: None,
+        },
+        Err(error) => create_error_response_in(reference, &error, lang),
+    }
+}
+
 /// Process text and convert scripture references to markdown links
 #[wasm_bindgen]
 pub fn process_text(text: &str) -> String {
@@ -92,33 +138,83 @@ pub fn process_text(text: &str) -> String {
     process_text_for_scripture_references(text)
 }
 
+/// Process text and convert scripture references to markdown links to pages in `lang`
+#[wasm_bindgen]
+pub fn process_text_with_lang(text: &str, lang: &str) -> ScriptureLinkResult {
+    console_log!("Processing text ({}): {}", lang, text);
+
+    match Language::new(lang) {
+        Ok(lang) => ScriptureLinkResult {
+            success: true,
+            result: TextProcessor::new(ProcessorOptions {
+                lang,
+                ..ProcessorOptions::default()
+            })
+            .process(text),
+            error: None,
+        },
+        Err(error) => ScriptureLinkResult {
+            success: false,
+            result: String::new(),
+            error: Some(error),
+        },
+    }
+}
+
+/// Process text and convert scripture references to HTML links to pages in `lang`
+///
+/// `class` is the class of the links ("scripture-ref" if undefined, none if empty)
+/// and `attributes` an optional object of attribute names and value templates
+/// added to or replacing data-book, data-chapter, data-verses and title.
+#[wasm_bindgen]
+pub fn process_text_html(
+    text: &str,
+    lang: &str,
+    class: Option<String>,
+    attributes: JsValue,
+) -> ScriptureLinkResult {
+    console_log!("Processing text (HTML, {}): {}", lang, text);
+
+    let options = Language::new(lang).and_then(|lang| {
+        let attributes: Option<BTreeMap<String, String>> =
+            serde_wasm_bindgen::from_value(attributes).map_err(|error| error.to_string())?;
+        let attributes = attributes
+            .map(HtmlAttributes::try_from)
+            .transpose()?
+            .unwrap_or_default();
+        let mut html = HtmlLink::default();
+        if let Some(class) = &class {
+            html.set_class(class);
+        }
+        html.extend_attributes(attributes);
+        Ok(ProcessorOptions {
+            format: OutputFormat::Html,
+            lang,
+            html,
+            ..ProcessorOptions::default()
+        })
+    });
+    match options {
+        Ok(options) => ScriptureLinkResult {
+            success: true,
+            result: TextProcessor::new(options).process(text),
+            error: None,
+        },
+        Err(error) => ScriptureLinkResult {
+            success: false,
+            result: String::new(),
+            error: Some(error),
+        },
+    }
+}
+
 /// Get information about supported formats
+///
+/// Returns JSON with the supported standard works, accepted reference styles,
+/// examples, and every book with its aliases and chapter and verse counts.
 #[wasm_bindgen]
 pub fn get_supported_formats() -> String {
-    r#"{
-  "supported_works": [
-    "Old Testament",
-    "New Testament", 
-    "Book of Mormon",
-    "Doctrine and Covenants",
-    "Pearl of Great Price"
-  ],
-  "formats": [
-    "Official abbreviations (e.g., 'Gen.', 'Matt.', '1 Ne.')",
-    "Full book names (e.g., 'Genesis', 'Matthew', '1 Nephi')",
-    "Compact abbreviations (e.g., 'Gen.', 'Matt.')",
-    "Case insensitive",
-    "Optional spacing between book and chapter"
-  ],
-  "examples": [
-    "Genesis 1:1",
-    "Matt. 5:3-4", 
-    "2 Ne. 10:14-15",
-    "D&C 128:22-23",
-    "Moses 1:39"
-  ]
-}"#
-    .to_string()
+    serde_json::to_string_pretty(&supported_formats()).unwrap_or_default()
 }
 
 /// Initialize the WASM module (called when module loads)
@@ -157,4 +253,36 @@ mod tests {
         assert!(result.contains("[Genesis 1:1]("));
         assert!(result.contains("[2 Nephi 10:14]("));
     }
+
+    #[test]
+    fn test_wasm_lang() {
+        let result = parse_reference_with_lang("Alma 32:21", "spa");
+        assert!(result.success());
+        assert!(result.result().contains("?lang=spa&id=p21"));
+
+        let result = process_text_with_lang("See Moroni 10:4", "por");
+        assert!(result.result().contains("/bofm/moro/10?lang=por"));
+
+        assert!(!parse_reference_with_lang("Alma 32:21", "es").success());
+        assert_eq!(
+            parse_reference_with_lang("Alma 64:1", "fra")
+                .error()
+                .unwrap(),
+            "Le chapitre 64 n'existe pas dans Alma. Alma compte 63 chapitres (1-63)"
+        );
+        assert_eq!(
+            parse_reference_with_lang("Alma 32:0", "por")
+                .error()
+                .unwrap(),
+            "O número do versículo deve ser maior que 0"
+        );
+        assert!(!process_text_with_lang("See Moroni 10:4", "es").success());
+    }
+
+    #[test]
+    fn test_wasm_supported_formats_include_books() {
+        let formats: serde_json::Value = serde_json::from_str(&get_supported_formats()).unwrap();
+        assert_eq!(formats["supported_works"][2], "Book of Mormon");
+        assert_eq!(formats["books"][0]["slug"], "gen");
+    }
 }
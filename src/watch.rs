@@ -0,0 +1,218 @@
+//! Watching directory trees and selecting the files to re-process on change
+
+use crate::files::{collect_files, would_collect};
+use notify::RecursiveMode;
+use notify_debouncer_mini::new_debouncer;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+/// How long to wait for writes to settle before processing a changed file
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Decides which changed paths should be processed again
+///
+/// A changed path is processed when it would be picked up by walking the
+/// watched roots with the same include/exclude globs and ignore files, checked
+/// for that path alone rather than by walking the roots again, and when
+/// its content differs from what the tool itself last wrote there. Tracking our
+/// own writes keeps a save from triggering an endless write/notify loop.
+#[derive(Debug)]
+pub struct ChangeFilter {
+    roots: Vec<String>,
+    include: Vec<String>,
+    exclude: Vec<String>,
+    own_writes: HashMap<PathBuf, u64>,
+}
+
+impl ChangeFilter {
+    #[must_use]
+    pub fn new(roots: &[String], include: &[String], exclude: &[String]) -> Self {
+        Self {
+            roots: roots.to_vec(),
+            include: include.to_vec(),
+            exclude: exclude.to_vec(),
+            own_writes: HashMap::new(),
+        }
+    }
+
+    /// All files currently matched under the watched roots
+    ///
+    /// # Errors
+    /// Returns an error if a glob is invalid or a root cannot be read.
+    pub fn matching_files(&self) -> Result<Vec<PathBuf>, String> {
+        collect_files(&self.roots, &self.include, &self.exclude)
+    }
+
+    /// Remember the current content of `path` as written by the tool
+    pub fn record_write(&mut self, path: &Path) {
+        if let (Ok(key), Ok(content)) = (fs::canonicalize(path), fs::read(path)) {
+            self.own_writes.insert(key, content_hash(&content));
+        }
+    }
+
+    /// Select the files among `changed` that need processing, in sorted order
+    ///
+    /// Deleted files, files outside the include globs or ignored, and files
+    /// whose content is exactly what the tool last wrote are skipped.
+    ///
+    /// # Errors
+    /// Returns an error if a glob is invalid.
+    pub fn files_to_process(&mut self, changed: &[PathBuf]) -> Result<Vec<PathBuf>, String> {
+        let roots: Vec<(&str, PathBuf)> = self
+            .roots
+            .iter()
+            .filter_map(|root| Some((root.as_str(), fs::canonicalize(root).ok()?)))
+            .collect();
+
+        let mut seen = HashSet::new();
+        let mut selected = Vec::new();
+        for changed in changed {
+            // Deleted files cannot be resolved and are skipped here
+            let Ok(key) = fs::canonicalize(changed) else {
+                continue;
+            };
+            if !seen.insert(key.clone()) {
+                continue;
+            }
+            let Some(path) = self.watched_path(&roots, &key)? else {
+                continue;
+            };
+            if let Some(written) = self.own_writes.get(&key)
+                && fs::read(&key).is_ok_and(|content| content_hash(&content) == *written)
+            {
+                continue;
+            }
+            self.own_writes.remove(&key);
+            selected.push(path);
+        }
+        selected.sort();
+        Ok(selected)
+    }
+
+    /// The file at canonical path `key` as named when walking the first of
+    /// `roots` that picks it up, if any does
+    fn watched_path(
+        &self,
+        roots: &[(&str, PathBuf)],
+        key: &Path,
+    ) -> Result<Option<PathBuf>, String> {
+        for (root, canonical_root) in roots {
+            let Ok(relative) = key.strip_prefix(canonical_root) else {
+                continue;
+            };
+            let path = if relative.as_os_str().is_empty() {
+                PathBuf::from(root)
+            } else {
+                Path::new(root).join(relative)
+            };
+            if would_collect(root, &path, &self.include, &self.exclude)? {
+                return Ok(Some(path));
+            }
+        }
+        Ok(None)
+    }
+}
+
+fn content_hash(content: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Watch `roots` recursively and call `on_change` with each debounced batch of changed paths
+///
+/// Blocks until the watcher fails or its event channel closes.
+///
+/// # Errors
+/// Returns an error if a root cannot be watched or the watcher reports an error.
+pub fn watch<F>(roots: &[String], debounce: Duration, mut on_change: F) -> Result<(), String>
+where
+    F: FnMut(&[PathBuf]),
+{
+    let (sender, receiver) = mpsc::channel();
+    let mut debouncer = new_debouncer(debounce, sender).map_err(|e| e.to_string())?;
+    for root in roots {
+        debouncer
+            .watcher()
+            .watch(Path::new(root), RecursiveMode::Recursive)
+            .map_err(|e| format!("Cannot watch '{root}': {e}"))?;
+    }
+
+    for result in receiver {
+        let events = result.map_err(|e| e.to_string())?;
+        let paths: Vec<PathBuf> = events.into_iter().map(|event| event.path).collect();
+        on_change(&paths);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter_for(root: &Path) -> ChangeFilter {
+        ChangeFilter::new(&[root.to_string_lossy().to_string()], &[], &[])
+    }
+
+    #[test]
+    fn test_selects_changed_markdown_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::write(root.join("notes.md"), "Alma 5:6").unwrap();
+        fs::write(root.join("other.md"), "Moro 10:4").unwrap();
+        fs::write(root.join("image.png"), "binary").unwrap();
+
+        let mut filter = filter_for(root);
+        let selected = filter
+            .files_to_process(&[root.join("notes.md"), root.join("image.png")])
+            .unwrap();
+        assert_eq!(selected.len(), 1);
+        assert!(selected[0].ends_with("notes.md"));
+    }
+
+    #[test]
+    fn test_skips_deleted_and_ignored_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::write(root.join(".gitignore"), "drafts/\n").unwrap();
+        fs::create_dir(root.join("drafts")).unwrap();
+        fs::write(root.join("drafts/wip.md"), "Alma 5:6").unwrap();
+
+        let mut filter = filter_for(root);
+        let selected = filter
+            .files_to_process(&[root.join("drafts/wip.md"), root.join("gone.md")])
+            .unwrap();
+        assert!(selected.is_empty());
+    }
+
+    #[test]
+    fn test_skips_own_writes_until_content_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let path = root.join("notes.md");
+        fs::write(&path, "[Alma 5:6](url)").unwrap();
+
+        let mut filter = filter_for(root);
+        filter.record_write(&path);
+        assert!(
+            filter
+                .files_to_process(std::slice::from_ref(&path))
+                .unwrap()
+                .is_empty()
+        );
+
+        fs::write(&path, "[Alma 5:6](url) and Moro 10:4").unwrap();
+        assert_eq!(
+            filter
+                .files_to_process(std::slice::from_ref(&path))
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+}
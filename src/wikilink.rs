@@ -0,0 +1,358 @@
+//! Templates for Obsidian wikilinks, so links can point into a vault's own note layout
+
+use crate::abbreviations::study_help_name;
+use crate::types::ScriptureReference;
+use crate::url_generator::topic_to_slug;
+use serde::Deserialize;
+
+/// Placeholders that may appear in a wikilink template
+pub const WIKILINK_PLACEHOLDERS: &[&str] = &[
+    "path",
+    "work",
+    "work_name",
+    "book",
+    "book_name",
+    "chapter",
+    "verse",
+    "end_verse",
+    "verses",
+    "text",
+];
+
+/// Placeholders that may appear in a note path prefix
+pub const PATH_PLACEHOLDERS: &[&str] = &["work", "work_name", "book", "book_name"];
+
+/// Built-in templates selectable by name
+const PRESETS: &[(&str, &str)] = &[
+    ("plain", "[[{path}{book_name} {chapter}]]:{verses}"),
+    ("alias", "[[{path}{book_name} {chapter}|{text}]]"),
+    ("heading", "[[{path}{book_name} {chapter}#{verse}|{text}]]"),
+    ("block", "[[{path}{book_name} {chapter}#^{verse}|{text}]]"),
+];
+
+/// Name of the template used when none is chosen
+pub const DEFAULT_WIKILINK: &str = "plain";
+
+/// Placeholders that may appear in a Study Helps wikilink template
+pub const STUDY_HELPS_PLACEHOLDERS: &[&str] = &[
+    "help",
+    "abbreviation",
+    "help_name",
+    "topic",
+    "topic_slug",
+    "text",
+];
+
+/// Built-in Study Helps templates selectable by name
+const STUDY_HELPS_PRESETS: &[(&str, &str)] = &[
+    ("folder", "[[{help_name}/{topic}|{text}]]"),
+    ("name", "[[{help_name} {topic}|{text}]]"),
+    ("abbreviation", "[[{abbreviation} {topic}]]"),
+];
+
+/// Name of the Study Helps template used when none is chosen
+pub const DEFAULT_STUDY_HELPS_WIKILINK: &str = "folder";
+
+/// How a reference is written as a wikilink
+///
+/// Placeholders are `{path}` (the note path prefix), `{work}` (e.g., "ot"),
+/// `{work_name}` (e.g., "Old Testament"), `{book}` (e.g., "1-sam"), `{book_name}`
+/// (e.g., "1 Samuel", in the display language), `{chapter}`, `{verse}`,
+/// `{end_verse}`, `{verses}` (e.g., "3" or "3-5") and `{text}` (the reference as
+/// written). Presets are "plain" (`[[Genesis 1]]:1`, the default), "alias"
+/// (`[[Genesis 1|Gen. 1:1]]`), "heading" (`[[Genesis 1#1|Gen. 1:1]]`) and "block"
+/// (`[[Genesis 1#^1|Gen. 1:1]]`).
+///
+/// # Examples
+///
+/// ```
+/// use scripture_links_lib::wikilink::WikilinkTemplate;
+/// use scripture_links_lib::parse_scripture_reference;
+///
+/// let template = WikilinkTemplate::new("heading").unwrap();
+/// let reference = parse_scripture_reference("Gen. 1:1").unwrap();
+/// assert_eq!(
+///     template.render(&reference, "Gen. 1:1", "Scriptures/{work_name}/", "Genesis"),
+///     "[[Scriptures/Old Testament/Genesis 1#1|Gen. 1:1]]"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct WikilinkTemplate {
+    template: String,
+}
+
+impl WikilinkTemplate {
+    /// A template by preset name or a template of its own
+    ///
+    /// # Errors
+    /// Returns an error if `spec` is neither a preset nor a template with known placeholders.
+    pub fn new(spec: &str) -> Result<Self, String> {
+        let spec = spec.trim();
+        if let Some((_, template)) = PRESETS.iter().find(|(name, _)| *name == spec) {
+            return Ok(Self {
+                template: (*template).to_string(),
+            });
+        }
+        if !spec.contains("[[") {
+            let presets: Vec<&str> = PRESETS.iter().map(|(name, _)| *name).collect();
+            return Err(format!(
+                "Unknown wikilink style '{spec}'; expected {} or a template like '[[{{book_name}} {{chapter}}|{{text}}]]'",
+                presets.join(", ")
+            ));
+        }
+        validate_placeholders(spec, WIKILINK_PLACEHOLDERS)?;
+        Ok(Self {
+            template: spec.to_string(),
+        })
+    }
+
+    #[must_use]
+    pub fn template(&self) -> &str {
+        &self.template
+    }
+
+    /// Write `scripture` as a wikilink to a note under `path`, with `text` as written
+    /// and `book_name` as the name of its book
+    #[must_use]
+    #[allow(clippy::literal_string_with_formatting_args)]
+    pub fn render(
+        &self,
+        scripture: &ScriptureReference,
+        text: &str,
+        path: &str,
+        book_name: &str,
+    ) -> String {
+        let verses = scripture.verse_end.map_or_else(
+            || scripture.verse_start.to_string(),
+            |end| format!("{}-{end}", scripture.verse_start),
+        );
+        // The path is substituted first so its own placeholders are filled in below
+        self.template
+            .replace("{path}", path)
+            .replace("{work_name}", scripture.standard_work.display_name())
+            .replace("{work}", scripture.standard_work.to_url_path())
+            .replace("{book_name}", book_name)
+            .replace("{book}", &scripture.book)
+            .replace("{chapter}", &scripture.chapter.to_string())
+            .replace("{verse}", &scripture.verse_start.to_string())
+            .replace(
+                "{end_verse}",
+                &scripture
+                    .verse_end
+                    .unwrap_or(scripture.verse_start)
+                    .to_string(),
+            )
+            .replace("{verses}", &verses)
+            .replace("{text}", text)
+    }
+}
+
+impl Default for WikilinkTemplate {
+    fn default() -> Self {
+        Self::new(DEFAULT_WIKILINK).unwrap()
+    }
+}
+
+impl TryFrom<String> for WikilinkTemplate {
+    type Error = String;
+
+    fn try_from(spec: String) -> Result<Self, String> {
+        Self::new(&spec)
+    }
+}
+
+/// How a Study Helps topic is written as a wikilink
+///
+/// Placeholders are `{help}` (e.g., "tg"), `{abbreviation}` (e.g., "TG"),
+/// `{help_name}` (e.g., "Topical Guide"), `{topic}` (e.g., "Faith"), `{topic_slug}`
+/// (e.g., "faith") and `{text}` (the reference as written). Presets are "folder"
+/// (`[[Topical Guide/Faith|TG Faith]]`, the default), "name"
+/// (`[[Topical Guide Faith|TG Faith]]`) and "abbreviation" (`[[TG Faith]]`).
+///
+/// # Examples
+///
+/// ```
+/// use scripture_links_lib::wikilink::StudyHelpsTemplate;
+/// use scripture_links_lib::{ScriptureReference, StandardWork};
+///
+/// let reference = ScriptureReference {
+///     book: "bd".to_string(),
+///     chapter: 1,
+///     verse_start: 1,
+///     verse_end: None,
+///     standard_work: StandardWork::StudyHelps,
+///     topic: Some("Aaronic Priesthood".to_string()),
+/// };
+/// assert_eq!(
+///     StudyHelpsTemplate::default().render(&reference, "BD Aaronic Priesthood"),
+///     "[[Bible Dictionary/Aaronic Priesthood|BD Aaronic Priesthood]]"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct StudyHelpsTemplate {
+    template: String,
+}
+
+impl StudyHelpsTemplate {
+    /// A template by preset name or a template of its own
+    ///
+    /// # Errors
+    /// Returns an error if `spec` is neither a preset nor a template with known placeholders.
+    pub fn new(spec: &str) -> Result<Self, String> {
+        let spec = spec.trim();
+        if let Some((_, template)) = STUDY_HELPS_PRESETS.iter().find(|(name, _)| *name == spec) {
+            return Ok(Self {
+                template: (*template).to_string(),
+            });
+        }
+        if !spec.contains("[[") {
+            let presets: Vec<&str> = STUDY_HELPS_PRESETS.iter().map(|(name, _)| *name).collect();
+            return Err(format!(
+                "Unknown Study Helps wikilink style '{spec}'; expected {} or a template like '[[{{help_name}}/{{topic}}|{{text}}]]'",
+                presets.join(", ")
+            ));
+        }
+        validate_placeholders(spec, STUDY_HELPS_PLACEHOLDERS)?;
+        Ok(Self {
+            template: spec.to_string(),
+        })
+    }
+
+    #[must_use]
+    pub fn template(&self) -> &str {
+        &self.template
+    }
+
+    /// Write the Study Helps topic of `scripture` as a wikilink, with `text` as written
+    #[must_use]
+    #[allow(clippy::literal_string_with_formatting_args)]
+    pub fn render(&self, scripture: &ScriptureReference, text: &str) -> String {
+        let topic = scripture.topic.as_deref().unwrap_or_default();
+        self.template
+            .replace(
+                "{help_name}",
+                study_help_name(&scripture.book).unwrap_or(&scripture.book),
+            )
+            .replace("{help}", &scripture.book)
+            .replace("{abbreviation}", &scripture.book.to_uppercase())
+            .replace("{topic_slug}", &topic_to_slug(topic))
+            .replace("{topic}", topic)
+            .replace("{text}", text)
+    }
+}
+
+impl Default for StudyHelpsTemplate {
+    fn default() -> Self {
+        Self::new(DEFAULT_STUDY_HELPS_WIKILINK).unwrap()
+    }
+}
+
+impl TryFrom<String> for StudyHelpsTemplate {
+    type Error = String;
+
+    fn try_from(spec: String) -> Result<Self, String> {
+        Self::new(&spec)
+    }
+}
+
+/// Check a note path prefix such as `Scriptures/{work_name}/`
+///
+/// # Errors
+/// Returns an error naming the first unknown or unclosed placeholder.
+pub fn validate_path(path: &str) -> Result<(), String> {
+    validate_placeholders(path, PATH_PLACEHOLDERS)
+}
+
+/// Parse a note path prefix from the command line
+///
+/// # Errors
+/// Returns an error if the path uses an unknown placeholder.
+pub fn parse_path(path: &str) -> Result<String, String> {
+    validate_path(path)?;
+    Ok(path.to_string())
+}
+
+pub(crate) fn validate_placeholders(template: &str, known: &[&str]) -> Result<(), String> {
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| format!("unclosed '{{' in '{template}'"))?;
+        let placeholder = &rest[start + 1..start + end];
+        if !known.contains(&placeholder) {
+            return Err(format!(
+                "unknown placeholder '{{{placeholder}}}' in '{template}'; expected one of {}",
+                known
+                    .iter()
+                    .map(|name| format!("{{{name}}}"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+        rest = &rest[start + end + 1..];
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_scripture_reference;
+
+    #[test]
+    fn test_presets() {
+        let reference = parse_scripture_reference("2 Ne. 10:14-15").unwrap();
+        let render = |style: &str| {
+            WikilinkTemplate::new(style).unwrap().render(
+                &reference,
+                "2 Ne. 10:14-15",
+                "",
+                "2 Nephi",
+            )
+        };
+        assert_eq!(render("plain"), "[[2 Nephi 10]]:14-15");
+        assert_eq!(render("alias"), "[[2 Nephi 10|2 Ne. 10:14-15]]");
+        assert_eq!(render("heading"), "[[2 Nephi 10#14|2 Ne. 10:14-15]]");
+        assert_eq!(render("block"), "[[2 Nephi 10#^14|2 Ne. 10:14-15]]");
+        assert_eq!(
+            WikilinkTemplate::default(),
+            WikilinkTemplate::new("plain").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_custom_templates_and_paths() {
+        let reference = parse_scripture_reference("Matt. 5:3").unwrap();
+        let template = WikilinkTemplate::new("[[{path}{book} {chapter}#v{verse}]]").unwrap();
+        assert_eq!(
+            template.render(&reference, "Matt. 5:3", "Bible/{work_name}/", "Matthäus"),
+            "[[Bible/New Testament/matt 5#v3]]"
+        );
+
+        assert!(
+            WikilinkTemplate::new("fancy")
+                .unwrap_err()
+                .contains("plain, alias")
+        );
+        assert!(
+            WikilinkTemplate::new("[[{note}]]")
+                .unwrap_err()
+                .contains("unknown placeholder '{note}'")
+        );
+        assert!(
+            StudyHelpsTemplate::new("[[{help_name}/{chapter}]]")
+                .unwrap_err()
+                .contains("unknown placeholder '{chapter}'")
+        );
+        assert!(
+            StudyHelpsTemplate::new("fancy")
+                .unwrap_err()
+                .contains("folder, name")
+        );
+        assert!(validate_path("Scriptures/{book_name}/").is_ok());
+        assert!(validate_path("Scriptures/{text}/").is_err());
+        assert!(validate_path("Scriptures/{work").is_err());
+    }
+}
@@ -0,0 +1,448 @@
+//! Non-canonical works declared in configuration, such as manuals or Lectures on Faith
+//!
+//! Works given to [`parse_scripture_reference_with_works`](crate::parse_scripture_reference_with_works)
+//! or the text processor (see [`ProcessorOptions::works`](crate::text_processor::ProcessorOptions::works))
+//! are recognized like the standard works. Their references use
+//! [`StandardWork::Custom`](crate::StandardWork::Custom) with the work's id as the book.
+
+use crate::abbreviations::create_abbreviation_map;
+use crate::language::Language;
+use crate::localized_names::{BOOK_NAME_LANGUAGES, localized_display_name, lookup_localized_book};
+use crate::messages::{MessageId, ReferenceError};
+use crate::types::ScriptureReference;
+use crate::url_provider::{render_template, validate_template};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// A work declared in configuration
+///
+/// ```toml
+/// [works.lof]
+/// name = "Lectures on Faith"
+/// abbreviations = ["LoF", "Lect. on Faith"]
+/// url = "https://example.org/lectures-on-faith/{chapter}#p{verse}"
+/// chapters = 7
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct CustomWork {
+    /// Identifier used as the book of its references (the key of its config table)
+    #[serde(skip)]
+    pub id: String,
+    pub name: String,
+    /// Other names recognized in references, in addition to the name
+    #[serde(default)]
+    pub abbreviations: Vec<String>,
+    /// URL template, see [`TemplateUrlProvider`](crate::url_provider::TemplateUrlProvider);
+    /// `{book}` is the work's id and `{book_name}` its name
+    pub url: String,
+    /// Number of chapters, when verse counts are not known
+    pub chapters: Option<u32>,
+    /// Number of verses in each chapter, starting with chapter 1
+    pub verses: Option<Vec<u32>>,
+}
+
+impl CustomWork {
+    /// Check the id, names and URL template against each other and the built-in books
+    ///
+    /// # Errors
+    /// Returns an error describing the first problem found.
+    pub fn validate(&self) -> Result<(), String> {
+        let id = &self.id;
+        if id.is_empty()
+            || !id
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        {
+            return Err(format!(
+                "Invalid work id '{id}': use lowercase letters, digits and dashes"
+            ));
+        }
+
+        let abbreviations = create_abbreviation_map();
+        if abbreviations.values().any(|(book, _)| book == id) {
+            return Err(format!("Work id '{id}' is already used by a built-in book"));
+        }
+        for name in self.names() {
+            let normalized = normalize(name);
+            if normalized.is_empty() || name.contains(':') {
+                return Err(format!("Invalid name '{name}' for work '{id}'"));
+            }
+            if abbreviations.keys().any(|key| normalize(key) == normalized)
+                || lookup_localized_book(name, BOOK_NAME_LANGUAGES).is_some()
+            {
+                return Err(format!(
+                    "Name '{name}' of work '{id}' is already used by a built-in book"
+                ));
+            }
+        }
+
+        validate_template(&self.url).map_err(|e| format!("Invalid URL for work '{id}': {e}"))?;
+
+        if let (Some(chapters), Some(verses)) = (self.chapters, &self.verses)
+            && chapters as usize != verses.len()
+        {
+            return Err(format!(
+                "Work '{id}' has {chapters} chapters but verse counts for {}",
+                verses.len()
+            ));
+        }
+        Ok(())
+    }
+
+    /// The name followed by the abbreviations
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.name.as_str()).chain(self.abbreviations.iter().map(String::as_str))
+    }
+
+    /// Check that the chapter and verses exist, as far as the configured counts tell
+    ///
+    /// # Errors
+    /// Returns an error if a chapter or verse is 0 or beyond the configured counts.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn validate_reference(
+        &self,
+        chapter: u32,
+        verse_start: u32,
+        verse_end: Option<u32>,
+    ) -> Result<(), ReferenceError> {
+        let name = &self.name;
+        if chapter == 0 {
+            return Err(ReferenceError::new(MessageId::ChapterZero, &[]));
+        }
+        if verse_start == 0 {
+            return Err(ReferenceError::new(MessageId::VerseZero, &[]));
+        }
+        if let Some(end_verse) = verse_end
+            && end_verse < verse_start
+        {
+            return Err(ReferenceError::new(
+                MessageId::EndVerseBeforeStart,
+                &[
+                    ("end", &end_verse.to_string()),
+                    ("start", &verse_start.to_string()),
+                ],
+            ));
+        }
+
+        let total_chapters = self
+            .chapters
+            .or_else(|| self.verses.as_ref().map(|verses| verses.len() as u32));
+        if let Some(total_chapters) = total_chapters
+            && chapter > total_chapters
+        {
+            return Err(ReferenceError::new(
+                MessageId::ChapterNotFound,
+                &[
+                    ("chapter", &chapter.to_string()),
+                    ("book", name),
+                    ("total", &total_chapters.to_string()),
+                ],
+            ));
+        }
+        if let Some(&total_verses) = self
+            .verses
+            .as_ref()
+            .and_then(|verses| verses.get(chapter as usize - 1))
+        {
+            let last = verse_end.unwrap_or(verse_start);
+            if last > total_verses {
+                return Err(ReferenceError::new(
+                    MessageId::VerseNotFound,
+                    &[
+                        ("verse", &last.to_string()),
+                        ("book", name),
+                        ("chapter", &chapter.to_string()),
+                        ("total", &total_verses.to_string()),
+                    ],
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Fill in the URL template for a reference to this work
+    #[must_use]
+    pub fn url_for(&self, scripture: &ScriptureReference, lang: Language) -> String {
+        render_template(&self.url, scripture, &self.name, lang)
+    }
+}
+
+/// A set of works whose names and ids do not clash
+///
+/// # Examples
+///
+/// ```
+/// use scripture_links_lib::aliases::BookAliases;
+/// use scripture_links_lib::parse_scripture_reference_with_works;
+/// use scripture_links_lib::works::{CustomWork, CustomWorks};
+///
+/// let works = CustomWorks::new([CustomWork {
+///     id: "gospel-principles".to_string(),
+///     name: "Gospel Principles".to_string(),
+///     abbreviations: vec!["GP".to_string()],
+///     url: "https://example.org/gospel-principles/{chapter}#p{verse}".to_string(),
+///     chapters: Some(47),
+///     verses: None,
+/// }])
+/// .unwrap();
+///
+/// let reference =
+///     parse_scripture_reference_with_works("GP 3:4", &BookAliases::default(), &works).unwrap();
+/// assert_eq!(reference.book, "gospel-principles");
+/// assert_eq!(works.book_name("gospel-principles"), "Gospel Principles");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "BTreeMap<String, CustomWork>")]
+pub struct CustomWorks {
+    works: Vec<CustomWork>,
+}
+
+impl CustomWorks {
+    /// Check and collect `works`; a work replaces an earlier one with the same id
+    ///
+    /// # Errors
+    /// Returns an error if a work is invalid or shares a name with another work.
+    pub fn new<I>(works: I) -> Result<Self, String>
+    where
+        I: IntoIterator<Item = CustomWork>,
+    {
+        let mut collected = Self::default();
+        for work in works {
+            work.validate()?;
+            collected.works.retain(|existing| existing.id != work.id);
+            if let Some(existing) = collected
+                .works
+                .iter()
+                .find(|existing| shares_name(existing, &work))
+            {
+                return Err(format!(
+                    "Works '{}' and '{}' share a name",
+                    existing.id, work.id
+                ));
+            }
+            collected.works.push(work);
+        }
+        Ok(collected)
+    }
+
+    /// Add the works of `other`, replacing existing works with the same id or a shared name
+    pub fn extend(&mut self, other: Self) {
+        for work in other.works {
+            self.works
+                .retain(|existing| existing.id != work.id && !shares_name(existing, &work));
+            self.works.push(work);
+        }
+    }
+
+    /// The work with the given id
+    #[must_use]
+    pub fn get(&self, id: &str) -> Option<&CustomWork> {
+        self.works.iter().find(|work| work.id == id)
+    }
+
+    /// The work with the given name or abbreviation (case-insensitive, trailing period ignored)
+    #[must_use]
+    pub fn lookup(&self, name: &str) -> Option<&CustomWork> {
+        let name = normalize(name);
+        self.works
+            .iter()
+            .find(|work| work.names().any(|known| normalize(known) == name))
+    }
+
+    /// The works, in the order they were added
+    pub fn iter(&self) -> impl Iterator<Item = &CustomWork> {
+        self.works.iter()
+    }
+
+    /// Whether no works are declared
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.works.is_empty()
+    }
+
+    /// Display name of a built-in book or one of the works, falling back to the book slug
+    #[must_use]
+    pub fn book_name(&self, book: &str) -> String {
+        self.book_name_in(book, Language::ENGLISH)
+    }
+
+    /// Display name of a built-in book in `language` (see [`localized_display_name`]),
+    /// or of one of the works, falling back to the book slug
+    #[must_use]
+    pub fn book_name_in(&self, book: &str, language: Language) -> String {
+        localized_display_name(book, language).map_or_else(
+            || {
+                self.get(book)
+                    .map_or_else(|| book.to_string(), |work| work.name.clone())
+            },
+            str::to_string,
+        )
+    }
+}
+
+impl TryFrom<BTreeMap<String, CustomWork>> for CustomWorks {
+    type Error = String;
+
+    /// Works keyed by id, as in the `[works]` table of a config file
+    fn try_from(works: BTreeMap<String, CustomWork>) -> Result<Self, String> {
+        Self::new(
+            works
+                .into_iter()
+                .map(|(id, work)| CustomWork { id, ..work }),
+        )
+    }
+}
+
+/// Display name of a built-in book, falling back to the book slug
+///
+/// Custom works are named by [`CustomWorks::book_name`].
+#[must_use]
+pub fn book_name(book: &str) -> String {
+    book_name_in(book, Language::ENGLISH)
+}
+
+/// Display name of a built-in book in `language` (see [`localized_display_name`]),
+/// falling back to the book slug
+#[must_use]
+pub fn book_name_in(book: &str, language: Language) -> String {
+    localized_display_name(book, language).map_or_else(|| book.to_string(), str::to_string)
+}
+
+fn shares_name(work: &CustomWork, other: &CustomWork) -> bool {
+    work.names().any(|name| {
+        other
+            .names()
+            .any(|other| normalize(name) == normalize(other))
+    })
+}
+
+fn normalize(name: &str) -> String {
+    name.trim().trim_end_matches('.').to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::StandardWork;
+
+    fn work(id: &str, name: &str, abbreviation: &str) -> CustomWork {
+        CustomWork {
+            id: id.to_string(),
+            name: name.to_string(),
+            abbreviations: vec![abbreviation.to_string()],
+            url: "https://example.org/{book}/{chapter}?verses={verses}#p{verse}".to_string(),
+            chapters: None,
+            verses: Some(vec![10, 5]),
+        }
+    }
+
+    #[test]
+    fn test_works_are_found_by_any_name() {
+        let works = CustomWorks::new([work("test-lookup", "Lookup Manual", "LkM.")]).unwrap();
+        assert_eq!(works.lookup("lkm").unwrap().id, "test-lookup");
+        assert_eq!(works.lookup("lookup manual").unwrap().id, "test-lookup");
+        assert!(works.get("test-lookup").is_some());
+        assert!(works.lookup("Unknown Manual").is_none());
+        assert_eq!(works.book_name("test-lookup"), "Lookup Manual");
+        assert_eq!(works.book_name("dc"), "D&C");
+        assert_eq!(
+            works.book_name_in("dc", Language::SPANISH),
+            "Doctrina y Convenios"
+        );
+        assert_eq!(
+            works.book_name_in("test-lookup", Language::SPANISH),
+            "Lookup Manual"
+        );
+        // Without the works only built-in books are named
+        assert_eq!(book_name("test-lookup"), "test-lookup");
+    }
+
+    #[test]
+    fn test_validate_reference_uses_configured_counts() {
+        let work = work("test-counts", "Counts Manual", "CnM");
+        assert!(work.validate_reference(2, 1, Some(5)).is_ok());
+        assert_eq!(
+            work.validate_reference(3, 1, None).unwrap_err().to_string(),
+            "Chapter 3 does not exist in Counts Manual. Counts Manual has 2 chapters (1-2)"
+        );
+        assert!(
+            work.validate_reference(2, 4, Some(6))
+                .unwrap_err()
+                .to_string()
+                .contains("Verse 6 does not exist")
+        );
+
+        // Without counts only zero and reversed ranges are rejected
+        let unbounded = CustomWork {
+            verses: None,
+            ..work
+        };
+        assert!(unbounded.validate_reference(99, 99, None).is_ok());
+        assert!(unbounded.validate_reference(1, 5, Some(4)).is_err());
+    }
+
+    #[test]
+    fn test_url_template() {
+        let work = work("test-url", "Url Manual", "UrM");
+        let reference = ScriptureReference {
+            book: "test-url".to_string(),
+            chapter: 2,
+            verse_start: 3,
+            verse_end: Some(4),
+            standard_work: StandardWork::Custom,
+            topic: None,
+        };
+        assert_eq!(
+            work.url_for(&reference, Language::ENGLISH),
+            "https://example.org/test-url/2?verses=3-4#p3"
+        );
+    }
+
+    #[test]
+    fn test_rejects_invalid_works() {
+        let invalid = |change: fn(&mut CustomWork)| {
+            let mut work = work("test-invalid", "Invalid Manual", "InM");
+            change(&mut work);
+            work.validate().unwrap_err()
+        };
+        assert!(invalid(|work| work.id = "Bad Id".to_string()).contains("Invalid work id"));
+        assert!(invalid(|work| work.id = "alma".to_string()).contains("built-in book"));
+        assert!(invalid(|work| work.abbreviations = vec!["Gen.".to_string()]).contains("built-in"));
+        // Names of books in other languages are taken too
+        assert!(invalid(|work| work.abbreviations = vec!["Juan".to_string()]).contains("built-in"));
+        assert!(
+            invalid(|work| work.url = "https://example.org/{page}".to_string())
+                .contains("unknown placeholder '{page}'")
+        );
+        assert!(invalid(|work| work.chapters = Some(3)).contains("verse counts for 2"));
+
+        assert_eq!(
+            CustomWorks::new([
+                work("test-clash-a", "Clash Manual", "ClM"),
+                work("test-clash-b", "Other Manual", "clm."),
+            ])
+            .unwrap_err(),
+            "Works 'test-clash-a' and 'test-clash-b' share a name"
+        );
+    }
+
+    #[test]
+    fn test_extend_replaces_works_with_the_same_id_or_name() {
+        let mut works = CustomWorks::new([
+            work("test-a", "First Manual", "FiM"),
+            work("test-b", "Second Manual", "SeM"),
+        ])
+        .unwrap();
+        works.extend(
+            CustomWorks::new([
+                work("test-a", "New First Manual", "NFM"),
+                work("test-c", "Third Manual", "SeM"),
+            ])
+            .unwrap(),
+        );
+        let ids: Vec<&str> = works.iter().map(|work| work.id.as_str()).collect();
+        assert_eq!(ids, ["test-a", "test-c"]);
+        assert_eq!(works.book_name("test-a"), "New First Manual");
+    }
+}
@@ -1,3 +1,4 @@
+use std::io::Write;
 use std::process::Command;
 
 #[test]
@@ -52,6 +53,7 @@ fn test_cli_text_processing() {
 fn test_cli_invalid_reference() {
     let output = Command::new("cargo")
         .args(["run", "--", "--reference", "InvalidBook 1:1"])
+        .env("LC_ALL", "C")
         .output()
         .expect("Failed to execute command");
 
@@ -64,6 +66,7 @@ fn test_cli_invalid_reference() {
 fn test_cli_invalid_chapter() {
     let output = Command::new("cargo")
         .args(["run", "--", "--reference", "Genesis 999:1"])
+        .env("LC_ALL", "C")
         .output()
         .expect("Failed to execute command");
 
@@ -76,6 +79,7 @@ fn test_cli_invalid_chapter() {
 fn test_cli_invalid_verse() {
     let output = Command::new("cargo")
         .args(["run", "--", "--reference", "Genesis 1:999"])
+        .env("LC_ALL", "C")
         .output()
         .expect("Failed to execute command");
 
@@ -125,6 +129,7 @@ fn test_cli_json_output() {
 fn test_cli_json_error() {
     let output = Command::new("cargo")
         .args(["run", "--", "--reference", "InvalidBook 1:1", "--json"])
+        .env("LC_ALL", "C")
         .output()
         .expect("Failed to execute command");
 
@@ -394,7 +399,7 @@ fn test_cli_no_arguments() {
 
     assert!(!output.status.success());
     let stderr = String::from_utf8(output.stderr).unwrap();
-    assert!(stderr.contains("Please provide either --reference, --batch, --text, or --file"));
+    assert!(stderr.contains("Please provide a command"));
 }
 
 #[test]
@@ -419,6 +424,7 @@ fn test_cli_validate_only_error_non_json() {
             "InvalidBook 1:1",
             "--validate-only",
         ])
+        .env("LC_ALL", "C")
         .output()
         .expect("Failed to execute command");
 
@@ -479,6 +485,7 @@ fn test_cli_validate_only_error_json() {
             "--validate-only",
             "--json",
         ])
+        .env("LC_ALL", "C")
         .output()
         .expect("Failed to execute command");
 
@@ -497,3 +504,1146 @@ fn test_cli_validate_only_error_json() {
             .contains("Unknown book")
     );
 }
+
+#[test]
+fn test_cli_check_fails_on_unlinked_references() {
+    use std::fs;
+    use tempfile::NamedTempFile;
+
+    let temp = NamedTempFile::new().expect("create temp file");
+    let path = temp.path().to_str().expect("path to str").to_string();
+    let original = "See Alma 5:6 for a verse.";
+    fs::write(&path, original).expect("write");
+
+    let output = Command::new("cargo")
+        .args(["run", "--", "--file", &path, "--check"])
+        .output()
+        .expect("run CLI");
+
+    assert_eq!(output.status.code(), Some(3));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains(&path));
+    // --check must never write the file
+    let content = fs::read_to_string(&path).expect("read back");
+    assert_eq!(content, original);
+}
+
+#[test]
+fn test_cli_check_passes_when_already_linked() {
+    use std::fs;
+    use tempfile::NamedTempFile;
+
+    let temp = NamedTempFile::new().expect("create temp file");
+    let path = temp.path().to_str().expect("path to str").to_string();
+    fs::write(
+        &path,
+        "See [Alma 5:6](https://www.churchofjesuschrist.org/study/scriptures/bofm/alma/5?lang=eng&id=p6#p6).",
+    )
+    .expect("write");
+
+    let output = Command::new("cargo")
+        .args(["run", "--", "--file", &path, "--check", "--json"])
+        .output()
+        .expect("run CLI");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON output");
+    assert_eq!(json["success"], true);
+    assert_eq!(json["files_checked"], 1);
+    assert!(json["files_needing_changes"].as_array().unwrap().is_empty());
+}
+
+#[test]
+fn test_cli_streamed_file_is_unchanged_when_already_linked() {
+    use std::fs;
+    use tempfile::NamedTempFile;
+
+    let temp = NamedTempFile::new().expect("create temp file");
+    let path = temp.path().to_str().expect("path to str").to_string();
+    let original = "See [Alma 5:6](https://www.churchofjesuschrist.org/study/scriptures/bofm/alma/5?lang=eng&id=p6#p6).\n";
+    fs::write(&path, original).expect("write");
+
+    let output = run_cli(&["--no-config", "--file", &path, "--check"]);
+    assert!(output.status.success());
+
+    let output = run_cli(&["--no-config", "--file", &path, "--in-place"]);
+    assert!(output.status.success());
+    assert_eq!(fs::read_to_string(&path).expect("read back"), original);
+
+    fs::write(&path, "See Alma 5:6.\n").expect("write");
+    let output = run_cli(&["--no-config", "--file", &path, "--check"]);
+    assert_eq!(output.status.code(), Some(3));
+}
+
+#[test]
+fn test_cli_check_requires_file() {
+    let output = Command::new("cargo")
+        .args(["run", "--", "--text", "Alma 5:6", "--check"])
+        .output()
+        .expect("run CLI");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("--check can only be used with --file"));
+}
+
+#[test]
+fn test_cli_check_json_reports_unreadable_file() {
+    for args in [
+        &[
+            "--no-config",
+            "--file",
+            "missing-file.md",
+            "--check",
+            "--json",
+        ][..],
+        &["--no-config", "lint", "--json", "missing-file.md"][..],
+    ] {
+        let output = run_cli(args);
+        assert_eq!(output.status.code(), Some(1));
+        let json: serde_json::Value =
+            serde_json::from_slice(&output.stdout).expect("Invalid JSON output");
+        assert_eq!(json["success"], false);
+        assert_eq!(json["error"]["code"], "FILE_READ_ERROR");
+        assert!(
+            json["error"]["message"]
+                .as_str()
+                .unwrap()
+                .contains("missing-file.md")
+        );
+    }
+
+    // Without --check the error is reported in the response only
+    let output = run_cli(&["--no-config", "--file", "missing-file.md", "--json"]);
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["error"]["category"], "FileReadError");
+}
+
+#[test]
+fn test_cli_diff_is_dry_run() {
+    use std::fs;
+    use tempfile::NamedTempFile;
+
+    let temp = NamedTempFile::new().expect("create temp file");
+    let path = temp.path().to_str().expect("path to str").to_string();
+    let original = "Intro\nSee Alma 5:6 for a verse.\n";
+    fs::write(&path, original).expect("write");
+
+    let output = Command::new("cargo")
+        .args(["run", "--", "--file", &path, "--diff"])
+        .output()
+        .expect("run CLI");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains(&format!("--- {path}")));
+    assert!(stdout.contains("@@ -1,2 +1,2 @@"));
+    assert!(stdout.contains("-See Alma 5:6 for a verse."));
+    assert!(stdout.contains("+See [Alma 5:6]("));
+    // Piped output must not be colored
+    assert!(!stdout.contains('\x1b'));
+    assert_eq!(fs::read_to_string(&path).expect("read back"), original);
+}
+
+#[test]
+fn test_cli_diff_json_lists_hunks() {
+    use std::fs;
+    use tempfile::NamedTempFile;
+
+    let temp = NamedTempFile::new().expect("create temp file");
+    let path = temp.path().to_str().expect("path to str").to_string();
+    fs::write(&path, "See Alma 5:6 for a verse.\n").expect("write");
+
+    let output = Command::new("cargo")
+        .args(["run", "--", "--file", &path, "--diff", "--json"])
+        .output()
+        .expect("run CLI");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON output");
+    assert_eq!(json["changed"], true);
+    let hunks = json["hunks"].as_array().unwrap();
+    assert_eq!(hunks.len(), 1);
+    assert_eq!(hunks[0]["old_start"], 1);
+    assert_eq!(hunks[0]["lines"][0]["kind"], "removed");
+    assert_eq!(hunks[0]["lines"][1]["kind"], "added");
+}
+
+#[test]
+fn test_cli_in_place_directory_with_globs() {
+    use std::fs;
+
+    let dir = tempfile::tempdir().expect("create temp dir");
+    let root = dir.path();
+    fs::create_dir_all(root.join("notes")).expect("mkdir");
+    fs::create_dir_all(root.join("drafts")).expect("mkdir");
+    fs::write(root.join("notes/lesson.md"), "See Alma 5:6.").expect("write");
+    fs::write(root.join("drafts/wip.md"), "See Alma 5:7.").expect("write");
+    fs::write(root.join("notes/data.csv"), "Alma 5:8").expect("write");
+    fs::write(root.join(".gitignore"), "ignored.md\n").expect("write");
+    fs::write(root.join("ignored.md"), "See Alma 5:9.").expect("write");
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--",
+            "--file",
+            root.to_str().unwrap(),
+            "--in-place",
+            "--exclude",
+            "drafts/**",
+            "--json",
+        ])
+        .output()
+        .expect("run CLI");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON output");
+    assert_eq!(json["total_files"], 1);
+    assert_eq!(json["changed"], 1);
+    assert_eq!(json["files"][0]["written"], true);
+
+    let read = |p: &str| fs::read_to_string(root.join(p)).expect("read back");
+    assert!(read("notes/lesson.md").contains("[Alma 5:6]("));
+    assert_eq!(read("drafts/wip.md"), "See Alma 5:7.");
+    assert_eq!(read("notes/data.csv"), "Alma 5:8");
+    assert_eq!(read("ignored.md"), "See Alma 5:9.");
+}
+
+#[test]
+fn test_cli_multiple_files_require_write_mode() {
+    let dir = tempfile::tempdir().expect("create temp dir");
+
+    let output = Command::new("cargo")
+        .args(["run", "--", "--file", dir.path().to_str().unwrap()])
+        .output()
+        .expect("run CLI");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("requires --in-place, --check, or --diff"));
+}
+
+#[test]
+fn test_cli_parallel_jobs_keep_file_order() {
+    use std::fs;
+
+    let dir = tempfile::tempdir().expect("create temp dir");
+    for n in 1..=12 {
+        fs::write(
+            dir.path().join(format!("note-{n:02}.md")),
+            format!("Alma {n}:1"),
+        )
+        .expect("write");
+    }
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--",
+            "--file",
+            dir.path().to_str().unwrap(),
+            "--check",
+            "--jobs",
+            "4",
+            "--json",
+        ])
+        .output()
+        .expect("run CLI");
+
+    assert_eq!(output.status.code(), Some(3));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON output");
+    assert_eq!(json["total_files"], 12);
+    assert_eq!(json["references_linked"], 12);
+    let paths: Vec<&str> = json["files"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|f| f["path"].as_str().unwrap())
+        .collect();
+    let mut sorted = paths.clone();
+    sorted.sort_unstable();
+    assert_eq!(paths, sorted);
+}
+
+fn run_with_stdin(args: &[&str], input: &str) -> std::process::Output {
+    use std::process::Stdio;
+
+    let mut child = Command::new("cargo")
+        .args(["run", "--"])
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("run CLI");
+    child
+        .stdin
+        .take()
+        .expect("stdin")
+        .write_all(input.as_bytes())
+        .expect("write stdin");
+    child.wait_with_output().expect("wait for CLI")
+}
+
+#[test]
+fn test_cli_text_from_stdin_is_a_filter() {
+    let output = run_with_stdin(&["--text", "-"], "See Alma 5:6.\nAnd more.\n");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.starts_with("See [Alma 5:6]("));
+    // Exactly the processed input: no extra trailing newline
+    assert!(stdout.ends_with(").\nAnd more.\n"));
+}
+
+#[test]
+fn test_cli_batch_from_stdin_json() {
+    let output = run_with_stdin(
+        &["--batch", "-", "--json"],
+        "Genesis 1:1\nAlma 5:6, Moro 10:4\n\nInvalidRef\n",
+    );
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("Invalid JSON output");
+    assert_eq!(json["total_processed"], 4);
+    assert_eq!(json["successful"], 3);
+    assert_eq!(json["failed"], 1);
+}
+
+#[test]
+fn test_cli_file_from_stdin_rejects_in_place() {
+    let output = run_with_stdin(&["--file", "-", "--in-place"], "Alma 5:6");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("--in-place cannot be used with standard input"));
+}
+
+#[test]
+fn test_cli_exits_cleanly_when_output_pipe_closes() {
+    use std::io::{BufRead, BufReader};
+    use std::process::Stdio;
+
+    let mut child = Command::new("cargo")
+        .args(["run", "--", "--no-config", "--text", "-"])
+        .env("LC_ALL", "C")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("run CLI");
+    let mut stdin = child.stdin.take().expect("stdin");
+    // Far more output than a pipe buffers, so writes fail once the reader is gone
+    let writer = std::thread::spawn(move || {
+        let _ = stdin.write_all("See Alma 5:6.\n".repeat(50_000).as_bytes());
+    });
+
+    // Read a single line and hang up, as `scripture-links books | head -1` does
+    let mut line = String::new();
+    BufReader::new(child.stdout.take().expect("stdout"))
+        .read_line(&mut line)
+        .expect("read first line");
+    assert!(line.starts_with("See [Alma 5:6]("));
+
+    let output = child.wait_with_output().expect("wait for CLI");
+    writer.join().expect("stdin writer");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(output.status.success(), "stderr: {stderr}");
+    assert!(!stderr.contains("panicked"));
+}
+
+#[test]
+fn test_cli_watch_links_saved_files() {
+    use std::process::Stdio;
+    use std::time::{Duration, Instant};
+
+    let dir = tempfile::tempdir().expect("temp dir");
+    let initial = dir.path().join("initial.md");
+    std::fs::write(&initial, "Start with Alma 5:6.\n").expect("write file");
+
+    let mut child = Command::new("cargo")
+        .args(["run", "--", "--watch"])
+        .arg(dir.path())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("run CLI");
+
+    let wait_for = |path: &std::path::Path, expected: &str| {
+        let deadline = Instant::now() + Duration::from_secs(30);
+        while Instant::now() < deadline {
+            if std::fs::read_to_string(path).is_ok_and(|c| c.contains(expected)) {
+                return true;
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+        false
+    };
+
+    // Existing files are linked on startup, then saves are picked up
+    let linked_initial = wait_for(&initial, "[Alma 5:6](");
+    let saved = dir.path().join("saved.md");
+    std::fs::write(&saved, "Later, Moro 10:4.\n").expect("write file");
+    let linked_saved = wait_for(&saved, "[Moro 10:4](");
+    std::thread::sleep(Duration::from_millis(500));
+    let content = std::fs::read_to_string(&saved).expect("read file");
+
+    child.kill().expect("stop watcher");
+    let _ = child.wait();
+
+    assert!(linked_initial);
+    assert!(linked_saved);
+    // The tool's own write must not be processed again
+    assert_eq!(content.matches("](").count(), 1);
+}
+
+fn run_cli(args: &[&str]) -> std::process::Output {
+    Command::new("cargo")
+        .args(["run", "--"])
+        .args(args)
+        .env("LC_ALL", "C")
+        .output()
+        .expect("run CLI")
+}
+
+#[test]
+fn test_cli_parse_command() {
+    let output = run_cli(&["parse", "Alma 5:6"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("bofm/alma/5?lang=eng&id=p6#p6"));
+
+    let output = run_cli(&["--json", "parse", "Alma 5:6", "Moro 10:4"]);
+    assert!(output.status.success());
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Invalid JSON output");
+    assert_eq!(json["total_processed"], 2);
+    assert_eq!(json["successful"], 2);
+}
+
+#[test]
+fn test_cli_validate_command() {
+    let output = run_cli(&["validate", "Gen 51:1", "--json"]);
+    assert!(output.status.success());
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Invalid JSON output");
+    assert_eq!(json["valid"], false);
+    assert_eq!(json["error"]["code"], "INVALID_CHAPTER");
+}
+
+#[test]
+fn test_cli_link_and_lint_commands() {
+    use std::fs;
+
+    let dir = tempfile::tempdir().expect("create temp dir");
+    let path = dir.path().join("notes.md");
+    fs::write(&path, "See Alma 5:6.\n").expect("write");
+    let path = path.to_str().expect("path to str");
+
+    let output = run_cli(&["lint", path]);
+    assert_eq!(output.status.code(), Some(3));
+
+    let output = run_cli(&["link", path, "--in-place", "--format", "wikilink"]);
+    assert!(output.status.success());
+    assert_eq!(
+        fs::read_to_string(path).expect("read back"),
+        "See [[Alma 5]]:6.\n"
+    );
+
+    let output = run_cli(&["lint", path]);
+    assert!(output.status.success());
+}
+
+#[test]
+fn test_cli_link_text_command() {
+    let output = run_cli(&["link", "--text", "Read Moro 10:4 today"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("[Moro 10:4](https://"));
+}
+
+#[test]
+fn test_cli_link_in_place_requires_paths() {
+    let output = run_cli(&["link", "--text", "Alma 5:6", "--in-place"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn test_cli_extract_command() {
+    let output = run_cli(&[
+        "extract",
+        "--text",
+        "See Alma 5:6 and [Moro 10:4](url).",
+        "--json",
+    ]);
+    assert!(output.status.success());
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Invalid JSON output");
+    assert_eq!(json["references_found"], 1);
+    let reference = &json["references"][0];
+    assert_eq!(reference["original_text"], "Alma 5:6");
+    assert_eq!(reference["position"]["start"], 4);
+    assert_eq!(reference["position"]["end"], 12);
+    assert_eq!(reference["parsed"]["book"], "alma");
+}
+
+#[test]
+fn test_cli_books_command() {
+    let output = run_cli(&["books"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Doctrine and Covenants\n"));
+    assert!(stdout.contains("138 chapters  3654 verses  D&C, DC, Doctrine & Covenants"));
+    assert!(stdout.contains("od       no chapter and verse data  OD\n"));
+
+    let output = run_cli(&["books", "--json"]);
+    assert!(output.status.success());
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Invalid JSON output");
+    let alma = json
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|book| book["slug"] == "alma")
+        .unwrap();
+    assert_eq!(alma["chapters"], 63);
+    assert_eq!(alma["standard_work"], "BookOfMormon");
+}
+
+#[test]
+fn test_cli_deprecated_flags_warn() {
+    let output = run_cli(&["--reference", "Alma 5:6"]);
+    assert!(output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("--reference is deprecated; use `scripture-links parse` instead"));
+
+    let output = run_cli(&["--reference", "Alma 5:6", "parse", "Alma 5:6"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn test_cli_info_command() {
+    let output = run_cli(&["info", "Ether 12"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Verses in chapter: 41 (1-41)"));
+    assert!(stdout.contains("Next chapter:      Ether 13"));
+
+    let output = run_cli(&["info", "Alma 63:1-3", "--json"]);
+    assert!(output.status.success());
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Invalid JSON output");
+    assert_eq!(json["info"]["verses_in_range"], 3);
+    assert_eq!(json["info"]["next_chapter"]["book"], "hel");
+
+    let output = run_cli(&["info", "Alma 64"]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_cli_info_uses_url_provider() {
+    let output = run_cli(&[
+        "--no-config",
+        "info",
+        "John 3",
+        "--json",
+        "--url-provider",
+        "biblegateway",
+    ]);
+    assert!(output.status.success());
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Invalid JSON output");
+    assert_eq!(
+        json["info"]["chapter_url"],
+        "https://www.biblegateway.com/passage/?search=John+3:1&version=KJV"
+    );
+    assert_eq!(
+        json["info"]["next_chapter"]["url"],
+        "https://www.biblegateway.com/passage/?search=John+4:1&version=KJV"
+    );
+}
+
+/// Run the CLI with `dir` as the working directory, so config discovery starts there
+fn run_cli_in(dir: &std::path::Path, args: &[&str]) -> std::process::Output {
+    Command::new("cargo")
+        .args(["run", "--quiet", "--manifest-path"])
+        .arg(concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml"))
+        .arg("--")
+        .args(args)
+        .current_dir(dir)
+        .env("LC_ALL", "C")
+        .output()
+        .expect("run CLI")
+}
+
+#[test]
+fn test_cli_project_config() {
+    use std::fs;
+
+    let dir = tempfile::tempdir().unwrap();
+    fs::write(
+        dir.path().join(".scripture-links.toml"),
+        "format = \"wikilink\"\nstudy-helps = true\n",
+    )
+    .unwrap();
+    let nested = dir.path().join("notes");
+    fs::create_dir(&nested).unwrap();
+
+    // Discovered from a parent directory
+    let output = run_cli_in(&nested, &["link", "--text", "Alma 5:6 and TG Faith"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("[[Alma 5]]:6"));
+    assert!(stdout.contains("[[Topical Guide/Faith|TG Faith]]"));
+
+    // Flags override the config
+    let output = run_cli_in(
+        &nested,
+        &[
+            "link",
+            "--text",
+            "Alma 5:6 and TG Faith",
+            "--format",
+            "markdown",
+            "--no-study-helps",
+        ],
+    );
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("[Alma 5:6](https://"));
+    assert!(stdout.contains("and TG Faith"));
+
+    let output = run_cli_in(&nested, &["--no-config", "parse", "Alma 5:6"]);
+    assert!(
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .starts_with("https://")
+    );
+
+    fs::write(dir.path().join(".scripture-links.toml"), "fromat = 1\n").unwrap();
+    let output = run_cli_in(&nested, &["parse", "Alma 5:6"]);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Invalid config file"));
+    assert!(stderr.contains("unknown field `fromat`"));
+}
+
+#[test]
+fn test_cli_config_aliases() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("links.toml");
+    std::fs::write(&config, "[aliases]\n\"D and C\" = \"dc\"\nJSH = \"js-h\"\n").unwrap();
+    let config = config.to_str().unwrap();
+
+    let output = run_cli(&["--config", config, "parse", "JSH 1:17"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("/pgp/js-h/1?lang=eng&id=p17#p17"));
+
+    let output = run_cli(&["--config", config, "link", "--text", "See D and C 4:2."]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("[D and C 4:2](https://"));
+
+    std::fs::write(config, "[aliases]\nJSH = \"joseph\"\n").unwrap();
+    let output = run_cli(&["--config", config, "parse", "JSH 1:17"]);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Alias 'JSH' maps to unknown book 'joseph'"));
+}
+
+#[test]
+fn test_cli_config_custom_works() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("links.toml");
+    std::fs::write(
+        &config,
+        "[works.lof]\nname = \"Lectures on Faith\"\nabbreviations = [\"LoF\"]\n\
+         url = \"https://example.org/lof/{chapter}#p{verse}\"\nchapters = 7\n",
+    )
+    .unwrap();
+    let config = config.to_str().unwrap();
+
+    let output = run_cli(&["--config", config, "parse", "LoF 2:5", "--json"]);
+    assert!(output.status.success());
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Invalid JSON output");
+    assert_eq!(json["url"], "https://example.org/lof/2#p5");
+    assert_eq!(json["parsed"]["book"], "lof");
+    assert_eq!(json["parsed"]["standard_work"], "Custom");
+
+    let output = run_cli(&[
+        "--config",
+        config,
+        "link",
+        "--text",
+        "Lectures on Faith 6:7 and Alma 32:21",
+    ]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("[Lectures on Faith 6:7](https://example.org/lof/6#p7)"));
+    assert!(stdout.contains("[Alma 32:21](https://www.churchofjesuschrist.org/"));
+
+    let output = run_cli(&["--config", config, "validate", "LoF 8:1"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Chapter 8 does not exist in Lectures on Faith"));
+}
+
+#[test]
+fn test_cli_url_providers() {
+    let output = run_cli(&[
+        "--no-config",
+        "link",
+        "--text",
+        "See Matt. 5:3-4 and Alma 5:6",
+        "--url-provider",
+        "bible=biblegateway",
+    ]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains(
+        "[Matt. 5:3-4](https://www.biblegateway.com/passage/?search=Matthew+5:3-4&version=KJV)"
+    ));
+    assert!(stdout.contains("[Alma 5:6](https://www.churchofjesuschrist.org/"));
+
+    let dir = tempfile::tempdir().unwrap();
+    let config = dir.path().join("links.toml");
+    std::fs::write(
+        &config,
+        "[urls]\ndefault = \"https://scriptures.example/{work}/{book}/{chapter}#{verse}\"\n",
+    )
+    .unwrap();
+    let output = run_cli(&["--config", config.to_str().unwrap(), "parse", "D&C 4:2"]);
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap().trim(),
+        "https://scriptures.example/dc-testament/dc/4#2"
+    );
+
+    // The command line overrides the config
+    let output = run_cli(&[
+        "--config",
+        config.to_str().unwrap(),
+        "parse",
+        "D&C 4:2",
+        "--url-provider",
+        "dc=church",
+    ]);
+    assert!(
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .starts_with("https://www.churchofjesuschrist.org/")
+    );
+
+    let output = run_cli(&["parse", "Alma 5:6", "--url-provider", "apocrypha=church"]);
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Unknown standard work 'apocrypha'"));
+}
+
+#[test]
+fn test_cli_lang() {
+    let output = run_cli(&["--no-config", "parse", "Alma 32:21", "--lang", "spa"]);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap().trim(),
+        "https://www.churchofjesuschrist.org/study/scriptures/bofm/alma/32?lang=spa&id=p21#p21"
+    );
+
+    let output = run_cli(&[
+        "--no-config",
+        "link",
+        "--text",
+        "See TG Faith and Moro. 10:4",
+        "--study-helps",
+        "--lang",
+        "por",
+    ]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("/tg/faith?lang=por)"));
+    assert!(stdout.contains("/bofm/moro/10?lang=por&id=p4#p4)"));
+
+    let output = run_cli(&["--no-config", "parse", "Alma 32:21", "--lang", "es"]);
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8(output.stderr)
+            .unwrap()
+            .contains("Invalid language code 'es'")
+    );
+}
+
+#[test]
+fn test_cli_book_names() {
+    let output = run_cli(&[
+        "--no-config",
+        "link",
+        "--text",
+        "Ver Juan 3:16 y Éxodo 20:3",
+        "--book-names",
+        "spa",
+    ]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout
+            .contains("[Juan 3:16](https://www.churchofjesuschrist.org/study/scriptures/nt/john/3")
+    );
+    assert!(
+        stdout
+            .contains("[Éxodo 20:3](https://www.churchofjesuschrist.org/study/scriptures/ot/ex/20")
+    );
+
+    let output = run_cli(&["--no-config", "parse", "1 Néfi 3:7", "--book-names", "por"]);
+    assert!(output.status.success());
+    assert!(
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .contains("/bofm/1-ne/3")
+    );
+
+    let output = run_cli(&["--no-config", "parse", "Juan 3:16", "--book-names", "ita"]);
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8(output.stderr)
+            .unwrap()
+            .contains("Book names are not available in 'ita'")
+    );
+}
+
+#[test]
+fn test_cli_detects_reference_language() {
+    let output = run_cli(&[
+        "--no-config",
+        "--json",
+        "extract",
+        "--text",
+        "John 3:16 y Juan 3:16",
+        "--book-names",
+        "spa",
+    ]);
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let references = json["references"].as_array().unwrap();
+    assert_eq!(references[0]["language"], serde_json::Value::Null);
+    assert!(references[0]["url"].as_str().unwrap().contains("lang=eng"));
+    assert_eq!(references[1]["language"], "spa");
+    assert!(references[1]["url"].as_str().unwrap().contains("lang=spa"));
+
+    let output = run_cli(&[
+        "--no-config",
+        "parse",
+        "Juan 3:16",
+        "--book-names",
+        "spa",
+        "--lang",
+        "eng",
+        "--force-lang",
+    ]);
+    assert!(
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .contains("lang=eng")
+    );
+}
+
+#[test]
+fn test_cli_display_lang() {
+    let output = run_cli(&[
+        "--no-config",
+        "link",
+        "--text",
+        "See Gen. 1:1",
+        "--format",
+        "wikilink",
+        "--display-lang",
+        "spa",
+    ]);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap().trim(),
+        "See [[Génesis 1]]:1"
+    );
+
+    let output = run_cli(&[
+        "--no-config",
+        "link",
+        "--text",
+        "See Matt 5:3",
+        "--canonicalize",
+        "--display-lang",
+        "deu",
+    ]);
+    assert!(
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .starts_with("See [Matthäus 5:3](https://www.churchofjesuschrist.org/")
+    );
+
+    let output = run_cli(&["--no-config", "parse", "Gen 1:1", "--display-lang", "ita"]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_cli_message_lang() {
+    let output = run_cli(&["--no-config", "--message-lang", "spa", "parse", "Gen. 51:1"]);
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap().lines().last(),
+        Some("Error: El capítulo 51 no existe en Génesis. Génesis tiene 50 capítulos (1-50)")
+    );
+
+    let output = run_cli(&[
+        "--no-config",
+        "--message-lang",
+        "fra",
+        "--json",
+        "parse",
+        "Gn 1:1",
+    ]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["error"]["code"], "UNKNOWN_BOOK");
+    assert!(
+        json["error"]["message"]
+            .as_str()
+            .unwrap()
+            .starts_with("Abréviation de livre inconnue : 'Gn'.")
+    );
+
+    // Without the option the language comes from the environment
+    let output = Command::new("cargo")
+        .args(["run", "--", "--no-config", "validate", "Rev. 22:22"])
+        .env("LC_ALL", "de_DE.UTF-8")
+        .output()
+        .expect("run CLI");
+    assert!(
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .contains("Vers 22 gibt es in Offenbarung 22 nicht.")
+    );
+
+    // JSON output stays in English whatever the environment
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--",
+            "--no-config",
+            "--json",
+            "validate",
+            "Rev. 22:22",
+        ])
+        .env("LC_ALL", "de_DE.UTF-8")
+        .output()
+        .expect("run CLI");
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["error"]["code"], "INVALID_VERSE");
+    assert!(
+        json["error"]["message"]
+            .as_str()
+            .unwrap()
+            .starts_with("Verse 22 does not exist in Revelation 22.")
+    );
+
+    assert!(
+        !run_cli(&["--message-lang", "ita", "parse", "Gen. 1:1"])
+            .status
+            .success()
+    );
+}
+
+#[test]
+fn test_cli_wikilink_templates() {
+    let output = run_cli(&[
+        "--no-config",
+        "link",
+        "--text",
+        "See Gen 1:1.",
+        "--format",
+        "wikilink",
+        "--wikilink",
+        "block",
+        "--wikilink-path",
+        "Scriptures/{work_name}/",
+    ]);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap().trim_end(),
+        "See [[Scriptures/Old Testament/Genesis 1#^1|Gen 1:1]]."
+    );
+
+    let output = run_cli(&[
+        "--no-config",
+        "parse",
+        "Moro. 10:4",
+        "--format",
+        "wikilink",
+        "--wikilink",
+        "alias",
+    ]);
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap().trim_end(),
+        "[[Moroni 10|Moro. 10:4]]"
+    );
+
+    assert!(
+        !run_cli(&["--wikilink", "fancy", "parse", "Gen. 1:1"])
+            .status
+            .success()
+    );
+}
+
+#[test]
+fn test_cli_convert_links() {
+    let output = run_cli(&[
+        "--no-config",
+        "link",
+        "--convert",
+        "--format",
+        "wikilink",
+        "--text",
+        "[Alma 32:21](https://www.churchofjesuschrist.org/study/scriptures/bofm/alma/32?lang=eng&id=p21#p21) and [home](https://example.com)",
+    ]);
+    assert!(output.status.success());
+    let converted = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(
+        converted.trim_end(),
+        "[[Alma 32]]:21 and [home](https://example.com)"
+    );
+
+    let output = run_cli(&[
+        "--no-config",
+        "link",
+        "--convert",
+        "--text",
+        converted.trim_end(),
+    ]);
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap().trim_end(),
+        "[Alma 32:21](https://www.churchofjesuschrist.org/study/scriptures/bofm/alma/32?lang=eng&id=p21#p21) and [home](https://example.com)"
+    );
+}
+
+#[test]
+fn test_cli_unlink() {
+    use std::fs;
+
+    let dir = tempfile::tempdir().expect("create temp dir");
+    let note = dir.path().join("lesson.md");
+    fs::write(
+        &note,
+        "Read [Alma 32:21](https://www.churchofjesuschrist.org/study/scriptures/bofm/alma/32?lang=eng&id=p21#p21) \
+         and [[Moroni 10]]:4.\n\n```\n[[Moroni 10]]:5\n```\n",
+    )
+    .expect("write");
+
+    let output = run_cli_in(
+        dir.path(),
+        &["--no-config", "link", "--unlink", "--in-place", "."],
+    );
+    assert!(output.status.success());
+    assert!(
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .contains("1 changed, 0 unchanged, 0 failed, 2 links removed")
+    );
+    assert_eq!(
+        fs::read_to_string(&note).unwrap(),
+        "Read Alma 32:21 and Moroni 10:4.\n\n```\n[[Moroni 10]]:5\n```\n"
+    );
+
+    let output = run_cli(&[
+        "--no-config",
+        "link",
+        "--unlink",
+        "--convert",
+        "--text",
+        "x",
+    ]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_cli_html_format() {
+    let output = run_cli(&[
+        "--no-config",
+        "--html-class",
+        "verse-link",
+        "--html-attribute",
+        "target=_blank",
+        "--html-attribute",
+        "title=",
+        "link",
+        "--format",
+        "html",
+        "--text",
+        "Read Alma 32:21 <today>.",
+    ]);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap().trim_end(),
+        "Read <a href=\"https://www.churchofjesuschrist.org/study/scriptures/bofm/alma/32?lang=eng&amp;id=p21#p21\" \
+         class=\"verse-link\" data-book=\"alma\" data-chapter=\"32\" data-verses=\"21\" target=\"_blank\">Alma 32:21</a> <today>."
+    );
+
+    let output = run_cli(&["--no-config", "parse", "D&C 4:2", "--format", "html"]);
+    assert!(
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .contains("title=\"D&amp;C 4:2\">D&amp;C 4:2</a>")
+    );
+
+    let output = run_cli(&["--no-config", "--html-attribute", "href={url}", "books"]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_cli_json_counts_links_created() {
+    // HTML links contain no brackets, and existing links are not counted
+    let output = run_cli(&[
+        "--no-config",
+        "link",
+        "--format",
+        "html",
+        "--json",
+        "--text",
+        "See Alma 32:21 and Moroni 10:4.",
+    ]);
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["references_found"], 2);
+
+    let output = run_cli(&[
+        "--no-config",
+        "link",
+        "--json",
+        "--text",
+        "Keep [[Alma 32]]:21 and [Moroni 10:4](https://example.com); link Ether 12:6.",
+    ]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["references_found"], 1);
+}
+
+#[test]
+fn test_cli_vault() {
+    let dir = tempfile::tempdir().unwrap();
+    let vault = dir.path().join("vault");
+    let vault_arg = vault.to_str().unwrap();
+
+    let output = run_cli(&["--no-config", "vault", vault_arg, "--anchors", "block"]);
+    assert!(output.status.success());
+    assert!(
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .starts_with("Wrote 1582 notes to ")
+    );
+    let note = std::fs::read_to_string(vault.join("Book of Mormon/Moroni 10.md")).unwrap();
+    assert!(note.starts_with("# Moroni 10\n\n[Gospel Library](https://www.churchofjesuschrist.org/study/scriptures/bofm/moro/10?lang=eng)\n"));
+    assert!(note.contains("\n4 ^4\n"));
+
+    let output = run_cli(&["--no-config", "--json", "vault", vault_arg]);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["written"], 0);
+    assert_eq!(json["skipped"], 1582);
+}